@@ -25,7 +25,8 @@ APP_ROOT = get_app_root()
 # 📁 DATA DIRECTORIES
 # =========================================================
 
-DATA_DIR = os.path.join(APP_ROOT, 'data')
+# The shell overrides this for demo mode (see src-tauri/src/demo.rs).
+DATA_DIR = os.environ.get('SIRI_DATA_DIR') or os.path.join(APP_ROOT, 'data')
 JSON_DIR = os.path.join(DATA_DIR, 'json')
 LOGS_DIR = os.path.join(DATA_DIR, 'logs')
 
@@ -1,3 +1,31 @@
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
 fn main() {
+  embed_sidecar_checksum();
   tauri_build::build()
 }
+
+/// Hash the sidecar binary that will be bundled for this target and expose
+/// it as `SIRI_BACKEND_SHA256`, so the app can detect a corrupted or
+/// tampered backend before spawning it.
+fn embed_sidecar_checksum() {
+  let target = std::env::var("TARGET").unwrap_or_default();
+  let ext = if target.contains("windows") { ".exe" } else { "" };
+  let path = format!("binaries/Siribilling-backend-{}{}", target, ext);
+  println!("cargo:rerun-if-changed={}", path);
+
+  match std::fs::read(Path::new(&path)) {
+    Ok(bytes) => {
+      let hash: String = Sha256::digest(&bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+      println!("cargo:rustc-env=SIRI_BACKEND_SHA256={}", hash);
+    }
+    Err(_) => {
+      println!("cargo:warning=Sidecar {} not found; backend integrity check disabled", path);
+    }
+  }
+}
@@ -0,0 +1,65 @@
+//! Pinning the main window above other windows.
+//!
+//! The preference is stored as `window.always_on_top` and applied at
+//! startup. Kiosk mode forces the window on top regardless: while it is
+//! active `set_always_on_top` only stores the preference, which takes
+//! effect once kiosk mode is exited. `always-on-top-changed` is emitted on
+//! every change so the UI toggle stays in sync.
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::error::AppError;
+use crate::kiosk::KioskState;
+use crate::settings::SettingsState;
+
+#[derive(Clone, Serialize)]
+pub struct AlwaysOnTop {
+    /// The stored preference.
+    pub enabled: bool,
+    /// Whether the window is actually on top.
+    pub effective: bool,
+    /// Kiosk mode is holding the window on top.
+    pub forced_by_kiosk: bool,
+}
+
+fn status(app: &tauri::AppHandle) -> AlwaysOnTop {
+    let enabled = app.state::<SettingsState>().get().window.always_on_top;
+    let kiosk = app.state::<KioskState>().is_active();
+    AlwaysOnTop { enabled, effective: enabled || kiosk, forced_by_kiosk: kiosk }
+}
+
+/// Apply the stored preference to the main window, unless kiosk mode
+/// holds it on top.
+pub fn apply(app: &tauri::AppHandle) {
+    if app.state::<KioskState>().is_active() {
+        return;
+    }
+    let Some(window) = app.get_webview_window("main") else { return };
+    let enabled = app.state::<SettingsState>().get().window.always_on_top;
+    if let Err(e) = window.set_always_on_top(enabled) {
+        warn!("⚠️ Failed to set always-on-top: {}", e);
+    }
+}
+
+#[tauri::command]
+pub fn get_always_on_top(app_handle: tauri::AppHandle) -> AlwaysOnTop {
+    status(&app_handle)
+}
+
+#[tauri::command]
+pub fn set_always_on_top(app_handle: tauri::AppHandle, enabled: bool) -> Result<AlwaysOnTop, AppError> {
+    app_handle
+        .state::<SettingsState>()
+        .update(serde_json::json!({ "window": { "always_on_top": enabled } }))?;
+    apply(&app_handle);
+    let status = status(&app_handle);
+    if status.forced_by_kiosk {
+        info!("📌 Always-on-top preference set to {}; kiosk mode keeps the window on top for now", enabled);
+    } else {
+        info!("📌 Always-on-top {}", if enabled { "on" } else { "off" });
+    }
+    let _ = app_handle.emit("always-on-top-changed", status.clone());
+    Ok(status)
+}
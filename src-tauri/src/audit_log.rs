@@ -0,0 +1,322 @@
+//! Append-only audit logs in the app data dir:
+//!
+//! - `events.jsonl`, business and lifecycle events (`record`);
+//! - `audit.jsonl`, every privileged command run in the shell — resets,
+//!   licenses, PINs, profile, update, settings and backend control — with
+//!   who ran it (OS user and session) and whether it succeeded
+//!   (`privileged`). Read-only commands are never written there, so it
+//!   stays a record of changes.
+//!
+//! One JSON object per line:
+//!
+//! ```text
+//! {"ts":<unix millis>,"tz":"+05:30","session":"<id>","kind":"<event>","data":{..},
+//!  "prev":"<hex>","hash":"<hex>"}
+//! ```
+//!
+//! `audit.jsonl` is always chained. In `events.jsonl`, with
+//! `audit_log.hash_chain` on, each record carries `prev` (the `hash` of
+//! the record before it, or 64 zeros for the first chained record) and
+//! `hash` = SHA-256 of the record serialized without its `hash` field. Any
+//! edit, insertion or deletion breaks the chain at that line, which
+//! `verify_log_chain` reports. With the option off, `prev`/`hash` are
+//! omitted; once chaining has started, an unchained record is itself
+//! reported as a break. `export_audit` copies a time range of
+//! `audit.jsonl` out for compliance.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::Manager;
+
+use crate::error::AppError;
+use crate::paths::AppPaths;
+use crate::settings::SettingsState;
+
+pub const EVENTS_FILE: &str = "events.jsonl";
+pub const AUDIT_FILE: &str = "audit.jsonl";
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Clone, Serialize, Deserialize)]
+struct EventRecord {
+    ts: u64,
+    /// UTC offset of the terminal when the event was written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tz: Option<String>,
+    session: String,
+    kind: String,
+    data: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prev: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+}
+
+impl EventRecord {
+    fn compute_hash(&self) -> String {
+        let unhashed = EventRecord { hash: None, ..self.clone() };
+        let bytes = serde_json::to_vec(&unhashed).unwrap_or_default();
+        Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// Serializes appends and remembers each file's last chain hash.
+#[derive(Default)]
+pub struct AuditLog {
+    /// Missing until the file has been read once.
+    last_hash: Mutex<HashMap<&'static str, Option<String>>>,
+}
+
+fn log_path(app: &tauri::AppHandle, file: &str) -> PathBuf {
+    app.state::<AppPaths>().root.join(file)
+}
+
+/// Hash of the last record in the file, if it is chained.
+fn read_last_hash(path: &Path) -> Option<String> {
+    let text = fs::read_to_string(path).ok()?;
+    let line = text.lines().rev().find(|l| !l.trim().is_empty())?;
+    serde_json::from_str::<EventRecord>(line).ok()?.hash
+}
+
+/// Append an event to `events.jsonl`. Failures are logged, never
+/// returned: auditing must not break the action being audited.
+pub fn record(app: &tauri::AppHandle, kind: &str, data: Value) {
+    let chain = app.state::<SettingsState>().get().audit_log.hash_chain;
+    append(app, EVENTS_FILE, chain, kind, data);
+}
+
+/// Record privileged command `operation` and its outcome in `audit.jsonl`,
+/// passing `result` through.
+pub fn privileged<T>(app: &tauri::AppHandle, operation: &str, result: Result<T, AppError>) -> Result<T, AppError> {
+    let user = std::env::var("USERNAME").or_else(|_| std::env::var("USER")).unwrap_or_default();
+    let data = match &result {
+        Ok(_) => serde_json::json!({ "user": user, "result": "ok" }),
+        Err(e) => serde_json::json!({ "user": user, "result": "error", "code": e.code(), "error": e.message() }),
+    };
+    append(app, AUDIT_FILE, true, operation, data);
+    result
+}
+
+fn append(app: &tauri::AppHandle, file: &'static str, chain: bool, kind: &str, data: Value) {
+    let path = log_path(app, file);
+    let log = app.state::<AuditLog>();
+    let mut hashes = log.last_hash.lock().unwrap();
+    let last_hash = hashes.entry(file).or_insert_with(|| read_last_hash(&path));
+    let previous = last_hash.clone();
+
+    let mut event = EventRecord {
+        ts: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        tz: Some(crate::timestamps::local_offset()),
+        session: crate::session::session_id().to_string(),
+        kind: kind.to_string(),
+        data,
+        prev: None,
+        hash: None,
+    };
+    if chain {
+        event.prev = Some(previous.unwrap_or_else(|| GENESIS_HASH.to_string()));
+        event.hash = Some(event.compute_hash());
+    }
+
+    let result = serde_json::to_string(&event)
+        .map_err(|e| e.to_string())
+        .and_then(|line| {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .map_err(|e| e.to_string())?;
+            writeln!(file, "{}", line).map_err(|e| e.to_string())
+        });
+    match result {
+        Ok(()) => *last_hash = event.hash,
+        Err(e) => warn!("⚠️ Failed to append '{}' to {:?}: {}", kind, path, e),
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct ChainReport {
+    pub records: usize,
+    pub chained: usize,
+    pub intact: bool,
+    /// 1-based line of the first broken link.
+    pub broken_at_line: Option<usize>,
+    pub reason: Option<String>,
+}
+
+/// Walk the chain in `path`, stopping at the first broken link.
+fn verify_chain(path: &Path) -> Result<ChainReport, String> {
+    let mut report = ChainReport { records: 0, chained: 0, intact: true, broken_at_line: None, reason: None };
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(report),
+        Err(e) => return Err(format!("Failed to open {:?}: {}", path, e)),
+    };
+
+    let mut last_hash: Option<String> = None;
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        report.records += 1;
+        let broken = match serde_json::from_str::<EventRecord>(&line) {
+            Err(e) => Some(format!("unreadable record: {}", e)),
+            Ok(event) => match (&event.prev, &event.hash) {
+                (None, None) if last_hash.is_some() => Some("unchained record after chain start".to_string()),
+                (None, None) => None,
+                (Some(prev), Some(hash)) => {
+                    report.chained += 1;
+                    let expected_prev = last_hash.as_deref().unwrap_or(GENESIS_HASH);
+                    if prev != expected_prev {
+                        Some("previous-hash mismatch (record inserted or removed before this line)".to_string())
+                    } else if *hash != event.compute_hash() {
+                        Some("hash mismatch (record edited)".to_string())
+                    } else {
+                        last_hash = Some(hash.clone());
+                        None
+                    }
+                }
+                _ => Some("record has only one of prev/hash".to_string()),
+            },
+        };
+        if let Some(reason) = broken {
+            warn!("⚠️ Audit log chain broken at line {}: {}", i + 1, reason);
+            report.intact = false;
+            report.broken_at_line = Some(i + 1);
+            report.reason = Some(reason);
+            break;
+        }
+    }
+    Ok(report)
+}
+
+/// Walk `events.jsonl`, or `audit.jsonl` when `log` is `"audit"`, and
+/// report the first broken link in the hash chain.
+#[tauri::command]
+pub async fn verify_log_chain(app_handle: tauri::AppHandle, log: Option<String>) -> Result<ChainReport, AppError> {
+    let file = match log.as_deref() {
+        None | Some("events") => EVENTS_FILE,
+        Some("audit") => AUDIT_FILE,
+        Some(other) => {
+            return Err(AppError::Validation(format!("Unknown log '{}' (expected events or audit)", other)))
+        }
+    };
+    tauri::async_runtime::spawn_blocking(move || verify_chain(&log_path(&app_handle, file)))
+        .await
+        .map_err(|e| format!("Log verification failed: {}", e))?
+        .map_err(AppError::Io)
+}
+
+/// Append an event from the frontend (e.g. a sale voided).
+#[tauri::command]
+pub fn record_event(app_handle: tauri::AppHandle, kind: String, data: Option<Value>) -> Result<(), AppError> {
+    if kind.trim().is_empty() {
+        return Err(AppError::Validation("Event kind must not be empty.".to_string()));
+    }
+    record(&app_handle, kind.trim(), data.unwrap_or(Value::Null));
+    Ok(())
+}
+
+/// Copy the `audit.jsonl` records between `from` and `to` (Unix millis,
+/// inclusive) to a file next to exported support bundles. The records are
+/// copied verbatim, so their hashes still check out. Returns its path.
+#[tauri::command]
+pub async fn export_audit(app_handle: tauri::AppHandle, from: u64, to: u64) -> Result<String, AppError> {
+    if from > to {
+        return Err(AppError::Validation("The start of the range must be before its end.".to_string()));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let source = log_path(&app_handle, AUDIT_FILE);
+        let text = match fs::read_to_string(&source) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Failed to read {:?}: {}", source, e)),
+        };
+        let records: Vec<&str> = text
+            .lines()
+            .filter(|line| {
+                serde_json::from_str::<EventRecord>(line).is_ok_and(|event| event.ts >= from && event.ts <= to)
+            })
+            .collect();
+
+        let path = crate::diagnostics::export_dir(&app_handle)
+            .join(format!("audit-{}.jsonl", crate::timestamps::file_stamp()));
+        let mut out = records.join("\n");
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        fs::write(&path, out).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+
+        info!("📤 Exported {} audit record(s) to {:?}", records.len(), path);
+        crate::recent_exports::record(&app_handle, &path, "audit");
+        Ok(path.display().to_string())
+    })
+    .await
+    .map_err(|e| format!("Audit export failed: {}", e))?
+    .map_err(AppError::Io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Three chained records as `append` writes them, one per line.
+    fn chained_lines() -> Vec<String> {
+        let mut prev = GENESIS_HASH.to_string();
+        (0..3)
+            .map(|i| {
+                let mut event = EventRecord {
+                    ts: 1_700_000_000_000 + i,
+                    tz: Some("+05:30".to_string()),
+                    session: "test".to_string(),
+                    kind: "factory_reset".to_string(),
+                    data: serde_json::json!({ "user": "cashier", "result": "ok" }),
+                    prev: Some(prev.clone()),
+                    hash: None,
+                };
+                event.hash = Some(event.compute_hash());
+                prev = event.hash.clone().unwrap();
+                serde_json::to_string(&event).unwrap()
+            })
+            .collect()
+    }
+
+    fn write_log(name: &str, lines: &[String]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("siri-audit-{}-{}.jsonl", name, std::process::id()));
+        fs::write(&path, lines.join("\n") + "\n").unwrap();
+        path
+    }
+
+    #[test]
+    fn verify_chain_accepts_an_untouched_log() {
+        let path = write_log("intact", &chained_lines());
+        let report = verify_chain(&path).unwrap();
+        assert!(report.intact);
+        assert_eq!((report.records, report.chained), (3, 3));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn verify_chain_detects_an_edited_line() {
+        let mut lines = chained_lines();
+        lines[1] = lines[1].replace("\"result\":\"ok\"", "\"result\":\"error\"");
+        let path = write_log("edited", &lines);
+        let report = verify_chain(&path).unwrap();
+        assert!(!report.intact);
+        assert_eq!(report.broken_at_line, Some(2));
+        assert_eq!(report.reason.as_deref(), Some("hash mismatch (record edited)"));
+        let _ = fs::remove_file(path);
+    }
+}
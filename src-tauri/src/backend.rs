@@ -0,0 +1,1634 @@
+//! Backend sidecar process management.
+
+use std::collections::VecDeque;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use log::{debug, error, info, warn};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{Emitter, Manager};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+
+use crate::backend_transport::{BackendRequest, BackendTransport, HttpTransport};
+use crate::error::AppError;
+use crate::event_bus::{self, LifecycleEvent};
+use crate::event_throttle::BACKEND_OUTPUT_TARGET;
+use crate::paths::AppPaths;
+use crate::settings::{SettingsState, StderrRule};
+use crate::splash;
+
+/// Sidecar name as configured in `tauri.conf.json` (`externalBin`).
+pub const SIDECAR_NAME: &str = "Siribilling-backend";
+
+/// SHA-256 of the bundled sidecar, computed by `build.rs`. `None` when the
+/// binary wasn't present at build time (e.g. dev builds on other targets).
+const EXPECTED_SIDECAR_SHA256: Option<&str> = option_env!("SIRI_BACKEND_SHA256");
+
+/// Handle to the running `Siribilling-backend` sidecar, if any.
+pub type ChildHandle = Arc<Mutex<Option<CommandChild>>>;
+
+/// Default cap (bytes) for a single captured backend stdout/stderr line.
+/// Override with `SIRI_BACKEND_LINE_MAX`.
+const DEFAULT_BACKEND_LINE_MAX: usize = 8 * 1024;
+
+/// Port the backend listens on until it announces a different one.
+pub const DEFAULT_BACKEND_PORT: u16 = 8080;
+/// Ports from `DEFAULT_BACKEND_PORT` tried before asking the OS for any.
+const PORT_SCAN_RANGE: u16 = 20;
+/// File in the data dir holding the port last passed to a sidecar, so an
+/// orphan left by a crashed run is found on the next start.
+const LAST_PORT_FILE: &str = "backend-port";
+/// Startup line the backend prints once it is listening, e.g. `LISTENING_ON=54321`.
+const LISTENING_PREFIX: &str = "LISTENING_ON=";
+/// Startup line announcing the backend's own version, e.g. `BACKEND_VERSION=1.4.2`.
+const VERSION_PREFIX: &str = "BACKEND_VERSION=";
+/// How long to wait for the `LISTENING_ON=` handshake before giving up on readiness.
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many backend exits are remembered for `backend_exit_history`.
+const EXIT_HISTORY_CAPACITY: usize = 50;
+
+/// One observed backend exit.
+#[derive(Clone, Debug, Serialize)]
+pub struct TerminationRecord {
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+    /// Unix millis when the exit was observed.
+    pub ts: u64,
+}
+
+/// Managed Tauri state giving commands access to the sidecar.
+pub struct BackendState {
+    pub child: ChildHandle,
+    exit_history: Mutex<VecDeque<TerminationRecord>>,
+    /// Port announced by the backend's startup handshake.
+    port: Mutex<Option<u16>>,
+    /// Port passed to the current sidecar as `SIRI_BACKEND_PORT`.
+    assigned_port: Mutex<Option<u16>>,
+    /// Set once the backend has told us where it is listening.
+    pub ready: AtomicBool,
+    /// Set the first time a backend became ready this session.
+    ever_ready: AtomicBool,
+    /// Version announced by the backend's startup handshake.
+    version: Mutex<Option<String>>,
+    /// How the backend was paused, while it is paused.
+    paused: Mutex<Option<PauseMethod>>,
+    /// When the output reader last received an event (unix millis).
+    last_output: Mutex<Option<u64>>,
+    /// False once the output reader task has died.
+    pub reader_alive: AtomicBool,
+    /// Set while we are stopping the backend on purpose, so its exit isn't
+    /// taken for a crash; cleared by the next start.
+    pub stopping: AtomicBool,
+    /// When the current sidecar was spawned.
+    spawned_at: Mutex<Option<Instant>>,
+    /// Automatic restarts after a crash, this session.
+    pub restarts: AtomicU32,
+    /// Held for the duration of `restart`.
+    restarting: Mutex<()>,
+    /// Set while the backend is failing health checks; see `backend_health.rs`.
+    pub unhealthy: AtomicBool,
+    /// Set by `force_kill_backend` until the killed backend's exit is seen,
+    /// so it isn't reported as a failed start.
+    force_killed: AtomicBool,
+    /// Set when `start_backend` reused a backend this session didn't spawn;
+    /// there is no child to stop, restart or supervise.
+    pub external: AtomicBool,
+}
+
+/// Payload of the `backend-version-mismatch` event.
+#[derive(Clone, Serialize)]
+struct VersionMismatch {
+    app_version: String,
+    backend_version: String,
+}
+
+impl BackendState {
+    pub fn new(child: ChildHandle) -> Self {
+        Self {
+            child,
+            exit_history: Mutex::new(VecDeque::new()),
+            port: Mutex::new(None),
+            assigned_port: Mutex::new(None),
+            ready: AtomicBool::new(false),
+            ever_ready: AtomicBool::new(false),
+            version: Mutex::new(None),
+            paused: Mutex::new(None),
+            last_output: Mutex::new(None),
+            reader_alive: AtomicBool::new(false),
+            stopping: AtomicBool::new(false),
+            spawned_at: Mutex::new(None),
+            restarts: AtomicU32::new(0),
+            restarting: Mutex::new(()),
+            unhealthy: AtomicBool::new(false),
+            force_killed: AtomicBool::new(false),
+            external: AtomicBool::new(false),
+        }
+    }
+
+    /// Discovered port; until the handshake, the one we assigned, or the
+    /// default for a backend we didn't spawn.
+    pub fn port(&self) -> u16 {
+        self.port
+            .lock()
+            .unwrap()
+            .or(*self.assigned_port.lock().unwrap())
+            .unwrap_or(DEFAULT_BACKEND_PORT)
+    }
+
+    pub fn base_url(&self) -> String {
+        format!("http://localhost:{}", self.port())
+    }
+
+    /// Transport for talking to the running backend.
+    pub fn transport(&self) -> Box<dyn BackendTransport> {
+        Box::new(HttpTransport::new(self.base_url()))
+    }
+
+    /// Record the listening port and mark the backend ready.
+    pub fn set_port<R: tauri::Runtime>(&self, app: &tauri::AppHandle<R>, port: u16) {
+        *self.port.lock().unwrap() = Some(port);
+        self.ready.store(true, Ordering::SeqCst);
+        self.ever_ready.store(true, Ordering::SeqCst);
+        info!("🔌 Backend listening on port {}", port);
+        event_bus::publish(app, LifecycleEvent::BackendReady { port });
+        crate::startup_timing::mark(app, "backend_ready");
+        splash::set_status(app, "ready", "Ready");
+        splash::finish(app);
+    }
+
+    /// Whether the backend is paused; a paused backend is not down and must
+    /// not be restarted or treated as unhealthy.
+    pub fn is_paused(&self) -> bool {
+        self.paused.lock().unwrap().is_some()
+    }
+
+    /// Whether `restart` is in progress.
+    pub fn is_restarting(&self) -> bool {
+        self.restarting.try_lock().is_err()
+    }
+
+    pub fn version(&self) -> Option<String> {
+        self.version.lock().unwrap().clone()
+    }
+
+    /// Record the backend's version, warning when its major version differs
+    /// from the app's: the shell and backend API only stay compatible
+    /// within a major version.
+    pub fn set_version<R: tauri::Runtime>(&self, app: &tauri::AppHandle<R>, version: String) {
+        let app_version = app.package_info().version.to_string();
+        info!("🏷️ Backend version {} (app {})", version, app_version);
+        if major_version(&version) != major_version(&app_version) {
+            warn!("⚠️ Backend {} is incompatible with app {}", version, app_version);
+            let _ = app.emit(
+                "backend-version-mismatch",
+                VersionMismatch { app_version, backend_version: version.clone() },
+            );
+        }
+        *self.version.lock().unwrap() = Some(version);
+    }
+
+    pub fn record_exit(&self, code: Option<i32>, signal: Option<i32>) {
+        let mut history = self.exit_history.lock().unwrap();
+        if history.len() == EXIT_HISTORY_CAPACITY {
+            history.pop_front();
+        }
+        history.push_back(TerminationRecord { code, signal, ts: unix_millis() });
+    }
+
+    /// Recorded exits, oldest first.
+    pub fn exit_history(&self) -> Vec<TerminationRecord> {
+        self.exit_history.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn touch_output(&self) {
+        *self.last_output.lock().unwrap() = Some(unix_millis());
+    }
+
+    /// Forget the handshake, e.g. when the process exits.
+    pub fn reset_readiness(&self) {
+        *self.port.lock().unwrap() = None;
+        *self.version.lock().unwrap() = None;
+        *self.paused.lock().unwrap() = None;
+        self.ready.store(false, Ordering::SeqCst);
+        self.unhealthy.store(false, Ordering::SeqCst);
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Parse the backend's `LISTENING_ON=<port>` handshake line.
+pub fn parse_listening_port(line: &str) -> Option<u16> {
+    line.trim().strip_prefix(LISTENING_PREFIX)?.trim().parse().ok()
+}
+
+/// Parse the backend's `BACKEND_VERSION=<version>` handshake line.
+pub fn parse_backend_version(line: &str) -> Option<String> {
+    let version = line.trim().strip_prefix(VERSION_PREFIX)?.trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+fn major_version(version: &str) -> Option<u64> {
+    version.trim_start_matches('v').split('.').next()?.parse().ok()
+}
+
+/// Compile `backend_stderr_rules`; invalid rules (rejected by settings
+/// validation, so only from a hand-edited file) are skipped with a warning.
+fn compile_stderr_rules(rules: &[StderrRule]) -> Vec<(regex::Regex, log::Level)> {
+    rules
+        .iter()
+        .filter_map(|rule| {
+            let level = crate::settings::parse_level(&rule.level).ok()?.to_level();
+            match (regex::Regex::new(&rule.pattern), level) {
+                (Ok(re), Some(level)) => Some((re, level)),
+                _ => {
+                    warn!("⚠️ Ignoring invalid backend stderr rule {:?}", rule);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Level of the first rule matching `line`, if any.
+fn classify_stderr(rules: &[(regex::Regex, log::Level)], line: &str) -> Option<log::Level> {
+    rules.iter().find(|(re, _)| re.is_match(line)).map(|(_, level)| *level)
+}
+
+fn level_marker(level: log::Level) -> &'static str {
+    match level {
+        log::Level::Error => "🔴",
+        log::Level::Warn => "🟡",
+        _ => "🔵",
+    }
+}
+
+/// Max length of a single backend output line, honouring `SIRI_BACKEND_LINE_MAX`.
+fn backend_line_max() -> usize {
+    std::env::var("SIRI_BACKEND_LINE_MAX")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_BACKEND_LINE_MAX)
+}
+
+/// Token bucket capping how many backend lines per second are decoded and
+/// logged. Lines over budget are dropped and reported as one
+/// `(N lines suppressed)` summary once the budget allows output again.
+struct LineRateLimiter {
+    per_sec: f64,
+    tokens: f64,
+    last_refill: Instant,
+    suppressed: u64,
+}
+
+impl LineRateLimiter {
+    /// Allows a burst of one second's worth of lines.
+    fn new(per_sec: u32) -> Self {
+        let per_sec = f64::from(per_sec.max(1));
+        Self { per_sec, tokens: per_sec, last_refill: Instant::now(), suppressed: 0 }
+    }
+
+    fn allow(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_sec).min(self.per_sec);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            self.suppressed += 1;
+            false
+        }
+    }
+
+    /// Number of lines dropped since the last call.
+    fn take_suppressed(&mut self) -> u64 {
+        std::mem::take(&mut self.suppressed)
+    }
+}
+
+/// Buffer a backend line for `get_backend_logs` and the next
+/// `backend-log-batch`.
+fn record_line(app: &tauri::AppHandle, level: log::Level, stream: &'static str, line: &str) {
+    let entry = app.state::<BackendLogBuffer>().push(level, stream, line);
+    crate::event_throttle::queue_log_line(app, entry);
+}
+
+/// Log (and buffer) the summary for lines a limiter dropped, if any.
+fn flush_suppressed(app: &tauri::AppHandle, limiter: &mut LineRateLimiter, stream: &'static str) {
+    let count = limiter.take_suppressed();
+    if count == 0 {
+        return;
+    }
+    let summary = format!("({} lines suppressed)", count);
+    warn!("🟡 [Backend {}] {}", stream, summary);
+    record_line(app, log::Level::Warn, stream, &summary);
+}
+
+/// Decode a captured backend line, cutting it at `max_len` bytes so a giant
+/// JSON blob or stack trace can't balloon the UI process's memory.
+fn truncate_backend_line(line: &[u8], max_len: usize) -> String {
+    if line.len() <= max_len {
+        return String::from_utf8_lossy(line).trim().to_string();
+    }
+    // Only the kept prefix is decoded; a multi-byte char split at the cut
+    // shows up as a single replacement character, which is fine for logs.
+    let kept = String::from_utf8_lossy(&line[..max_len]);
+    format!("{}…(truncated)", kept.trim())
+}
+
+/// Ports a backend may already be listening on: the one assigned this
+/// session, the one recorded by the last run, then the default.
+fn candidate_ports(app: &tauri::AppHandle) -> Vec<u16> {
+    let last_run = std::fs::read_to_string(app.state::<AppPaths>().root.join(LAST_PORT_FILE))
+        .ok()
+        .and_then(|s| s.trim().parse().ok());
+    let assigned = *app.state::<BackendState>().assigned_port.lock().unwrap();
+    let mut ports = Vec::new();
+    for port in [assigned, last_run, Some(DEFAULT_BACKEND_PORT)].into_iter().flatten() {
+        if !ports.contains(&port) {
+            ports.push(port);
+        }
+    }
+    ports
+}
+
+/// The port of a backend already answering the health endpoint, if any.
+pub fn running_backend_port(app: &tauri::AppHandle) -> Option<u16> {
+    candidate_ports(app).into_iter().find(|port| {
+        HttpTransport::new(format!("http://localhost:{}", port))
+            .request(&BackendRequest::get("/api/health", Duration::from_millis(800)))
+            .map(|r| r.is_success())
+            .unwrap_or(false)
+    })
+}
+
+/// Bring the backend up: reuse one that is already healthy, otherwise
+/// validate the bundled binary and spawn it.
+pub fn start_backend(app: &tauri::AppHandle) -> Result<(), String> {
+    // Guard against a second backend process: whether this is a
+    // duplicate launch that slipped past single-instance (e.g. a
+    // rapid double-click race) or a leftover orphan from a prior
+    // crash, spawning another Siribilling-backend.exe on top of a
+    // live one causes two writers to hit the same local JSON files
+    // (bills/products/inventory) with no cross-process locking,
+    // which can corrupt them. So before spawning, check if a
+    // backend is already answering on a port we may have given
+    // it; if so, reuse it instead of starting a duplicate.
+    let already_running = running_backend_port(app);
+    let state = app.state::<BackendState>();
+    state.stopping.store(false, Ordering::SeqCst);
+
+    log_backend_binary_check();
+
+    if let Some(port) = already_running {
+        // A child of ours would have been stopped before we got here, so
+        // whatever answers belongs to another process.
+        warn!(
+            "♻️ A backend not started by this session is already healthy on :{} — reusing it unmanaged, \
+             not spawning a duplicate.",
+            port
+        );
+        state.external.store(true, Ordering::SeqCst);
+        state.set_port(app, port);
+        Ok(())
+    } else if let Err(e) = verify_sidecar_integrity() {
+        error!("❌ Not starting backend: {}", e);
+        let _ = app.emit("backend-binary-corrupt", e);
+        splash::finish(app);
+        Ok(())
+    } else {
+        spawn_sidecar(app).map(|_| ())
+    }
+}
+
+/// The configured `backend_working_dir` if it is an existing directory,
+/// otherwise the app data dir.
+fn backend_working_dir(app: &tauri::AppHandle) -> PathBuf {
+    let default = app.state::<AppPaths>().root.clone();
+    match app.state::<SettingsState>().get().backend_working_dir {
+        Some(dir) if Path::new(&dir).is_dir() => PathBuf::from(dir),
+        Some(dir) => {
+            warn!("⚠️ Configured backend working directory {:?} does not exist; using {:?}", dir, default);
+            default
+        }
+        None => default,
+    }
+}
+
+/// The first free port from `DEFAULT_BACKEND_PORT`, so two builds run side
+/// by side don't collide; any free port if that range is taken.
+fn pick_port() -> u16 {
+    let free = |port| std::net::TcpListener::bind(("127.0.0.1", port)).ok();
+    let scanned = (DEFAULT_BACKEND_PORT..DEFAULT_BACKEND_PORT + PORT_SCAN_RANGE).find(|&p| free(p).is_some());
+    let port = scanned
+        .or_else(|| free(0).and_then(|l| l.local_addr().ok()).map(|a| a.port()))
+        .unwrap_or(DEFAULT_BACKEND_PORT);
+    if port == DEFAULT_BACKEND_PORT {
+        info!("🔌 Backend port: {}", port);
+    } else {
+        warn!("⚠️ Port {} is taken; backend will listen on {}", DEFAULT_BACKEND_PORT, port);
+    }
+    port
+}
+
+/// The data dir the backend is started with: the demo copy in demo mode.
+fn spawn_data_dir(app: &tauri::AppHandle) -> PathBuf {
+    crate::demo::data_dir(app).unwrap_or_else(|| app.state::<AppPaths>().backend_data_dir())
+}
+
+/// Where the backend should listen and keep its data, as `--port` /
+/// `--data-dir` plus `SIRI_BACKEND_PORT` / `SIRI_DATA_DIR` for builds that
+/// read the environment, followed by the safe-mode flags.
+fn base_config(app: &tauri::AppHandle, port: u16) -> (Vec<String>, Vec<(&'static str, String)>) {
+    let mut args = vec!["--port".to_string(), port.to_string()];
+    let mut env = vec![("SIRI_BACKEND_PORT", port.to_string())];
+    let dir = spawn_data_dir(app);
+    args.extend(["--data-dir".to_string(), dir.display().to_string()]);
+    env.push(("SIRI_DATA_DIR", dir.display().to_string()));
+    args.extend(crate::safe_mode::backend_args(app).into_iter().map(String::from));
+    (args, env)
+}
+
+/// Spawn attempts when the sidecar is locked right after install.
+const SPAWN_ATTEMPTS: u32 = 4;
+const SPAWN_RETRY_DELAY: Duration = Duration::from_millis(750);
+
+/// Whether a spawn failure looks like the binary being briefly locked (an
+/// antivirus scan holding it after extraction) rather than missing or
+/// broken, so that retrying can help.
+fn is_transient_spawn_error(e: &tauri_plugin_shell::Error) -> bool {
+    /// `ERROR_ACCESS_DENIED` and `ERROR_SHARING_VIOLATION`.
+    const LOCKED_OS_ERRORS: [i32; 2] = [5, 32];
+    match e {
+        tauri_plugin_shell::Error::Io(io) => {
+            io.kind() == std::io::ErrorKind::PermissionDenied
+                || (cfg!(windows) && io.raw_os_error().is_some_and(|code| LOCKED_OS_ERRORS.contains(&code)))
+        }
+        _ => false,
+    }
+}
+
+/// Forwards one backend's stdout/stderr into the shell log.
+struct OutputReader {
+    app: tauri::AppHandle,
+    stdout_limit: LineRateLimiter,
+    stderr_limit: LineRateLimiter,
+    line_max: usize,
+    stderr_rules: Vec<(regex::Regex, log::Level)>,
+}
+
+impl OutputReader {
+    fn handle(&mut self, event: CommandEvent) {
+        match event {
+            CommandEvent::Stdout(line) => {
+                crate::backend_log_file::write(&self.app, &line);
+                // Handshake lines must never be dropped.
+                let start = line.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(line.len());
+                let trimmed = &line[start..];
+                let handshake = trimmed.starts_with(LISTENING_PREFIX.as_bytes())
+                    || trimmed.starts_with(VERSION_PREFIX.as_bytes());
+                if !handshake && !self.stdout_limit.allow() {
+                    return;
+                }
+                flush_suppressed(&self.app, &mut self.stdout_limit, "stdout");
+                let output = truncate_backend_line(&line, self.line_max);
+                if let Some(port) = parse_listening_port(&output) {
+                    self.app.state::<BackendState>().set_port(&self.app, port);
+                }
+                if let Some(version) = parse_backend_version(&output) {
+                    self.app.state::<BackendState>().set_version(&self.app, version);
+                }
+                info!(target: BACKEND_OUTPUT_TARGET, "🔵 [Backend] {}", output);
+                record_line(&self.app, detect_line_level(&output, log::Level::Info), "stdout", &output);
+            }
+            CommandEvent::Stderr(line) => {
+                crate::backend_log_file::write(&self.app, &line);
+                if !self.stderr_limit.allow() {
+                    return;
+                }
+                flush_suppressed(&self.app, &mut self.stderr_limit, "stderr");
+                let output = truncate_backend_line(&line, self.line_max);
+                match classify_stderr(&self.stderr_rules, &output) {
+                    Some(level) => {
+                        log::log!(
+                            target: BACKEND_OUTPUT_TARGET,
+                            level,
+                            "{} [Backend stderr] {}",
+                            level_marker(level),
+                            output
+                        );
+                        record_line(&self.app, level, "stderr", &output);
+                    }
+                    None => {
+                        error!(target: BACKEND_OUTPUT_TARGET, "🔴 [Backend] {}", output);
+                        record_line(&self.app, detect_line_level(&output, log::Level::Error), "stderr", &output);
+                    }
+                }
+            }
+            CommandEvent::Error(err) => {
+                error!("❌ [Backend] Error: {}", err);
+                startup_failed(&self.app, &err);
+            }
+            CommandEvent::Terminated(payload) => {
+                flush_suppressed(&self.app, &mut self.stdout_limit, "stdout");
+                flush_suppressed(&self.app, &mut self.stderr_limit, "stderr");
+                warn!("⚠️ [Backend] Terminated with code: {:?}", payload.code);
+                let state = self.app.state::<BackendState>();
+                state.record_exit(payload.code, payload.signal);
+                if payload.code != Some(0) || payload.signal.is_some() {
+                    let detail = match payload.signal {
+                        Some(signal) => format!("It was terminated by signal {}.", signal),
+                        None => format!("It exited with code {:?}.", payload.code),
+                    };
+                    startup_failed(&self.app, &detail);
+                }
+                // Snapshotted now, after `startup_failed` may have set it:
+                // by the time subscribers run, a restart can have cleared it.
+                let expected = state.stopping.load(Ordering::SeqCst);
+                event_bus::publish(
+                    &self.app,
+                    LifecycleEvent::BackendExited { code: payload.code, signal: payload.signal, expected },
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Show the fatal-error dialog for a backend that failed before it was ever
+/// ready this session: that's a broken install, not a crash to restart.
+fn startup_failed(app: &tauri::AppHandle, detail: &str) {
+    let state = app.state::<BackendState>();
+    // Checked first so the flag is consumed by the exit it was set for.
+    if state.force_killed.swap(false, Ordering::SeqCst)
+        || state.ever_ready.load(Ordering::SeqCst)
+        || state.stopping.swap(true, Ordering::SeqCst)
+    {
+        return;
+    }
+    splash::finish(app);
+    crate::show_fatal_error(
+        app,
+        &format!("Siri Billing's backend service failed to start.\n\n{}", detail),
+    );
+}
+
+fn panic_message(panic: &Box<dyn std::any::Any + Send>) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+#[derive(Clone, Serialize)]
+struct ReaderTaskFailed {
+    error: String,
+    /// Whether the reader carried on with the next line.
+    recovered: bool,
+}
+
+fn reader_failed(app: &tauri::AppHandle, error: &str, recovered: bool) {
+    if recovered {
+        error!("❌ Backend output reader panicked; continuing with the next line: {}", error);
+    } else {
+        error!("❌ Backend output reader stopped; output is lost until the backend restarts: {}", error);
+        app.state::<BackendState>().reader_alive.store(false, Ordering::SeqCst);
+    }
+    let _ = app.emit("reader-task-failed", ReaderTaskFailed { error: error.to_string(), recovered });
+}
+
+/// Spawn the sidecar, store its handle and start forwarding its output.
+/// A spawn refused because the binary is locked is retried a few times; a
+/// missing binary fails at once. Returns the new PID.
+pub fn spawn_sidecar(app: &tauri::AppHandle) -> Result<u32, String> {
+    // Pin the backend's working directory so its relative paths
+    // resolve the same whether we were started from the
+    // installed shortcut or a terminal.
+    let backend_cwd = backend_working_dir(app);
+    info!("📂 Backend working directory: {:?}", backend_cwd);
+    splash::set_status(app, "spawning", "Starting backend…");
+    let port = pick_port();
+    *app.state::<BackendState>().assigned_port.lock().unwrap() = Some(port);
+    let last_port_file = app.state::<AppPaths>().root.join(LAST_PORT_FILE);
+    if let Err(e) = std::fs::write(&last_port_file, port.to_string()) {
+        warn!("⚠️ Failed to record backend port in {:?}: {}", last_port_file, e);
+    }
+    let (args, base_env) = base_config(app, port);
+    info!("🚀 Backend args: {:?}", args);
+    let mut attempt = 1;
+    let (mut rx, command_child) = loop {
+        let cmd = app.shell().sidecar(SIDECAR_NAME)
+            .map_err(|e| format!("Failed to prepare backend sidecar: {}", e))?
+            .current_dir(&backend_cwd)
+            .args(&args)
+            .envs(base_env.clone())
+            .envs(crate::demo::backend_env(app))
+            .envs(crate::backend_trace::backend_env(app))
+            .envs(crate::backend_dump::backend_env(app));
+        match cmd.spawn() {
+            Ok(spawned) => {
+                if attempt > 1 {
+                    info!("✅ Backend spawned on attempt {}/{}", attempt, SPAWN_ATTEMPTS);
+                }
+                break spawned;
+            }
+            Err(e) if attempt < SPAWN_ATTEMPTS && is_transient_spawn_error(&e) => {
+                warn!(
+                    "⚠️ Backend spawn attempt {}/{} failed: {} — the binary is probably locked by an antivirus scan; retrying in {:?}",
+                    attempt, SPAWN_ATTEMPTS, e, SPAWN_RETRY_DELAY
+                );
+                thread::sleep(SPAWN_RETRY_DELAY);
+                attempt += 1;
+            }
+            Err(e) => {
+                error!("❌ Backend spawn failed after {} attempt(s): {}", attempt, e);
+                return Err(format!("Failed to spawn backend: {}", e));
+            }
+        }
+    };
+    let pid = command_child.pid();
+
+    info!("✅ Backend spawned successfully");
+    info!("🆔 Process ID: {}", pid);
+    splash::set_status(app, "connecting", "Connecting to backend…");
+
+    let state = app.state::<BackendState>();
+    state.reset_readiness();
+    state.external.store(false, Ordering::SeqCst);
+    *state.child.lock().unwrap() = Some(command_child);
+    *state.spawned_at.lock().unwrap() = Some(Instant::now());
+    crate::backend_affinity::apply_on_spawn(app, pid);
+
+    // Fail readiness if the backend never announces its port.
+    let handshake_app = app.clone();
+    thread::spawn(move || {
+        thread::sleep(HANDSHAKE_TIMEOUT);
+        let state = handshake_app.state::<BackendState>();
+        if !state.ready.load(Ordering::SeqCst) {
+            error!(
+                "❌ Backend did not report LISTENING_ON within {:?} — marking it not ready",
+                HANDSHAKE_TIMEOUT
+            );
+            let _ = handshake_app.emit("backend-not-ready", ());
+            splash::finish(&handshake_app);
+        }
+    });
+
+    let settings = app.state::<SettingsState>().get();
+    let rate = settings.backend_log_rate;
+    let mut reader = OutputReader {
+        app: app.clone(),
+        stdout_limit: LineRateLimiter::new(rate.stdout_per_sec),
+        stderr_limit: LineRateLimiter::new(rate.stderr_per_sec),
+        line_max: backend_line_max(),
+        stderr_rules: compile_stderr_rules(&settings.backend_stderr_rules),
+    };
+    app.state::<BackendState>().reader_alive.store(true, Ordering::SeqCst);
+    let events_app = app.clone();
+    let reader_task = tauri::async_runtime::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            reader.app.state::<BackendState>().touch_output();
+            // A panic handling one line must not stop the reader: catch
+            // it, report it and carry on with the next event.
+            let handled = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| reader.handle(event)));
+            if let Err(panic) = handled {
+                reader_failed(&reader.app, &panic_message(&panic), true);
+            }
+        }
+
+        let state = reader.app.state::<BackendState>();
+        if !release_child(&mut *state.child.lock().unwrap(), pid, CommandChild::pid) {
+            // Restarted meanwhile: the state belongs to the new backend now.
+            info!("🛑 Old backend (PID: {}) ended after its replacement started", pid);
+            return;
+        }
+        state.reset_readiness();
+        state.reader_alive.store(false, Ordering::SeqCst);
+        warn!("🛑 Backend sidecar process ended");
+    });
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = reader_task.await {
+            // The channel went with the task; output can't be read again
+            // until the backend is restarted.
+            reader_failed(&events_app, &e.to_string(), false);
+        }
+    });
+
+    event_bus::publish(app, LifecycleEvent::BackendSpawned { pid });
+    crate::startup_timing::mark(app, "sidecar_spawned");
+    Ok(pid)
+}
+
+/// Clear `slot` for the backend `pid` that just ended, unless a newer child
+/// has already replaced it. Returns whether the slot was its to clear.
+fn release_child<T>(slot: &mut Option<T>, pid: u32, pid_of: impl Fn(&T) -> u32) -> bool {
+    match slot.as_ref().map(pid_of) {
+        Some(current) if current != pid => false,
+        _ => {
+            slot.take();
+            true
+        }
+    }
+}
+
+/// Kill the backend on SIGINT/SIGTERM (e.g. Ctrl+C in a dev terminal),
+/// which bypass the window-close and `RunEvent::Exit` cleanup. Taking the
+/// child out of `child` keeps this from double-killing alongside those paths.
+pub fn install_signal_cleanup(child: ChildHandle) {
+    let result = ctrlc::set_handler(move || {
+        warn!("🛑 Termination signal received — cleaning up backend before exit");
+        if let Some(child) = child.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            let pid = child.pid();
+            info!("🔄 Signal cleanup of backend process (PID: {})", pid);
+            log_kill_outcome(&kill_process_tree(pid));
+        }
+        std::process::exit(130);
+    });
+    if let Err(e) = result {
+        warn!("⚠️ Could not install signal handler: {}", e);
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct BackendPing {
+    pub ok: bool,
+    /// The backend is paused on purpose; no request was made.
+    pub paused: bool,
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+// ============================================================================
+// READINESS
+// ============================================================================
+
+/// Where the backend is in its lifecycle: `maintenance`, `stopped`,
+/// `starting` (spawned, no handshake yet) or `ready`.
+pub fn phase(app: &tauri::AppHandle) -> &'static str {
+    let state = app.state::<BackendState>();
+    if crate::maintenance::is_active(app) {
+        "maintenance"
+    } else if state.ready.load(Ordering::SeqCst) {
+        "ready"
+    } else if state.child.lock().unwrap().is_some() {
+        "starting"
+    } else {
+        "stopped"
+    }
+}
+
+fn not_ready(phase: &'static str) -> AppError {
+    let message = match phase {
+        "maintenance" => "The app is in maintenance mode; the backend is not running.",
+        "starting" => "The backend is still starting.",
+        _ => "The backend is not running.",
+    };
+    AppError::BackendNotReady(message.to_string()).with_details(serde_json::json!({ "phase": phase }))
+}
+
+/// Guard for commands that need the backend: fails with
+/// `BackendNotReady` instead of letting the call hit a dead socket.
+pub fn ensure_ready(app: &tauri::AppHandle, command: &str) -> Result<(), AppError> {
+    match phase(app) {
+        "ready" => Ok(()),
+        phase => {
+            debug!("🚧 '{}' called before the backend is ready ({})", command, phase);
+            Err(not_ready(phase))
+        }
+    }
+}
+
+/// Longest a caller may wait in `wait_for_backend`.
+const MAX_READY_WAIT: Duration = Duration::from_secs(120);
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Resolve once the backend is ready, or fail with `BackendNotReady` after
+/// `timeout_ms` (capped at two minutes).
+#[tauri::command]
+pub async fn wait_for_backend(app_handle: tauri::AppHandle, timeout_ms: u64) -> Result<(), AppError> {
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms).min(MAX_READY_WAIT);
+    loop {
+        match phase(&app_handle) {
+            "ready" => return Ok(()),
+            phase if Instant::now() >= deadline => return Err(not_ready(phase)),
+            _ => tokio::time::sleep(READY_POLL_INTERVAL).await,
+        }
+    }
+}
+
+/// One request to the health endpoint over `transport`, timed.
+fn ping(transport: &dyn BackendTransport) -> BackendPing {
+    let started = std::time::Instant::now();
+    let result = transport.request(&BackendRequest::get("/api/health", Duration::from_secs(3)));
+    let latency_ms = started.elapsed().as_millis() as u64;
+    match result {
+        Ok(r) => BackendPing { ok: r.is_success(), paused: false, status: Some(r.status), latency_ms, error: None },
+        Err(e) => BackendPing { ok: false, paused: false, status: None, latency_ms, error: Some(e) },
+    }
+}
+
+/// Round-trip to the backend's health endpoint.
+#[tauri::command]
+pub async fn ping_backend(app_handle: tauri::AppHandle) -> Result<BackendPing, AppError> {
+    ensure_ready(&app_handle, "ping_backend")?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app_handle.state::<BackendState>();
+        if state.is_paused() {
+            return BackendPing { ok: false, paused: true, status: None, latency_ms: 0, error: None };
+        }
+        ping(&*state.transport())
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Backend ping failed: {}", e)))
+}
+
+/// Gracefully stop the backend and start it again. One restart runs at a
+/// time, and a backend that won't stop is never joined by a second one.
+pub fn restart(app: &tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<BackendState>();
+    let Ok(_restarting) = state.restarting.try_lock() else {
+        return Err("A backend restart is already in progress.".to_string());
+    };
+    info!("🔁 Restarting backend");
+    if let Some(Err(e)) = shutdown_backend(&state) {
+        return Err(format!("The old backend did not stop: {}", e));
+    }
+    start_backend(app)
+}
+
+#[derive(Clone, Serialize)]
+struct BackendRestarted {
+    pid: u32,
+}
+
+async fn restart_now(app_handle: tauri::AppHandle) -> Result<u32, AppError> {
+    // `restart` refuses too; checking here reports it as a conflict.
+    if app_handle.state::<BackendState>().is_restarting() {
+        return Err(AppError::Conflict("A backend restart is already in progress.".to_string()));
+    }
+    let app = app_handle.clone();
+    let pid = tauri::async_runtime::spawn_blocking(move || {
+        restart(&app)?;
+        backend_pid(&app.state::<BackendState>()).ok_or_else(|| "The backend did not start.".to_string())
+    })
+    .await
+    .map_err(|e| format!("Restart task failed: {}", e))?
+    .map_err(AppError::Backend)?;
+    let _ = app_handle.emit("backend-restarted", BackendRestarted { pid });
+    Ok(pid)
+}
+
+/// Restart a wedged backend without relaunching the app. Returns the new
+/// backend's PID.
+#[tauri::command]
+pub async fn restart_backend(app_handle: tauri::AppHandle) -> Result<u32, AppError> {
+    let result = restart_now(app_handle.clone()).await;
+    crate::audit_log::privileged(&app_handle, "restart_backend", result)
+}
+
+// ============================================================================
+// PAUSE / RESUME
+// ============================================================================
+
+/// How a paused backend was paused, so it is resumed the same way.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseMethod {
+    /// The backend accepted `POST /api/pause` and stopped taking work.
+    Http,
+    /// The process tree was stopped with `SIGSTOP` (Unix only).
+    Signal,
+}
+
+#[derive(Clone, Serialize)]
+pub struct BackendStatus {
+    pub running: bool,
+    pub ready: bool,
+    /// Running but failing health checks.
+    pub unhealthy: bool,
+    pub paused: Option<PauseMethod>,
+    pub pid: Option<u32>,
+    pub port: u16,
+    pub version: Option<String>,
+    /// When the backend last wrote a line or event (unix millis).
+    pub last_output_ms: Option<u64>,
+    /// Whether its output is still being read.
+    pub reader_alive: bool,
+    /// Seconds since the running sidecar was spawned.
+    pub uptime_secs: Option<u64>,
+    /// Automatic restarts after a crash, this session.
+    pub restart_count: u32,
+    /// The backend in use was already running and isn't a child of this
+    /// session, so `running`, `pid` and `uptime_secs` don't describe it.
+    pub external: bool,
+    /// Exit code of the last backend to terminate, while none is running.
+    pub last_exit_code: Option<i32>,
+}
+
+fn backend_pid(state: &BackendState) -> Option<u32> {
+    state.child.lock().unwrap().as_ref().map(|c| c.pid())
+}
+
+/// Send `signal` to the backend and its descendants.
+#[cfg(unix)]
+fn signal_tree(pid: u32, signal: sysinfo::Signal) -> Result<(), String> {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes();
+    let tree = crate::resource_monitor::process_tree(&sys, sysinfo::Pid::from_u32(pid));
+    for pid in tree {
+        if let Some(process) = sys.process(pid) {
+            if process.kill_with(signal) != Some(true) {
+                return Err(format!("Failed to send {:?} to PID {}", signal, pid));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn control_request(state: &BackendState, path: &str) -> Result<(), String> {
+    let response = state
+        .transport()
+        .request(&BackendRequest::post(path, None, Duration::from_secs(3)))?;
+    if response.is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", response.status))
+    }
+}
+
+fn pause(state: &BackendState) -> Result<PauseMethod, String> {
+    let Some(pid) = backend_pid(state) else {
+        return Err("Backend is not running.".to_string());
+    };
+    let mut paused = state.paused.lock().unwrap();
+    if let Some(method) = *paused {
+        return Ok(method);
+    }
+
+    let method = match control_request(state, "/api/pause") {
+        Ok(()) => PauseMethod::Http,
+        #[cfg(unix)]
+        Err(e) => {
+            info!("⏸️ Backend did not accept /api/pause ({}); stopping PID {} instead", e, pid);
+            signal_tree(pid, sysinfo::Signal::Stop)?;
+            PauseMethod::Signal
+        }
+        #[cfg(not(unix))]
+        Err(e) => return Err(format!("Backend (PID {}) could not be paused: {}", pid, e)),
+    };
+    *paused = Some(method);
+    info!("⏸️ Backend paused ({:?})", method);
+    Ok(method)
+}
+
+fn resume(state: &BackendState) -> Result<(), String> {
+    let mut paused = state.paused.lock().unwrap();
+    let Some(method) = *paused else { return Ok(()) };
+    match method {
+        PauseMethod::Http => control_request(state, "/api/resume")?,
+        #[cfg(unix)]
+        PauseMethod::Signal => {
+            let pid = backend_pid(state).ok_or_else(|| "Backend is not running.".to_string())?;
+            signal_tree(pid, sysinfo::Signal::Continue)?;
+        }
+        #[cfg(not(unix))]
+        PauseMethod::Signal => unreachable!("signal pausing is unix-only"),
+    }
+    *paused = None;
+    info!("▶️ Backend resumed");
+    Ok(())
+}
+
+/// Pause the backend for maintenance, keeping its state. Prefers asking it
+/// over HTTP; on Unix falls back to stopping the process tree.
+#[tauri::command]
+pub async fn pause_backend(app_handle: tauri::AppHandle) -> Result<PauseMethod, AppError> {
+    let app = app_handle.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || pause(&app.state::<BackendState>()))
+        .await
+        .map_err(|e| AppError::from(format!("Pause task failed: {}", e)))
+        .and_then(|paused| paused.map_err(AppError::Backend));
+    crate::audit_log::privileged(&app_handle, "pause_backend", result)
+}
+
+#[tauri::command]
+pub async fn resume_backend(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    let app = app_handle.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || resume(&app.state::<BackendState>()))
+        .await
+        .map_err(|e| AppError::from(format!("Resume task failed: {}", e)))
+        .and_then(|resumed| resumed.map_err(AppError::Backend));
+    crate::audit_log::privileged(&app_handle, "resume_backend", result)
+}
+
+#[tauri::command]
+pub fn backend_status(state: tauri::State<'_, BackendState>) -> BackendStatus {
+    // The child lock is held only to read the pid; window close takes it too.
+    let pid = backend_pid(&state);
+    let uptime_secs = pid.and(*state.spawned_at.lock().unwrap()).map(|at| at.elapsed().as_secs());
+    BackendStatus {
+        running: pid.is_some(),
+        ready: state.ready.load(Ordering::SeqCst),
+        unhealthy: state.unhealthy.load(Ordering::SeqCst),
+        paused: *state.paused.lock().unwrap(),
+        pid,
+        port: state.port(),
+        version: state.version(),
+        last_output_ms: *state.last_output.lock().unwrap(),
+        reader_alive: state.reader_alive.load(Ordering::SeqCst),
+        uptime_secs,
+        restart_count: state.restarts.load(Ordering::SeqCst),
+        external: pid.is_none() && state.external.load(Ordering::SeqCst),
+        last_exit_code: match pid {
+            Some(_) => None,
+            None => state.exit_history.lock().unwrap().back().and_then(|exit| exit.code),
+        },
+    }
+}
+
+/// Port the frontend should use to reach the backend.
+#[tauri::command]
+pub fn get_backend_port(state: tauri::State<'_, BackendState>) -> u16 {
+    state.port()
+}
+
+/// `backend_status` under the name the frontend's status indicator polls.
+#[tauri::command]
+pub fn get_backend_status(state: tauri::State<'_, BackendState>) -> BackendStatus {
+    backend_status(state)
+}
+
+/// `backend_kill_grace_secs`, applied from settings.
+static KILL_GRACE_SECS: AtomicU64 = AtomicU64::new(5);
+
+/// How long the backend waits for its own exit after `/api/shutdown`.
+const SHUTDOWN_WAIT: Duration = Duration::from_secs(5);
+
+pub fn set_kill_grace(secs: u64) {
+    KILL_GRACE_SECS.store(secs, Ordering::Relaxed);
+}
+
+/// How a backend process tree came to stop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KillOutcome {
+    /// It exited on its own within the grace period.
+    Graceful,
+    /// It was still running after the grace period and was killed.
+    Forced,
+}
+
+/// Whether `process` is a backend sidecar. Linux truncates `comm` to 15
+/// bytes, so a prefix of the name counts when the exe can't be read.
+fn is_sidecar(process: &sysinfo::Process) -> bool {
+    if let Some(stem) = process.exe().and_then(|exe| exe.file_stem()) {
+        return stem == SIDECAR_NAME;
+    }
+    let name = process.name().trim_end_matches(".exe");
+    name == SIDECAR_NAME || (name.len() >= 15 && SIDECAR_NAME.starts_with(name))
+}
+
+/// `pid` and everything it spawned. The tree has to be taken before
+/// anything is signalled: once the backend dies its children are
+/// reparented and can no longer be found from it. Empty if `pid` is gone
+/// or, its number having been reused, is no longer our sidecar.
+fn snapshot_tree(pid: u32) -> (sysinfo::System, Vec<sysinfo::Pid>) {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes();
+    let root = sysinfo::Pid::from_u32(pid);
+    let tree = match sys.process(root) {
+        Some(process) if is_sidecar(process) => crate::resource_monitor::process_tree(&sys, root),
+        Some(process) => {
+            warn!("⚠️ PID {} is now {:?}, not the backend; not killing it", pid, process.name());
+            Vec::new()
+        }
+        None => Vec::new(),
+    };
+    (sys, tree)
+}
+
+/// Processes of `tree` still running; zombies waiting to be reaped count
+/// as gone.
+fn still_running(sys: &mut sysinfo::System, tree: &[sysinfo::Pid]) -> Vec<sysinfo::Pid> {
+    sys.refresh_processes();
+    tree.iter()
+        .copied()
+        .filter(|pid| sys.process(*pid).is_some_and(|p| p.status() != sysinfo::ProcessStatus::Zombie))
+        .collect()
+}
+
+/// Wait up to `timeout` for all of `tree` to exit.
+fn wait_for_tree(sys: &mut sysinfo::System, tree: &[sysinfo::Pid], timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if still_running(sys, tree).is_empty() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// SIGTERM `tree`, give it `backend_kill_grace_secs`, then kill what's
+/// left. On Windows it is killed at once. Errors only if processes survive
+/// even that.
+fn stop_tree(pid: u32, sys: &mut sysinfo::System, tree: &[sysinfo::Pid]) -> Result<KillOutcome, String> {
+    if tree.is_empty() {
+        return Ok(KillOutcome::Graceful);
+    }
+    // Windows has nothing like SIGTERM for the sidecar: `taskkill` without
+    // /F only posts WM_CLOSE, and the sidecar has no window, while
+    // CTRL_BREAK needs a console shared with a process group of its own,
+    // which the shell plugin doesn't spawn it in. Waiting would only delay
+    // the kill; `/api/shutdown` is its one graceful path.
+    if cfg!(target_os = "windows") {
+        warn!("⚠️ Killing backend PID {} and its children; Windows has no way to ask them to exit", pid);
+        return kill_now(sys, tree).map(|()| KillOutcome::Forced);
+    }
+    for pid in tree {
+        if let Some(process) = sys.process(*pid) {
+            process.kill_with(sysinfo::Signal::Term);
+        }
+    }
+
+    let grace = Duration::from_secs(KILL_GRACE_SECS.load(Ordering::Relaxed));
+    if wait_for_tree(sys, tree, grace) {
+        return Ok(KillOutcome::Graceful);
+    }
+
+    let survivors = still_running(sys, tree);
+    warn!(
+        "⚠️ {} process(es) of backend PID {} still running after {:?}; killing them",
+        survivors.len(),
+        pid,
+        grace
+    );
+    kill_now(sys, &survivors).map(|()| KillOutcome::Forced)
+}
+
+/// SIGKILL / `TerminateProcess` every process of `tree`.
+fn kill_now(sys: &mut sysinfo::System, tree: &[sysinfo::Pid]) -> Result<(), String> {
+    for pid in tree {
+        if let Some(process) = sys.process(*pid) {
+            process.kill();
+        }
+    }
+    if wait_for_tree(sys, tree, Duration::from_secs(2)) {
+        Ok(())
+    } else {
+        let left = still_running(sys, tree);
+        Err(format!("{} backend process(es) still running after being killed: {:?}", left.len(), left))
+    }
+}
+
+/// Stop `pid` and everything it spawned: SIGTERM first, and a forced kill
+/// only after `backend_kill_grace_secs`, so the backend gets to finish its
+/// writes. On Windows this is always a forced kill; see `stop_tree`.
+pub fn kill_process_tree(pid: u32) -> Result<KillOutcome, String> {
+    let (mut sys, tree) = snapshot_tree(pid);
+    stop_tree(pid, &mut sys, &tree)
+}
+
+/// Log which way a stop went, for the shutdown paths.
+pub fn log_kill_outcome(result: &Result<KillOutcome, String>) {
+    match result {
+        Ok(KillOutcome::Graceful) => info!("✅ Backend shut down gracefully"),
+        Ok(KillOutcome::Forced) => warn!("⚠️ Backend did not exit in time and was force-killed"),
+        Err(e) => error!("❌ Failed to stop backend: {}", e),
+    }
+}
+
+/// Ask the backend to shut down over HTTP and give it `SHUTDOWN_WAIT` to
+/// flush; if it's still running, stop the tree as `kill_process_tree` does,
+/// which on Unix adds up to `backend_kill_grace_secs` after SIGTERM and on
+/// Windows kills it at once. `None` if no backend was running.
+#[must_use]
+pub fn shutdown_backend(state: &BackendState) -> Option<Result<KillOutcome, String>> {
+    state.stopping.store(true, Ordering::SeqCst);
+    // A paused backend can't answer the shutdown request or flush its data.
+    if state.is_paused() {
+        if let Err(e) = resume(state) {
+            warn!("⚠️ Failed to resume paused backend before shutdown: {}", e);
+        }
+    }
+
+    let pid = state.child.lock().unwrap().as_ref().map(|child| child.pid())?;
+    let (mut sys, tree) = snapshot_tree(pid);
+    info!("📡 Sending graceful shutdown request to backend PID: {}", pid);
+    let shutdown = BackendRequest::post(
+        "/api/shutdown",
+        Some(serde_json::json!({ "reason": "shutdown from tauri" })),
+        Duration::from_secs(5),
+    );
+    match state.transport().request(&shutdown) {
+        Ok(response) => {
+            info!("✅ Backend shutdown signal sent: HTTP {}", response.status);
+        }
+        Err(e) => {
+            warn!("⚠️ Failed to send shutdown signal: {}", e);
+        }
+    }
+
+    info!("⏳ Waiting up to {:?} for backend graceful shutdown...", SHUTDOWN_WAIT);
+    let result = if wait_for_tree(&mut sys, &tree, SHUTDOWN_WAIT) {
+        Ok(KillOutcome::Graceful)
+    } else {
+        info!("🔄 Backend still running; terminating process tree (PID: {})", pid);
+        stop_tree(pid, &mut sys, &tree)
+    };
+    state.child.lock().unwrap().take();
+    Some(result)
+}
+
+/// `shutdown_backend` for callers that must not carry on while the old
+/// backend may still be running.
+pub fn stop_backend(state: &BackendState) -> Result<(), String> {
+    match shutdown_backend(state) {
+        Some(Err(e)) => Err(format!("The backend did not stop: {}", e)),
+        _ => Ok(()),
+    }
+}
+
+fn force_kill(state: tauri::State<'_, BackendState>) -> Result<u32, AppError> {
+    // `stopping` stays clear: the kill is seen as a crash and respawned.
+    let child = state.child.lock().unwrap().take();
+    match child {
+        Some(child) => {
+            let pid = child.pid();
+            warn!("💥 Force-kill of backend (PID: {}) explicitly requested — bypassing graceful shutdown", pid);
+            state.force_killed.store(true, Ordering::SeqCst);
+            let (mut sys, tree) = snapshot_tree(pid);
+            kill_now(&mut sys, &tree).map_err(AppError::Backend)?;
+            info!("✅ Backend force-killed (PID: {})", pid);
+            Ok(pid)
+        }
+        None => Err(AppError::Backend("Backend is not running.".to_string())),
+    }
+}
+
+/// Immediately kill the backend process tree, skipping the graceful
+/// shutdown request and grace period. Meant for support when the backend is
+/// wedged; any writes it had not flushed are lost. The supervisor then
+/// respawns it as after a crash. Returns the killed PID.
+#[tauri::command]
+pub fn force_kill_backend(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, BackendState>,
+) -> Result<u32, AppError> {
+    let result = force_kill(state);
+    crate::audit_log::privileged(&app_handle, "force_kill_backend", result)
+}
+
+/// What we found when looking for the bundled backend binary.
+#[derive(Clone, Debug, Serialize)]
+pub struct BackendBinaryInfo {
+    pub path: String,
+    pub exists: bool,
+    pub executable: bool,
+    pub size: Option<u64>,
+    /// Why the binary can't be used, if it can't.
+    pub problem: Option<String>,
+}
+
+impl BackendBinaryInfo {
+    fn diagnose(&self) -> Option<String> {
+        if !self.exists {
+            Some(format!(
+                "Backend binary is missing at {}. The installation may be incomplete or the file was removed by antivirus software.",
+                self.path
+            ))
+        } else if !self.executable {
+            Some(format!("Backend binary at {} is not executable.", self.path))
+        } else if self.size == Some(0) {
+            Some(format!("Backend binary at {} is empty (0 bytes).", self.path))
+        } else {
+            None
+        }
+    }
+}
+
+/// Path the shell plugin spawns the sidecar from: next to our own
+/// executable, with the target triple already stripped by the bundler.
+pub fn sidecar_path() -> Result<PathBuf, String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to locate current executable: {}", e))?;
+    let dir = exe
+        .parent()
+        .ok_or_else(|| "Current executable has no parent directory".to_string())?;
+    Ok(dir.join(format!("{}{}", SIDECAR_NAME, std::env::consts::EXE_SUFFIX)))
+}
+
+/// Before profiles, the backend kept its `data/` dir next to the sidecar
+/// executable, shared by every profile. Move it into the default profile's
+/// `backend-data/` once, so existing sales stay with that profile.
+pub fn adopt_legacy_data_dir(paths: &AppPaths) {
+    let target = paths.backend_data_dir();
+    if paths.profile != crate::profiles::DEFAULT_PROFILE || target.exists() {
+        return;
+    }
+    let Some(legacy) = sidecar_path().ok().and_then(|sidecar| Some(sidecar.parent()?.join("data"))) else {
+        return;
+    };
+    if !legacy.is_dir() {
+        return;
+    }
+    match std::fs::rename(&legacy, &target) {
+        Ok(()) => info!("📦 Moved backend data {:?} → {:?}", legacy, target),
+        // Across volumes a rename fails; copy and leave the original.
+        Err(e) => match crate::paths::copy_dir(&legacy, &target) {
+            Ok(()) => info!("📦 Copied backend data {:?} → {:?} ({})", legacy, target, e),
+            Err(e) => {
+                error!("❌ Failed to move backend data {:?} → {:?}: {}", legacy, target, e);
+                let _ = std::fs::remove_dir_all(&target);
+            }
+        },
+    }
+}
+
+pub fn inspect_backend_binary() -> Result<BackendBinaryInfo, String> {
+    let path = sidecar_path()?;
+    let meta = std::fs::metadata(&path).ok().filter(|m| m.is_file());
+
+    #[cfg(unix)]
+    let executable = {
+        use std::os::unix::fs::PermissionsExt;
+        meta.as_ref().map(|m| m.permissions().mode() & 0o111 != 0).unwrap_or(false)
+    };
+    #[cfg(not(unix))]
+    let executable = meta.is_some();
+
+    let mut info = BackendBinaryInfo {
+        path: path.display().to_string(),
+        exists: meta.is_some(),
+        executable,
+        size: meta.map(|m| m.len()),
+        problem: None,
+    };
+    info.problem = info.diagnose();
+    Ok(info)
+}
+
+/// Log the state of the sidecar binary; returns the problem, if any.
+pub fn log_backend_binary_check() -> Option<String> {
+    match inspect_backend_binary() {
+        Ok(info) => match info.problem {
+            Some(problem) => {
+                error!("❌ {}", problem);
+                Some(problem)
+            }
+            None => {
+                info!("✅ Backend binary OK: {} ({} bytes)", info.path, info.size.unwrap_or(0));
+                None
+            }
+        },
+        Err(e) => {
+            error!("❌ Could not check backend binary: {}", e);
+            Some(e)
+        }
+    }
+}
+
+#[tauri::command]
+pub fn check_backend_binary() -> Result<BackendBinaryInfo, AppError> {
+    inspect_backend_binary().map_err(AppError::Backend)
+}
+
+/// Hex-encoded SHA-256 of a file, streamed so large binaries aren't read
+/// into memory at once.
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Compare the sidecar on disk against the checksum embedded at build time.
+/// Returns `Err` with a user-facing message on mismatch or read failure.
+pub fn verify_sidecar_integrity() -> Result<(), String> {
+    let Some(expected) = EXPECTED_SIDECAR_SHA256 else {
+        warn!("⚠️ No embedded backend checksum; skipping integrity check");
+        return Ok(());
+    };
+
+    let path = sidecar_path()?;
+    let actual = sha256_file(&path)
+        .map_err(|e| format!("Failed to read backend binary {:?}: {}", path, e))?;
+
+    info!("🔐 Backend checksum: computed {} / expected {}", actual, expected);
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Backend binary {:?} is corrupt or was modified (checksum mismatch). Please reinstall the app.",
+            path
+        ))
+    }
+}
+
+// ============================================================================
+// RECENT BACKEND OUTPUT
+// ============================================================================
+
+/// How many backend lines are kept in memory for `get_backend_logs`.
+const LOG_BUFFER_CAPACITY: usize = 2000;
+
+#[derive(Clone, Serialize)]
+pub struct BackendLogLine {
+    /// Unix millis when the line was captured.
+    pub ts: u64,
+    pub level: String,
+    /// `stdout` or `stderr`.
+    pub stream: &'static str,
+    pub line: String,
+}
+
+/// Bounded buffer of recent backend output, managed as Tauri state.
+#[derive(Default)]
+pub struct BackendLogBuffer {
+    lines: Mutex<VecDeque<(log::Level, BackendLogLine)>>,
+}
+
+impl BackendLogBuffer {
+    pub fn push(&self, level: log::Level, stream: &'static str, line: &str) -> BackendLogLine {
+        let entry = BackendLogLine {
+            ts: unix_millis(),
+            level: level.to_string(),
+            stream,
+            line: line.to_string(),
+        };
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() == LOG_BUFFER_CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back((level, entry.clone()));
+        entry
+    }
+}
+
+/// Best-effort level of a backend line from the level name the Python
+/// logger prints, falling back to `default` for the stream.
+pub fn detect_line_level(line: &str, default: log::Level) -> log::Level {
+    let head: String = line.chars().take(64).collect::<String>().to_ascii_uppercase();
+    if head.contains("CRITICAL") || head.contains("ERROR") {
+        log::Level::Error
+    } else if head.contains("WARNING") || head.contains("WARN") {
+        log::Level::Warn
+    } else if head.contains("INFO") {
+        log::Level::Info
+    } else if head.contains("DEBUG") {
+        log::Level::Debug
+    } else {
+        default
+    }
+}
+
+/// Most recent `limit` backend lines at `min_level` or more severe, oldest first.
+#[tauri::command]
+pub fn get_backend_logs(
+    buffer: tauri::State<'_, BackendLogBuffer>,
+    min_level: String,
+    limit: usize,
+) -> Result<Vec<BackendLogLine>, AppError> {
+    let min_level = min_level
+        .parse::<log::Level>()
+        .map_err(|_| AppError::Validation(format!("Unknown log level '{}'", min_level)))?;
+
+    let lines = buffer.lines.lock().unwrap();
+    let mut matched: Vec<BackendLogLine> = lines
+        .iter()
+        .rev()
+        .filter(|(level, _)| *level <= min_level)
+        .take(limit)
+        .map(|(_, line)| line.clone())
+        .collect();
+    matched.reverse();
+    Ok(matched)
+}
+
+/// Version the running backend announced, if any.
+#[tauri::command]
+pub fn backend_version(state: tauri::State<'_, BackendState>) -> Option<String> {
+    state.version()
+}
+
+/// Recent backend exits (code, signal, time), oldest first.
+#[tauri::command]
+pub fn backend_exit_history(state: tauri::State<'_, BackendState>) -> Vec<TerminationRecord> {
+    state.exit_history()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend_transport::{BackendResponse, MockTransport};
+
+    use super::*;
+
+    #[test]
+    fn ping_reports_status_of_health_endpoint() {
+        let transport = MockTransport::new(|_| Ok(BackendResponse { status: 200, body: "ok".to_string() }));
+        let result = ping(&transport);
+        assert!(result.ok);
+        assert_eq!(result.status, Some(200));
+        assert_eq!(result.error, None);
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(matches!(requests[0].method, crate::backend_transport::Method::Get));
+        assert_eq!(requests[0].path, "/api/health");
+    }
+
+    #[test]
+    fn ping_is_not_ok_on_error_status() {
+        let transport = MockTransport::new(|_| Ok(BackendResponse { status: 503, body: String::new() }));
+        let result = ping(&transport);
+        assert!(!result.ok);
+        assert_eq!(result.status, Some(503));
+    }
+
+    #[test]
+    fn ping_reports_unreachable_backend() {
+        let transport = MockTransport::new(|_| Err("connection refused".to_string()));
+        let result = ping(&transport);
+        assert!(!result.ok);
+        assert_eq!(result.status, None);
+        assert_eq!(result.error.as_deref(), Some("connection refused"));
+    }
+
+    #[test]
+    fn release_child_keeps_a_newer_child() {
+        let mut slot = Some(2u32);
+        assert!(!release_child(&mut slot, 1, |pid| *pid));
+        assert_eq!(slot, Some(2));
+
+        assert!(release_child(&mut slot, 2, |pid| *pid));
+        assert_eq!(slot, None);
+        assert!(release_child(&mut slot, 2, |pid| *pid));
+    }
+
+    /// `sh -c 'sleep 60 & wait'` run from a copy of `/bin/sh` named like the
+    /// sidecar, so `kill_process_tree` accepts it as ours.
+    #[cfg(target_os = "linux")]
+    fn spawn_fake_sidecar() -> (std::process::Child, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("siri-kill-tree-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let exe = dir.join(SIDECAR_NAME);
+        std::fs::copy("/bin/sh", &exe).unwrap();
+        let child = std::process::Command::new(&exe).args(["-c", "sleep 60 & wait"]).spawn().unwrap();
+        (child, dir)
+    }
+
+    /// The shell and its `sleep`, once the shell has forked it.
+    #[cfg(target_os = "linux")]
+    fn wait_for_fork(pid: u32) -> Vec<sysinfo::Pid> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let mut sys = sysinfo::System::new();
+            sys.refresh_processes();
+            let tree = crate::resource_monitor::process_tree(&sys, sysinfo::Pid::from_u32(pid));
+            if tree.len() >= 2 || Instant::now() >= deadline {
+                return tree;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn kill_process_tree_kills_forked_children() {
+        let (mut child, dir) = spawn_fake_sidecar();
+        let tree = wait_for_fork(child.id());
+        assert_eq!(tree.len(), 2, "shell did not fork sleep: {:?}", tree);
+
+        assert!(kill_process_tree(child.id()).is_ok());
+
+        let mut sys = sysinfo::System::new();
+        assert!(still_running(&mut sys, &tree).is_empty(), "survivors of {:?}", tree);
+        let _ = child.wait();
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,153 @@
+//! Pinning the backend to a set of CPUs.
+//!
+//! On POS hardware shared with other software, keeping the backend off the
+//! cores the UI runs on stops the two contending. `set_backend_affinity`
+//! stores the set as `backend_cpu_affinity` (empty means every CPU) and
+//! applies it to the running backend and its children. Each respawn applies
+//! it again; processes the backend starts later inherit it.
+//!
+//! Applied with `SetProcessAffinityMask` on Windows and `sched_setaffinity`
+//! (for every thread) on Linux. Other platforms get `unsupported`.
+
+use log::{info, warn};
+use tauri::Manager;
+
+use crate::backend::BackendState;
+use crate::error::AppError;
+use crate::settings::SettingsState;
+
+/// CPUs a single affinity mask can address.
+const MASK_BITS: usize = 64;
+
+fn cpu_count() -> usize {
+    let sys = sysinfo::System::new_with_specifics(
+        sysinfo::RefreshKind::new().with_cpu(sysinfo::CpuRefreshKind::new()),
+    );
+    sys.cpus().len()
+}
+
+/// The mask for `cpus`, checked against the CPUs this machine has.
+fn mask_for(cpus: &[usize]) -> Result<u64, String> {
+    let available = cpu_count().min(MASK_BITS);
+    let mut mask = 0u64;
+    for &cpu in cpus {
+        if cpu >= available {
+            return Err(format!("CPU {} does not exist; this machine has CPUs 0-{}", cpu, available - 1));
+        }
+        if mask & (1 << cpu) != 0 {
+            return Err(format!("CPU {} is listed more than once", cpu));
+        }
+        mask |= 1 << cpu;
+    }
+    Ok(mask)
+}
+
+/// Every CPU this machine has.
+fn all_cpus() -> Vec<usize> {
+    (0..cpu_count().min(MASK_BITS)).collect()
+}
+
+#[cfg(target_os = "windows")]
+fn set_affinity(pid: u32, mask: u64) -> Result<(), String> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, SetProcessAffinityMask, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_SET_INFORMATION,
+    };
+
+    unsafe {
+        let process = OpenProcess(PROCESS_SET_INFORMATION | PROCESS_QUERY_LIMITED_INFORMATION, false, pid)
+            .map_err(|e| format!("Failed to open PID {}: {}", pid, e))?;
+        let result = SetProcessAffinityMask(process, mask as usize);
+        let _ = CloseHandle(process);
+        result.map_err(|e| format!("SetProcessAffinityMask failed for PID {}: {}", pid, e))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_affinity(pid: u32, mask: u64) -> Result<(), String> {
+    // Affinity is per thread on Linux; threads started later inherit it.
+    let tids: Vec<i32> = std::fs::read_dir(format!("/proc/{}/task", pid))
+        .map_err(|e| format!("Failed to list threads of PID {}: {}", pid, e))?
+        .flatten()
+        .filter_map(|e| e.file_name().to_str()?.parse().ok())
+        .collect();
+    for tid in tids {
+        unsafe {
+            let mut set: libc::cpu_set_t = std::mem::zeroed();
+            for cpu in (0..MASK_BITS).filter(|cpu| mask & (1 << cpu) != 0) {
+                libc::CPU_SET(cpu, &mut set);
+            }
+            if libc::sched_setaffinity(tid, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+                return Err(format!(
+                    "sched_setaffinity failed for PID {} thread {}: {}",
+                    pid,
+                    tid,
+                    std::io::Error::last_os_error()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn set_affinity(_pid: u32, _mask: u64) -> Result<(), String> {
+    Err("CPU affinity is not supported on this platform".to_string())
+}
+
+/// Apply `mask` to `pid` and everything it spawned.
+fn apply_tree(pid: u32, mask: u64) -> Result<(), String> {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes();
+    for pid in crate::resource_monitor::process_tree(&sys, sysinfo::Pid::from_u32(pid)) {
+        set_affinity(pid.as_u32(), mask)?;
+    }
+    Ok(())
+}
+
+/// Apply the stored affinity to a freshly spawned backend.
+pub fn apply_on_spawn(app: &tauri::AppHandle, pid: u32) {
+    let cpus = app.state::<SettingsState>().get().backend_cpu_affinity;
+    if cpus.is_empty() {
+        return;
+    }
+    let result = mask_for(&cpus).and_then(|mask| set_affinity(pid, mask).map(|()| mask));
+    match result {
+        Ok(mask) => info!("🧮 Backend PID {} pinned to CPUs {:?} (mask {:#x})", pid, cpus, mask),
+        Err(e) => warn!("⚠️ Backend CPU affinity {:?} not applied: {}", cpus, e),
+    }
+}
+
+async fn set_affinity_for(app_handle: tauri::AppHandle, cpus: Vec<usize>) -> Result<(), AppError> {
+    let mask = mask_for(&cpus).map_err(AppError::Validation)?;
+    if cfg!(not(any(target_os = "windows", target_os = "linux"))) {
+        return Err(AppError::Unsupported("CPU affinity is not supported on this platform.".to_string()));
+    }
+
+    app_handle
+        .state::<SettingsState>()
+        .update(serde_json::json!({ "backend_cpu_affinity": cpus }))?;
+
+    let Some(pid) = app_handle.state::<BackendState>().child.lock().unwrap().as_ref().map(|c| c.pid()) else {
+        info!("🧮 Backend CPU affinity {:?} stored; applied when the backend starts", cpus);
+        return Ok(());
+    };
+    let (mask, label) = if cpus.is_empty() {
+        (mask_for(&all_cpus())?, "all CPUs".to_string())
+    } else {
+        (mask, format!("CPUs {:?}", cpus))
+    };
+    tauri::async_runtime::spawn_blocking(move || apply_tree(pid, mask))
+        .await
+        .map_err(|e| format!("Affinity task failed: {}", e))?
+        .map_err(AppError::Backend)?;
+    info!("🧮 Backend PID {} pinned to {} (mask {:#x})", pid, label, mask);
+    Ok(())
+}
+
+/// Pin the backend to `cpus` (0-based), or to every CPU when empty.
+#[tauri::command]
+pub async fn set_backend_affinity(app_handle: tauri::AppHandle, cpus: Vec<usize>) -> Result<(), AppError> {
+    let result = set_affinity_for(app_handle.clone(), cpus).await;
+    crate::audit_log::privileged(&app_handle, "set_backend_affinity", result)
+}
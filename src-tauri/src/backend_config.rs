@@ -0,0 +1,180 @@
+//! `backend-config.json`: settings the Python backend reads (tax rates,
+//! currency, receipt header), edited from the shell so nobody has to touch
+//! the file by hand.
+//!
+//! The file sits in the app data dir. After a write the backend is asked to
+//! reload it via `POST /api/reload-config`; if it doesn't support that, the
+//! backend is restarted so the new values take effect.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::Manager;
+
+use crate::backend::{self, BackendState};
+use crate::backend_transport::BackendRequest;
+use crate::error::AppError;
+use crate::paths::{self, AppPaths};
+
+pub const BACKEND_CONFIG_FILE: &str = "backend-config.json";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TaxRate {
+    pub name: String,
+    /// Percentage, e.g. `18.0` for 18%.
+    pub rate: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackendConfig {
+    pub currency: String,
+    pub tax_rates: Vec<TaxRate>,
+    /// Lines printed at the top of every receipt.
+    pub receipt_header: Vec<String>,
+    /// Python logging level for the backend, independent of the shell's.
+    pub log_level: String,
+    /// Keys the shell doesn't know about are kept as-is.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for BackendConfig {
+    fn default() -> Self {
+        Self {
+            currency: "INR".to_string(),
+            tax_rates: Vec::new(),
+            receipt_header: Vec::new(),
+            log_level: "INFO".to_string(),
+            extra: Map::new(),
+        }
+    }
+}
+
+/// Levels understood by Python's `logging`.
+const BACKEND_LOG_LEVELS: [&str; 5] = ["DEBUG", "INFO", "WARNING", "ERROR", "CRITICAL"];
+
+impl BackendConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !BACKEND_LOG_LEVELS.contains(&self.log_level.as_str()) {
+            return Err(format!(
+                "log_level must be one of {}, got '{}'",
+                BACKEND_LOG_LEVELS.join(", "),
+                self.log_level
+            ));
+        }
+        let currency = self.currency.trim();
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(format!("currency must be a 3-letter ISO code, got '{}'", self.currency));
+        }
+        for tax in &self.tax_rates {
+            if tax.name.trim().is_empty() {
+                return Err("tax rate names must not be empty".to_string());
+            }
+            if !(0.0..=100.0).contains(&tax.rate) {
+                return Err(format!("tax rate '{}' must be between 0 and 100", tax.name));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn config_path(app: &tauri::AppHandle) -> PathBuf {
+    app.state::<AppPaths>().root.join(BACKEND_CONFIG_FILE)
+}
+
+/// Read the config, or defaults when the file doesn't exist yet or is
+/// corrupt (see `paths::read_config_or_default`).
+pub fn read_config(app: &tauri::AppHandle) -> Result<BackendConfig, String> {
+    Ok(paths::read_config_or_default(&config_path(app)))
+}
+
+/// Validate and atomically write the config, then get the backend to pick
+/// it up.
+pub fn write_config(app: &tauri::AppHandle, cfg: &BackendConfig) -> Result<(), String> {
+    cfg.validate()?;
+    let path = config_path(app);
+    let text = serde_json::to_vec_pretty(cfg).map_err(|e| e.to_string())?;
+    paths::write_atomic(&path, &text)
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    info!("⚙️ Backend config written to {:?}", path);
+    reload_backend(app)
+}
+
+/// Ask the backend to reload its config, restarting it if it can't.
+fn reload_backend(app: &tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<BackendState>();
+    if state.child.lock().unwrap().is_none() {
+        info!("Backend not running; config applies on next start");
+        return Ok(());
+    }
+
+    let reloaded = state
+        .transport()
+        .request(&BackendRequest::post("/api/reload-config", None, Duration::from_secs(5)))
+        .map(|r| r.is_success())
+        .unwrap_or(false);
+
+    if reloaded {
+        info!("✅ Backend reloaded its config");
+        Ok(())
+    } else {
+        warn!("⚠️ Backend did not accept a config reload; restarting it");
+        backend::restart(app)
+    }
+}
+
+#[tauri::command]
+pub fn get_backend_config(app_handle: tauri::AppHandle) -> Result<BackendConfig, AppError> {
+    read_config(&app_handle).map_err(AppError::Io)
+}
+
+async fn apply_config(app_handle: tauri::AppHandle, cfg: BackendConfig) -> Result<BackendConfig, AppError> {
+    cfg.validate().map_err(AppError::Validation)?;
+    let app = app_handle.clone();
+    let applied = cfg.clone();
+    tauri::async_runtime::spawn_blocking(move || write_config(&app, &applied))
+        .await
+        .map_err(|e| format!("Failed to apply backend config: {}", e))?
+        .map_err(AppError::Backend)?;
+    Ok(cfg)
+}
+
+/// Replace the backend config and return what was applied.
+#[tauri::command]
+pub async fn set_backend_config(
+    app_handle: tauri::AppHandle,
+    cfg: BackendConfig,
+) -> Result<BackendConfig, AppError> {
+    let result = apply_config(app_handle.clone(), cfg).await;
+    crate::audit_log::privileged(&app_handle, "set_backend_config", result)
+}
+
+async fn apply_log_level(app_handle: tauri::AppHandle, level: String) -> Result<String, AppError> {
+    let level = match level.trim().to_ascii_uppercase().as_str() {
+        "WARN" => "WARNING".to_string(),
+        other => other.to_string(),
+    };
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || -> Result<String, AppError> {
+        let mut cfg = read_config(&app).map_err(AppError::Io)?;
+        cfg.log_level = level;
+        cfg.validate().map_err(AppError::Validation)?;
+        write_config(&app, &cfg).map_err(AppError::Backend)?;
+        info!("📝 Backend log level set to {}", cfg.log_level);
+        Ok(cfg.log_level)
+    })
+    .await
+    .map_err(|e| format!("Failed to set backend log level: {}", e))?
+}
+
+/// Change only the backend's log level and return the level now in effect.
+/// Accepts any case, and `warn` for `WARNING`.
+#[tauri::command]
+pub async fn set_backend_log_level(app_handle: tauri::AppHandle, level: String) -> Result<String, AppError> {
+    let result = apply_log_level(app_handle.clone(), level).await;
+    crate::audit_log::privileged(&app_handle, "set_backend_log_level", result)
+}
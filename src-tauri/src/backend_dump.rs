@@ -0,0 +1,158 @@
+//! Stack dumps of a backend that is alive but wedged.
+//!
+//! `dump_backend_state` first asks the backend over HTTP
+//! (`POST /api/debug/stack-dump { path }`) to write every thread's stack to
+//! `logs/backend-stack-<ts>.txt`. That works while at least one request
+//! thread is free.
+//!
+//! When the backend doesn't answer, Unix has a fallback. Each spawn passes
+//! `SIRI_BACKEND_DUMP_FILE`; a backend that supports dumps opens that file
+//! and registers `faulthandler` on `SIGUSR1` to write into it. The file
+//! existing is the signal that the handler is in place — sending `SIGUSR1`
+//! to a backend without one would kill it — so the shell removes it before
+//! every spawn and only signals when it has reappeared. What the handler
+//! appends is copied out to the same `backend-stack-<ts>.txt`.
+//!
+//! A backend that supports neither gets an `unsupported` error.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use tauri::Manager;
+
+use crate::backend::BackendState;
+use crate::backend_transport::BackendRequest;
+use crate::error::AppError;
+use crate::paths::AppPaths;
+
+const DUMP_PATH: &str = "/api/debug/stack-dump";
+/// Short: the backend is probably hung, and the signal path is next.
+const DUMP_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Set up by the backend at start; see the module docs.
+const SIGNAL_DUMP_FILE: &str = "backend-faulthandler.txt";
+const DUMP_WAIT: Duration = Duration::from_secs(5);
+
+fn signal_dump_file(app: &tauri::AppHandle) -> PathBuf {
+    app.state::<AppPaths>().logs_dir().join(SIGNAL_DUMP_FILE)
+}
+
+/// Environment for the backend sidecar. Clears the previous backend's
+/// file so only a backend that sets the handler up again is signalled.
+pub fn backend_env(app: &tauri::AppHandle) -> Vec<(&'static str, String)> {
+    let file = signal_dump_file(app);
+    let _ = fs::remove_file(&file);
+    vec![("SIRI_BACKEND_DUMP_FILE", file.display().to_string())]
+}
+
+/// Wait up to `DUMP_WAIT` for `path` to be longer than `len` and stop growing.
+fn wait_for_growth(path: &Path, len: u64) -> Option<u64> {
+    let deadline = Instant::now() + DUMP_WAIT;
+    let mut last = len;
+    while Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(200));
+        let now = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if now > len && now == last {
+            return Some(now);
+        }
+        last = now;
+    }
+    (last > len).then_some(last)
+}
+
+fn dump_over_http(state: &BackendState, out: &Path) -> Result<bool, String> {
+    let body = serde_json::json!({ "path": out.display().to_string() });
+    let response = state
+        .transport()
+        .request(&BackendRequest::post(DUMP_PATH, Some(body), DUMP_REQUEST_TIMEOUT))?;
+    match response.status {
+        404 => Ok(false),
+        _ if response.is_success() => {
+            if out.exists() || wait_for_growth(out, 0).is_some() {
+                Ok(true)
+            } else {
+                Err("backend accepted the request but wrote no dump".to_string())
+            }
+        }
+        status => Err(format!("HTTP {}: {}", status, response.body.trim())),
+    }
+}
+
+#[cfg(unix)]
+fn dump_with_signal(app: &tauri::AppHandle, pid: u32, out: &Path) -> Result<(), AppError> {
+    let file = signal_dump_file(app);
+    let Ok(meta) = fs::metadata(&file) else {
+        return Err(AppError::Unsupported(
+            "The backend can't write stack dumps: it has no dump endpoint or signal handler.".to_string(),
+        ));
+    };
+    let before = meta.len();
+
+    let mut sys = sysinfo::System::new();
+    let pid = sysinfo::Pid::from_u32(pid);
+    sys.refresh_process(pid);
+    let sent = sys.process(pid).and_then(|p| p.kill_with(sysinfo::Signal::User1));
+    if sent != Some(true) {
+        return Err(AppError::Backend(format!("Failed to send SIGUSR1 to PID {}", pid)));
+    }
+
+    let after = wait_for_growth(&file, before).ok_or_else(|| {
+        AppError::Backend("The backend did not write a stack dump after SIGUSR1.".to_string())
+    })?;
+    let bytes = fs::read(&file).map_err(|e| AppError::Io(format!("Failed to read {:?}: {}", file, e)))?;
+    let dump = &bytes[before as usize..(after as usize).min(bytes.len())];
+    fs::write(out, dump).map_err(|e| AppError::Io(format!("Failed to write {:?}: {}", out, e)))?;
+    Ok(())
+}
+
+fn dump(app: &tauri::AppHandle) -> Result<PathBuf, AppError> {
+    let state = app.state::<BackendState>();
+    let pid = state
+        .child
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|c| c.pid())
+        .ok_or_else(|| AppError::Conflict("Backend is not running.".to_string()))?;
+
+    let out = app
+        .state::<AppPaths>()
+        .logs_dir()
+        .join(format!("backend-stack-{}.txt", crate::timestamps::file_stamp()));
+
+    let http_error = match dump_over_http(&state, &out) {
+        Ok(true) => {
+            info!("🧵 Backend stack dump written to {:?}", out);
+            return Ok(out);
+        }
+        Ok(false) => "the backend has no dump endpoint".to_string(),
+        Err(e) => e,
+    };
+
+    #[cfg(unix)]
+    {
+        warn!("⚠️ Stack dump over HTTP failed ({}); signalling PID {}", http_error, pid);
+        dump_with_signal(app, pid, &out)?;
+        info!("🧵 Backend stack dump (SIGUSR1) written to {:?}", out);
+        Ok(out)
+    }
+    #[cfg(not(unix))]
+    {
+        Err(AppError::Unsupported(format!(
+            "Backend (PID {}) could not write a stack dump: {}",
+            pid, http_error
+        )))
+    }
+}
+
+/// Have the backend write a stack dump of all its threads. Returns the
+/// file's path.
+#[tauri::command]
+pub async fn dump_backend_state(app_handle: tauri::AppHandle) -> Result<String, AppError> {
+    let path = tauri::async_runtime::spawn_blocking(move || dump(&app_handle))
+        .await
+        .map_err(|e| format!("Stack dump task failed: {}", e))??;
+    Ok(path.display().to_string())
+}
@@ -0,0 +1,111 @@
+//! Health probing of a running backend.
+//!
+//! A deadlocked backend keeps its process alive, so neither the exit
+//! handler nor the supervisor ever notices it. Every
+//! `backend_monitor.health_interval_secs` the health endpoint is requested;
+//! after `backend_monitor.unhealthy_after` failures in a row the backend is
+//! marked unhealthy and `backend-unresponsive { failures, error }` is
+//! emitted, along with `backend://unhealthy` carrying the same payload for
+//! listeners written against that name. With
+//! `backend_monitor.restart_when_unhealthy` it is then restarted.
+//! `backend-responsive` is emitted when an unhealthy backend answers again.
+//!
+//! Probing only runs once the handshake has marked the backend ready, so a
+//! slow start is never taken for a hang; while it is paused, being stopped
+//! or being restarted the count is reset. `stop` ends the probing at exit.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{error, info, warn};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::backend::{self, BackendState};
+use crate::backend_transport::BackendRequest;
+use crate::settings::SettingsState;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+static STOPPED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Serialize)]
+struct BackendUnresponsive {
+    failures: u32,
+    error: String,
+}
+
+fn probe(state: &BackendState) -> Result<(), String> {
+    let response = state.transport().request(&BackendRequest::get("/api/health", PROBE_TIMEOUT))?;
+    if response.is_success() {
+        Ok(())
+    } else {
+        Err(format!("health check returned HTTP {}", response.status))
+    }
+}
+
+/// Stop probing; called on app exit.
+pub fn stop() {
+    STOPPED.store(true, Ordering::SeqCst);
+}
+
+pub fn start_health_monitor(app: tauri::AppHandle) {
+    thread::spawn(move || {
+        let mut failures = 0u32;
+        loop {
+            let settings = app.state::<SettingsState>().get().backend_monitor;
+            thread::sleep(Duration::from_secs(settings.health_interval_secs));
+            if STOPPED.load(Ordering::SeqCst) {
+                return;
+            }
+
+            let state = app.state::<BackendState>();
+            let idle = !state.ready.load(Ordering::SeqCst)
+                || state.is_paused()
+                || state.stopping.load(Ordering::SeqCst)
+                || state.is_restarting();
+            if idle || settings.unhealthy_after == 0 {
+                failures = 0;
+                continue;
+            }
+
+            let started = Instant::now();
+            let error = match probe(&state) {
+                Ok(()) => {
+                    failures = 0;
+                    if state.unhealthy.swap(false, Ordering::SeqCst) {
+                        info!("💚 Backend is answering health checks again");
+                        let _ = app.emit("backend-responsive", ());
+                    }
+                    continue;
+                }
+                Err(e) => e,
+            };
+
+            failures += 1;
+            warn!(
+                "🩺 Backend health check {}/{} failed after {} ms: {}",
+                failures,
+                settings.unhealthy_after,
+                started.elapsed().as_millis(),
+                error
+            );
+            if failures < settings.unhealthy_after || state.unhealthy.swap(true, Ordering::SeqCst) {
+                continue;
+            }
+
+            error!("❌ Backend unresponsive after {} failed health checks", failures);
+            let payload = BackendUnresponsive { failures, error };
+            let _ = app.emit("backend-unresponsive", payload.clone());
+            let _ = app.emit("backend://unhealthy", payload);
+            if settings.restart_when_unhealthy {
+                match backend::restart(&app) {
+                    Ok(()) => info!("🔁 Unresponsive backend restarted"),
+                    Err(e) => error!("❌ Failed to restart unresponsive backend: {}", e),
+                }
+            }
+            failures = 0;
+        }
+    });
+}
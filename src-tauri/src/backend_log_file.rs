@@ -0,0 +1,95 @@
+//! `logs/backend.log`: the backend's stdout and stderr exactly as it
+//! printed them, for handing to the backend team. Lines are written before
+//! any rate limiting or truncation, without our timestamps or markers.
+//!
+//! The file rolls over at `MAX_FILE_SIZE`, like the main log, keeping
+//! `GENERATIONS` old ones as `backend.log.1` (newest) and `backend.log.2`.
+//! The shell log still gets every line too, interleaved with our own.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::warn;
+use tauri::Manager;
+
+use crate::paths::AppPaths;
+
+pub const BACKEND_LOG_FILE: &str = "backend.log";
+const MAX_FILE_SIZE: u64 = 10_000_000;
+const GENERATIONS: usize = 2;
+
+#[derive(Default)]
+pub struct BackendLogFile {
+    open: Mutex<Option<OpenFile>>,
+}
+
+struct OpenFile {
+    file: File,
+    len: u64,
+}
+
+fn log_path(app: &tauri::AppHandle) -> PathBuf {
+    app.state::<AppPaths>().logs_dir().join(BACKEND_LOG_FILE)
+}
+
+fn open(path: &Path) -> std::io::Result<OpenFile> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let len = file.metadata()?.len();
+    Ok(OpenFile { file, len })
+}
+
+fn generation(path: &Path, n: usize) -> PathBuf {
+    path.with_file_name(format!("{}.{}", BACKEND_LOG_FILE, n))
+}
+
+/// Shift `backend.log.1` to `.2` and so on, dropping the oldest, and move a
+/// full `backend.log` to `.1` so the next write starts a new one.
+fn rotate(path: &Path) -> std::io::Result<()> {
+    let _ = fs::remove_file(generation(path, GENERATIONS));
+    for n in (1..GENERATIONS).rev() {
+        let from = generation(path, n);
+        if from.exists() {
+            fs::rename(&from, generation(path, n + 1))?;
+        }
+    }
+    fs::rename(path, generation(path, 1))
+}
+
+/// Append one line of backend output.
+pub fn write(app: &tauri::AppHandle, line: &[u8]) {
+    let path = log_path(app);
+    let state = app.state::<BackendLogFile>();
+    let mut open_file = state.open.lock().unwrap();
+
+    if open_file.as_ref().is_some_and(|f| f.len >= MAX_FILE_SIZE) {
+        *open_file = None;
+        if let Err(e) = rotate(&path) {
+            warn!("⚠️ Failed to rotate {:?}: {}", path, e);
+        }
+    }
+    if open_file.is_none() {
+        match open(&path) {
+            Ok(f) => *open_file = Some(f),
+            Err(e) => {
+                warn!("⚠️ Failed to open {:?}: {}", path, e);
+                return;
+            }
+        }
+    }
+
+    let Some(f) = open_file.as_mut() else { return };
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    match f.file.write_all(line).and_then(|()| f.file.write_all(b"\n")) {
+        Ok(()) => f.len += line.len() as u64 + 1,
+        Err(e) => {
+            warn!("⚠️ Failed to write {:?}: {}", path, e);
+            *open_file = None;
+        }
+    }
+}
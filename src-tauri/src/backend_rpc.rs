@@ -0,0 +1,130 @@
+//! Single controlled channel for frontend → backend calls.
+//!
+//! `backend_invoke` wraps the call in a JSON-RPC 2.0 request and posts it
+//! to the backend's `/api/rpc` through `BackendTransport`, so the frontend
+//! never needs the port and every call is logged in one place. Failures are
+//! `AppError::Backend` with `details.kind` set to one of `unavailable`,
+//! `timeout`, `transport`, `http`, `invalid_response` or `rpc`
+//! (`invalid_request` is a validation error). Calls made before the
+//! backend is ready fail with `BackendNotReady` without being sent.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant};
+
+use log::{debug, warn};
+use serde_json::{json, Value};
+use tauri::Manager;
+
+use crate::backend::BackendState;
+use crate::backend_transport::BackendRequest;
+use crate::error::AppError;
+
+const RPC_PATH: &str = "/api/rpc";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
+/// Longest timeout a caller may ask for.
+const MAX_TIMEOUT: Duration = Duration::from_secs(120);
+
+fn request_id() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    // Keep it within JSON's safe integer range.
+    hasher.finish() >> 11
+}
+
+/// Turn a JSON-RPC response body into the call's result.
+fn parse_rpc_response(body: &str) -> Result<Value, String> {
+    let response: Value = serde_json::from_str(body)
+        .map_err(|e| format!("invalid_response: {}", e))?;
+    if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+        let code = error.get("code").and_then(Value::as_i64).unwrap_or(0);
+        let message = error.get("message").and_then(Value::as_str).unwrap_or("unknown error");
+        return Err(format!("rpc: {} ({})", message, code));
+    }
+    response
+        .get("result")
+        .cloned()
+        .ok_or_else(|| "invalid_response: missing result".to_string())
+}
+
+/// Turn a `"<kind>: <detail>"` failure into a backend error tagged with
+/// its kind.
+fn rpc_error(error: String) -> AppError {
+    let (kind, detail) = error.split_once(": ").unwrap_or(("transport", error.as_str()));
+    AppError::Backend(detail.to_string()).with_details(json!({ "kind": kind }))
+}
+
+async fn invoke(
+    app_handle: tauri::AppHandle,
+    method: String,
+    params: Option<Value>,
+    timeout_ms: Option<u64>,
+) -> Result<Value, AppError> {
+    if method.trim().is_empty() {
+        return Err(AppError::Validation("method must not be empty".to_string())
+            .with_details(json!({ "kind": "invalid_request" })));
+    }
+    crate::backend::ensure_ready(&app_handle, "backend_invoke")?;
+    let timeout = timeout_ms.map(Duration::from_millis).unwrap_or(DEFAULT_TIMEOUT).min(MAX_TIMEOUT);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app_handle.state::<BackendState>();
+        if state.is_paused() {
+            return Err("unavailable: backend is paused".to_string());
+        }
+
+        let id = request_id();
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params.unwrap_or(Value::Null),
+        });
+        let started = Instant::now();
+        let result = state
+            .transport()
+            .request(&BackendRequest::post(RPC_PATH, Some(body), timeout))
+            .map_err(|e| {
+                if e.contains("timed out") {
+                    format!("timeout: no response within {:?}", timeout)
+                } else {
+                    format!("transport: {}", e)
+                }
+            })
+            .and_then(|response| {
+                if response.is_success() {
+                    parse_rpc_response(&response.body)
+                } else {
+                    Err(format!("http: status {}", response.status))
+                }
+            });
+
+        let elapsed = started.elapsed();
+        match &result {
+            Ok(_) => debug!("📨 Backend RPC '{}' (id {}) ok in {:?}", method, id, elapsed),
+            Err(e) => warn!("⚠️ Backend RPC '{}' (id {}) failed in {:?}: {}", method, id, elapsed, e),
+        }
+        result
+    })
+    .await
+    .map_err(|e| format!("transport: RPC task failed: {}", e))
+    .and_then(|result| result)
+    .map_err(rpc_error)
+}
+
+/// Call `method` on the backend with `params` and return its result.
+#[tauri::command]
+pub async fn backend_invoke(
+    app_handle: tauri::AppHandle,
+    method: String,
+    params: Option<Value>,
+    timeout_ms: Option<u64>,
+) -> Result<Value, AppError> {
+    let result = invoke(app_handle.clone(), method, params, timeout_ms).await;
+    crate::audit_log::privileged(&app_handle, "backend_invoke", result)
+}
@@ -0,0 +1,117 @@
+//! Automatic restart of a crashed backend.
+//!
+//! When the sidecar exits with a non-zero code or a signal,
+//! `backend-crashed { code, signal, restarting }` is emitted so the
+//! frontend can show a banner, and the sidecar is started again after a
+//! backoff of 1s, 2s, 4s… capped at `MAX_BACKOFF`. Crashes and failed
+//! spawns are counted over the last `backend_monitor.restart_window_secs`;
+//! past `backend_monitor.max_restarts` of them it stops being restarted and
+//! `backend-restart-exhausted { attempts }` is emitted.
+//!
+//! Exits we caused on purpose (graceful shutdown, app exit) set
+//! `BackendState::stopping` first, arrive with `expected` set and are never
+//! restarted. A force-kill
+//! doesn't, so the killed backend is restarted like a crashed one.
+
+use std::collections::VecDeque;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{error, info, warn};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::backend::{self, BackendState};
+use crate::event_bus::LifecycleEvent;
+use crate::settings::SettingsState;
+
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+pub struct BackendSupervisor {
+    /// Crashes and failed restarts within the restart window, oldest first.
+    failures: Mutex<VecDeque<Instant>>,
+}
+
+#[derive(Clone, Serialize)]
+struct BackendCrashed {
+    code: Option<i32>,
+    signal: Option<i32>,
+    /// Whether a restart has been scheduled.
+    restarting: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct RestartExhausted {
+    attempts: u32,
+}
+
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1u64 << (attempt - 1).min(5)).min(MAX_BACKOFF)
+}
+
+/// Event-bus subscriber: restart the backend after a crash.
+pub fn on_lifecycle(app: &tauri::AppHandle, event: &LifecycleEvent) {
+    let LifecycleEvent::BackendExited { code, signal, expected } = *event else { return };
+    if expected || (code == Some(0) && signal.is_none()) {
+        return;
+    }
+    let restarting = if app.state::<SettingsState>().get().backend_monitor.auto_restart {
+        schedule_restart(app, format!("Backend crashed (code {:?}, signal {:?})", code, signal))
+    } else {
+        warn!("⚠️ Backend exited (code {:?}); automatic restart is off", code);
+        false
+    };
+    let _ = app.emit("backend-crashed", BackendCrashed { code, signal, restarting });
+}
+
+/// Record a failure and start the backend again after the next backoff,
+/// unless the window's budget is used up. A restart that fails to spawn
+/// schedules the next. Returns whether a restart was scheduled.
+fn schedule_restart(app: &tauri::AppHandle, reason: String) -> bool {
+    let settings = app.state::<SettingsState>().get().backend_monitor;
+    let window = Duration::from_secs(settings.restart_window_secs);
+    let mut failures = app.state::<BackendSupervisor>().failures.lock().unwrap();
+    while failures.front().is_some_and(|at| at.elapsed() > window) {
+        failures.pop_front();
+    }
+    failures.push_back(Instant::now());
+    let attempt = failures.len() as u32;
+    drop(failures);
+
+    let max_restarts = settings.max_restarts;
+    if attempt > max_restarts {
+        error!(
+            "❌ {}: {} failure(s) in the last {:?}; giving up until the app restarts",
+            reason, attempt, window
+        );
+        let _ = app.emit("backend-restart-exhausted", RestartExhausted { attempts: attempt - 1 });
+        return false;
+    }
+
+    let delay = backoff(attempt);
+    warn!("🔁 {}; restart attempt {}/{} in {:?}", reason, attempt, max_restarts, delay);
+    let app = app.clone();
+    thread::spawn(move || {
+        thread::sleep(delay);
+        if app.state::<BackendState>().stopping.load(Ordering::SeqCst) {
+            info!("🔁 Backend restart attempt {} cancelled: the backend is being stopped", attempt);
+            return;
+        }
+        match backend::spawn_sidecar(&app) {
+            Ok(pid) => {
+                let total = app.state::<BackendState>().restarts.fetch_add(1, Ordering::SeqCst) + 1;
+                info!(
+                    "🔁 Backend restart attempt {} spawned PID {} ({} restart(s) this session)",
+                    attempt, pid, total
+                );
+            }
+            Err(e) => {
+                schedule_restart(&app, format!("Backend restart attempt {} failed: {}", attempt, e));
+            }
+        }
+    });
+    true
+}
@@ -0,0 +1,133 @@
+//! Short diagnostic sessions with the backend's most verbose tracing.
+//!
+//! `set_backend_trace(true)` restarts the backend with `SIRI_BACKEND_TRACE=1`
+//! and `SIRI_BACKEND_TRACE_FILE` pointing at `backend.log` in the logs dir.
+//! That is far heavier than `set_backend_log_level`, which only changes
+//! the Python logging level, and it slows the backend down, so the mode is
+//! never persisted and expires after `backend_trace_mins`. Once it is
+//! turned off or expires the backend is restarted normally.
+//!
+//! `backend-trace { enabled, expires_in_secs }` is emitted on every change
+//! so the UI can show a "tracing — performance reduced" banner.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{error, info, warn};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::backend;
+use crate::error::AppError;
+use crate::paths::AppPaths;
+use crate::restart_schedule::RestartScheduler;
+use crate::settings::SettingsState;
+
+const TRACE_FILE: &str = "backend.log";
+
+#[derive(Default)]
+pub struct BackendTraceState {
+    /// When the current session expires, while tracing is on.
+    until: Mutex<Option<Instant>>,
+    /// Bumped on every change so a stale expiry timer does nothing.
+    generation: AtomicU64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct BackendTraceStatus {
+    pub enabled: bool,
+    pub expires_in_secs: Option<u64>,
+}
+
+fn status(app: &tauri::AppHandle) -> BackendTraceStatus {
+    let until = *app.state::<BackendTraceState>().until.lock().unwrap();
+    BackendTraceStatus {
+        enabled: until.is_some(),
+        expires_in_secs: until.map(|at| at.saturating_duration_since(Instant::now()).as_secs()),
+    }
+}
+
+/// Environment for the backend sidecar while tracing is on.
+pub fn backend_env(app: &tauri::AppHandle) -> Vec<(&'static str, String)> {
+    if app.state::<BackendTraceState>().until.lock().unwrap().is_none() {
+        return Vec::new();
+    }
+    let file = app.state::<AppPaths>().logs_dir().join(TRACE_FILE);
+    vec![
+        ("SIRI_BACKEND_TRACE", "1".to_string()),
+        ("SIRI_BACKEND_TRACE_FILE", file.display().to_string()),
+    ]
+}
+
+/// Switch tracing and restart the backend so the environment takes effect.
+fn switch(app: &tauri::AppHandle, until: Option<Instant>) -> Result<BackendTraceStatus, String> {
+    let state = app.state::<BackendTraceState>();
+    *state.until.lock().unwrap() = until;
+    let generation = state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+    backend::restart(app)?;
+
+    if let Some(until) = until {
+        let app = app.clone();
+        thread::spawn(move || {
+            thread::sleep(until.saturating_duration_since(Instant::now()));
+            if app.state::<BackendTraceState>().generation.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            info!("🔬 Backend trace session expired; restarting the backend normally");
+            match switch(&app, None) {
+                Ok(status) => {
+                    let _ = app.emit("backend-trace", status);
+                }
+                Err(e) => error!("❌ Failed to end backend trace session: {}", e),
+            }
+        });
+    }
+    Ok(status(app))
+}
+
+async fn set_trace(app_handle: tauri::AppHandle, enabled: bool) -> Result<BackendTraceStatus, AppError> {
+    if status(&app_handle).enabled == enabled {
+        return Ok(status(&app_handle));
+    }
+    if app_handle.state::<RestartScheduler>().is_sale_in_progress() {
+        return Err(AppError::Conflict(
+            "Finish the current sale first; the backend has to restart.".to_string(),
+        ));
+    }
+
+    let until = if enabled {
+        let mins = app_handle.state::<SettingsState>().get().backend_trace_mins;
+        warn!(
+            "🔬 Backend tracing ON for {} min — expect reduced performance; trace goes to {}",
+            mins, TRACE_FILE
+        );
+        Some(Instant::now() + Duration::from_secs(mins * 60))
+    } else {
+        info!("🔬 Backend tracing OFF");
+        None
+    };
+
+    let app = app_handle.clone();
+    let status = tauri::async_runtime::spawn_blocking(move || switch(&app, until))
+        .await
+        .map_err(|e| format!("Backend trace task failed: {}", e))?
+        .map_err(AppError::Backend)?;
+    let _ = app_handle.emit("backend-trace", status.clone());
+    Ok(status)
+}
+
+/// Turn verbose backend tracing on for `backend_trace_mins`, or off.
+/// Either way the backend is restarted, so it's refused mid-sale.
+#[tauri::command]
+pub async fn set_backend_trace(app_handle: tauri::AppHandle, enabled: bool) -> Result<BackendTraceStatus, AppError> {
+    let result = set_trace(app_handle.clone(), enabled).await;
+    crate::audit_log::privileged(&app_handle, "set_backend_trace", result)
+}
+
+#[tauri::command]
+pub fn backend_trace_status(app_handle: tauri::AppHandle) -> BackendTraceStatus {
+    status(&app_handle)
+}
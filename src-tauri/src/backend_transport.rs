@@ -0,0 +1,150 @@
+//! How the shell talks to the backend.
+//!
+//! Everything goes through `BackendTransport::request`, so callers don't
+//! assume the backend listens on HTTP. `HttpTransport` is the only
+//! implementation today; a stdin/stdout JSON-RPC transport can slot in
+//! behind the same trait. Tests use `MockTransport` instead.
+//!
+//! A request carries either a JSON body or a `FileBody`, which is streamed
+//! from disk so large uploads are never held in memory.
+
+use std::fs::File;
+use std::path::PathBuf;
+#[cfg(test)]
+use std::sync::Mutex;
+use std::time::Duration;
+
+use reqwest::blocking::{Body, Client};
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Method {
+    Get,
+    Post,
+}
+
+#[derive(Clone, Debug)]
+pub struct BackendRequest {
+    pub method: Method,
+    /// Path under the backend root, e.g. `/api/health`.
+    pub path: String,
+    pub body: Option<Value>,
+    /// File to send as the body instead of `body`.
+    pub file: Option<FileBody>,
+    pub timeout: Duration,
+}
+
+/// A file sent as the raw body of a `POST`.
+#[derive(Clone, Debug)]
+pub struct FileBody {
+    pub path: PathBuf,
+    pub content_type: &'static str,
+    /// Original file name, sent URL-encoded as `X-File-Name`.
+    pub file_name: String,
+}
+
+impl BackendRequest {
+    pub fn get(path: &str, timeout: Duration) -> Self {
+        Self { method: Method::Get, path: path.to_string(), body: None, file: None, timeout }
+    }
+
+    pub fn post(path: &str, body: Option<Value>, timeout: Duration) -> Self {
+        Self { method: Method::Post, path: path.to_string(), body, file: None, timeout }
+    }
+
+    /// `POST` streaming `file` as the body.
+    pub fn upload(path: &str, file: FileBody, timeout: Duration) -> Self {
+        Self { method: Method::Post, path: path.to_string(), body: None, file: Some(file), timeout }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BackendResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl BackendResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+pub trait BackendTransport: Send + Sync {
+    /// Send one request. `Err` means the backend couldn't be reached at
+    /// all; an error status is still `Ok`.
+    fn request(&self, req: &BackendRequest) -> Result<BackendResponse, String>;
+}
+
+pub struct HttpTransport {
+    base_url: String,
+}
+
+impl HttpTransport {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self { base_url: base_url.into() }
+    }
+}
+
+impl BackendTransport for HttpTransport {
+    fn request(&self, req: &BackendRequest) -> Result<BackendResponse, String> {
+        let client = Client::builder()
+            .timeout(req.timeout)
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        let url = format!("{}{}", self.base_url, req.path);
+        let builder = match req.method {
+            Method::Get => client.get(url),
+            Method::Post => client.post(url),
+        };
+        let builder = match (&req.file, &req.body) {
+            (Some(upload), _) => {
+                let file = File::open(&upload.path).map_err(|e| format!("Cannot open {:?}: {}", upload.path, e))?;
+                let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+                builder
+                    .header(reqwest::header::CONTENT_TYPE, upload.content_type)
+                    .header("X-File-Name", urlencoding::encode(&upload.file_name).into_owned())
+                    .body(Body::sized(file, size))
+            }
+            (None, Some(body)) => builder.json(body),
+            (None, None) => builder,
+        };
+        let response = builder.send().map_err(|e| e.to_string())?;
+        Ok(BackendResponse {
+            status: response.status().as_u16(),
+            body: response.text().unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+type Responder = dyn Fn(&BackendRequest) -> Result<BackendResponse, String> + Send + Sync;
+
+/// Answers every request with `respond` and records it.
+#[cfg(test)]
+pub struct MockTransport {
+    respond: Box<Responder>,
+    requests: Mutex<Vec<BackendRequest>>,
+}
+
+#[cfg(test)]
+impl MockTransport {
+    pub fn new(
+        respond: impl Fn(&BackendRequest) -> Result<BackendResponse, String> + Send + Sync + 'static,
+    ) -> Self {
+        Self { respond: Box::new(respond), requests: Mutex::new(Vec::new()) }
+    }
+
+    /// Requests made so far, oldest first.
+    pub fn requests(&self) -> Vec<BackendRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl BackendTransport for MockTransport {
+    fn request(&self, req: &BackendRequest) -> Result<BackendResponse, String> {
+        self.requests.lock().unwrap().push(req.clone());
+        (self.respond)(req)
+    }
+}
@@ -0,0 +1,401 @@
+//! Zip backups of the app data dir under `backups/`, and their retention.
+//!
+//! `prune_backups` runs after every backup: it keeps at most
+//! `backups.max_backups` archives and removes any older than
+//! `backups.retention_days`, whichever is stricter, but never the most
+//! recent one. Log retention is handled separately in `main.rs`.
+//!
+//! Backups also carry the backend's own data dir (its JSON store and offline
+//! queues) under `backend/` in the archive.
+//!
+//! `create_backup` reports `backup-progress { phase, bytes_copied,
+//! total_bytes }` while it runs (`total_bytes` is `null` when the size
+//! couldn't be measured up front) and ends with `backup-complete` or
+//! `backup-failed`.
+
+use std::cell::Cell;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use log::{error, info, warn};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+use zip::write::SimpleFileOptions;
+
+use crate::disk;
+use crate::error::AppError;
+use crate::paths::AppPaths;
+use crate::settings::{BackupSettings, SettingsState};
+
+pub const BACKUPS_DIR: &str = "backups";
+/// Scratch and diagnostic folders left out of regular backups.
+const EXCLUDED_FROM_BACKUP: [&str; 4] = ["logs", "tmp", "crashes", "screenshots"];
+/// Folder the backend's data dir is stored under in an archive.
+const BACKEND_ARCHIVE_DIR: &str = "backend/";
+/// Headroom required for a backup when its compressed size is unknown.
+const MIN_BACKUP_FREE_SPACE: u64 = 100 * 1024 * 1024;
+
+pub fn backups_dir(paths: &AppPaths) -> PathBuf {
+    paths.root.join(BACKUPS_DIR)
+}
+
+/// Called with the running total of bytes copied into a backup.
+pub type Progress<'a> = &'a mut dyn FnMut(u64);
+
+/// Total size of the files `zip_tree` would copy, if it can be measured.
+fn tree_size(dir: &Path, skip: &[PathBuf]) -> Option<u64> {
+    let mut total = 0;
+    for entry in fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        if skip.contains(&path) {
+            continue;
+        }
+        let meta = entry.metadata().ok()?;
+        total += if meta.is_dir() { tree_size(&path, skip)? } else { meta.len() };
+    }
+    Some(total)
+}
+
+/// Add every file under `dir` to `zip`, named relative to `root` after
+/// `prefix`, skipping the paths in `skip`. `copied` accumulates bytes read
+/// for `progress`.
+fn zip_tree(
+    zip: &mut zip::ZipWriter<File>,
+    root: &Path,
+    dir: &Path,
+    prefix: &str,
+    skip: &[PathBuf],
+    copied: &mut u64,
+    progress: Progress,
+) -> Result<(), String> {
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read {:?}: {}", dir, e))?;
+    let mut buf = vec![0u8; 64 * 1024];
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if skip.contains(&path) {
+            continue;
+        }
+        if path.is_dir() {
+            zip_tree(zip, root, &path, prefix, skip, copied, &mut *progress)?;
+            continue;
+        }
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+        let name = format!("{}{}", prefix, relative);
+        let mut file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) => {
+                warn!("⚠️ Leaving {:?} out of the backup: {}", path, e);
+                continue;
+            }
+        };
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to backup: {}", name, e))?;
+        loop {
+            let n = file.read(&mut buf).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+            if n == 0 {
+                break;
+            }
+            zip.write_all(&buf[..n]).map_err(|e| format!("Failed to back up {}: {}", name, e))?;
+            *copied += n as u64;
+            progress(*copied);
+        }
+    }
+    Ok(())
+}
+
+/// Zip the data dir, and the backend's under `backend/`, to
+/// `backups/<prefix>-<ts>.zip`. `backups/` itself is always skipped, plus
+/// any top-level folders named in `exclude`. `on_start` receives the
+/// expected total before copying begins.
+pub fn write_backup(
+    paths: &AppPaths,
+    prefix: &str,
+    exclude: &[&str],
+    on_start: impl FnOnce(Option<u64>),
+    progress: Progress,
+) -> Result<PathBuf, String> {
+    let backups = backups_dir(paths);
+    // Other store profiles live under the default profile's root.
+    let mut skip = vec![backups.clone(), paths.root.join(crate::profiles::PROFILES_DIR)];
+    skip.extend(exclude.iter().map(|dir| paths.root.join(dir)));
+    let backend_data = paths.backend_data_dir();
+    // Stored under its own prefix, not as part of the root walk.
+    skip.push(backend_data.clone());
+    let mut trees = vec![(paths.root.clone(), "")];
+    if backend_data.is_dir() {
+        trees.push((backend_data, BACKEND_ARCHIVE_DIR));
+    }
+    on_start(trees.iter().map(|(dir, _)| tree_size(dir, &skip)).sum());
+    zip_to(&backups, prefix, &trees, &skip, progress)
+}
+
+/// Zip just `dir` (e.g. the backend's data dir) to
+/// `backups/<prefix>-<ts>.zip`.
+pub fn write_dir_backup(paths: &AppPaths, dir: &Path, prefix: &str) -> Result<PathBuf, String> {
+    zip_to(&backups_dir(paths), prefix, &[(dir.to_path_buf(), "")], &[], &mut |_| {})
+}
+
+/// Zip each `(dir, archive prefix)` of `trees` into one archive.
+fn zip_to(
+    backups: &Path,
+    prefix: &str,
+    trees: &[(PathBuf, &str)],
+    skip: &[PathBuf],
+    progress: Progress,
+) -> Result<PathBuf, String> {
+    fs::create_dir_all(backups).map_err(|e| format!("Failed to create {:?}: {}", backups, e))?;
+    let target = backups.join(format!(
+        "{}-{}.zip",
+        prefix,
+        crate::timestamps::file_stamp()
+    ));
+
+    let file = File::create(&target).map_err(|e| format!("Failed to create {:?}: {}", target, e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let mut copied = 0;
+    let written = trees
+        .iter()
+        .try_for_each(|(root, name)| zip_tree(&mut zip, root, root, name, skip, &mut copied, &mut *progress))
+        .and_then(|_| zip.finish().map(|_| ()).map_err(|e| format!("Failed to finish backup: {}", e)));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&target);
+        return Err(e);
+    }
+    Ok(target)
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct PruneReport {
+    pub pruned: u32,
+    pub bytes_freed: u64,
+}
+
+/// When a backup was taken, from the `<prefix>-<stamp>.zip` name. `None`
+/// for older local-time names, which fall back to the file's mtime.
+fn backup_time(path: &Path) -> Option<SystemTime> {
+    let stem = path.file_stem()?.to_str()?;
+    let (_, stamp) = stem.rsplit_once('-')?;
+    crate::timestamps::parse_file_stamp(stamp).map(SystemTime::from)
+}
+
+/// Apply the retention policy to `backups/*.zip`.
+pub fn prune_backups(paths: &AppPaths, policy: &BackupSettings) -> PruneReport {
+    let mut report = PruneReport::default();
+    let Ok(entries) = fs::read_dir(backups_dir(paths)) else { return report };
+
+    let mut backups: Vec<(SystemTime, u64, PathBuf)> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "zip"))
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            Some((backup_time(&e.path()).or(meta.modified().ok())?, meta.len(), e.path()))
+        })
+        .collect();
+    backups.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let max_age = Duration::from_secs(policy.retention_days * 24 * 60 * 60);
+    let now = SystemTime::now();
+    // Index 0 is the newest backup and is always kept.
+    for (i, (modified, size, path)) in backups.iter().enumerate().skip(1) {
+        let too_many = policy.max_backups > 0 && i >= policy.max_backups;
+        let too_old = policy.retention_days > 0
+            && now.duration_since(*modified).unwrap_or_default() > max_age;
+        if !(too_many || too_old) {
+            continue;
+        }
+        match fs::remove_file(path) {
+            Ok(_) => {
+                info!("🗑️ Pruned backup {:?}", path);
+                report.pruned += 1;
+                report.bytes_freed += size;
+            }
+            Err(e) => warn!("⚠️ Could not prune backup {:?}: {}", path, e),
+        }
+    }
+
+    info!(
+        "🧹 Backup retention: {} pruned, {} bytes reclaimed",
+        report.pruned, report.bytes_freed
+    );
+    report
+}
+
+#[derive(Clone, Serialize)]
+pub struct BackupResult {
+    pub path: String,
+    pub size: u64,
+    pub pruned: PruneReport,
+}
+
+/// Minimum gap between `backup-progress` events while copying.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Serialize)]
+struct BackupProgress {
+    /// `scanning`, `copying` or `pruning`.
+    phase: &'static str,
+    bytes_copied: u64,
+    /// `None` when the size couldn't be measured (indeterminate progress).
+    total_bytes: Option<u64>,
+}
+
+#[derive(Clone, Serialize)]
+struct BackupFailed {
+    error: String,
+}
+
+fn run_backup(app: &tauri::AppHandle, paths: &AppPaths, policy: &BackupSettings) -> Result<BackupResult, String> {
+    let emit = |phase, bytes_copied, total_bytes| {
+        let progress = BackupProgress { phase, bytes_copied, total_bytes };
+        crate::event_throttle::emit_coalesced(app, "backup-progress", progress);
+    };
+
+    info!("💾 Creating backup...");
+    emit("scanning", 0, None);
+    let total = Cell::new(None);
+    let mut last_emit = Instant::now();
+    let path = write_backup(
+        paths,
+        "backup",
+        &EXCLUDED_FROM_BACKUP,
+        |size| {
+            total.set(size);
+            emit("copying", 0, size);
+        },
+        &mut |copied| {
+            if last_emit.elapsed() >= PROGRESS_INTERVAL {
+                last_emit = Instant::now();
+                emit("copying", copied, total.get());
+            }
+        },
+    )?;
+    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    info!("✅ Backup written to {:?} ({} bytes)", path, size);
+
+    emit("pruning", total.get().unwrap_or(0), total.get());
+    let pruned = prune_backups(paths, policy);
+    Ok(BackupResult { path: path.display().to_string(), size, pruned })
+}
+
+/// Back up the data dir now, then apply retention.
+#[tauri::command]
+pub async fn create_backup(app_handle: tauri::AppHandle) -> Result<BackupResult, AppError> {
+    let paths = app_handle.state::<AppPaths>().inner().clone();
+    disk::ensure_space(&app_handle, &paths.root, MIN_BACKUP_FREE_SPACE, "backup").map_err(AppError::Io)?;
+    let policy = app_handle.state::<SettingsState>().get().backups;
+
+    let app = app_handle.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        crate::db_checkpoint::checkpoint_before_backup(&app);
+        run_backup(&app, &paths, &policy)
+    })
+        .await
+        .map_err(|e| format!("Backup failed: {}", e))
+        .and_then(|r| r);
+
+    match &result {
+        Ok(done) => {
+            let _ = app_handle.emit("backup-complete", done.clone());
+        }
+        Err(e) => {
+            error!("❌ Backup failed: {}", e);
+            let _ = app_handle.emit("backup-failed", BackupFailed { error: e.clone() });
+        }
+    }
+    result.map_err(AppError::Io)
+}
+
+/// Apply the retention policy without taking a new backup.
+#[tauri::command]
+pub fn prune_backups_now(app_handle: tauri::AppHandle) -> PruneReport {
+    let policy = app_handle.state::<SettingsState>().get().backups;
+    let report = prune_backups(&app_handle.state::<AppPaths>(), &policy);
+    let _ = crate::audit_log::privileged(&app_handle, "prune_backups_now", Ok(()));
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A data dir whose `backups/` holds one archive per entry of `ages`
+    /// (in days), returned with their names in the same order.
+    fn backups_aged(name: &str, ages: &[i64]) -> (AppPaths, Vec<String>) {
+        let root = std::env::temp_dir().join(format!("siri-backups-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let dir = root.join(BACKUPS_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        let names: Vec<String> = ages
+            .iter()
+            .map(|days| {
+                let taken = chrono::Utc::now() - chrono::Duration::days(*days);
+                let name = format!("backup-{}.zip", taken.format(crate::timestamps::FILE_STAMP_FORMAT));
+                fs::write(dir.join(&name), b"zip").unwrap();
+                name
+            })
+            .collect();
+        let paths = AppPaths { root: root.clone(), base: root, profile: "default".to_string() };
+        (paths, names)
+    }
+
+    fn remaining(paths: &AppPaths) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(backups_dir(paths))
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn sorted(names: &[&String]) -> Vec<String> {
+        let mut names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn prune_keeps_the_newest_max_backups() {
+        let (paths, names) = backups_aged("count", &[0, 1, 2, 3]);
+        let report = prune_backups(&paths, &BackupSettings { max_backups: 2, retention_days: 0 });
+        assert_eq!(report.pruned, 2);
+        assert_eq!(remaining(&paths), sorted(&[&names[0], &names[1]]));
+        let _ = fs::remove_dir_all(&paths.root);
+    }
+
+    #[test]
+    fn prune_removes_backups_older_than_retention() {
+        let (paths, names) = backups_aged("age", &[0, 5, 20, 40]);
+        let report = prune_backups(&paths, &BackupSettings { max_backups: 0, retention_days: 10 });
+        assert_eq!(report.pruned, 2);
+        assert_eq!(remaining(&paths), sorted(&[&names[0], &names[1]]));
+        let _ = fs::remove_dir_all(&paths.root);
+    }
+
+    #[test]
+    fn prune_applies_whichever_limit_is_stricter() {
+        // The count keeps three, the age only two.
+        let (paths, names) = backups_aged("both-age", &[0, 5, 20, 40]);
+        prune_backups(&paths, &BackupSettings { max_backups: 3, retention_days: 10 });
+        assert_eq!(remaining(&paths), sorted(&[&names[0], &names[1]]));
+        let _ = fs::remove_dir_all(&paths.root);
+
+        // The age keeps all four, the count only one.
+        let (paths, names) = backups_aged("both-count", &[0, 1, 2, 3]);
+        prune_backups(&paths, &BackupSettings { max_backups: 1, retention_days: 10 });
+        assert_eq!(remaining(&paths), sorted(&[&names[0]]));
+        let _ = fs::remove_dir_all(&paths.root);
+    }
+
+    #[test]
+    fn prune_never_deletes_the_newest_backup() {
+        let (paths, names) = backups_aged("newest", &[400]);
+        let report = prune_backups(&paths, &BackupSettings { max_backups: 1, retention_days: 1 });
+        assert_eq!(report.pruned, 0);
+        assert_eq!(remaining(&paths), sorted(&[&names[0]]));
+        let _ = fs::remove_dir_all(&paths.root);
+    }
+}
@@ -0,0 +1,79 @@
+//! Extra root certificates for update checks/downloads and the network
+//! probe, for networks that terminate TLS with a private or corporate CA.
+//!
+//! `network.ca_certificates` names a PEM file holding one or more
+//! `CERTIFICATE` blocks; they are trusted in addition to the built-in
+//! roots. A file that can't be read, holds no certificates or has a block
+//! that doesn't decode is rejected as a whole: settings validation refuses
+//! it and update checks fail with the reason rather than quietly
+//! connecting without it.
+
+use std::fs;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use log::{error, info};
+use tauri::Manager;
+
+use crate::settings::SettingsState;
+
+const BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+const END: &str = "-----END CERTIFICATE-----";
+
+/// Read `path` and return the DER bytes of every certificate in it.
+pub fn load(path: &str) -> Result<Vec<Vec<u8>>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read CA certificates {}: {}", path, e))?;
+
+    let mut certs = Vec::new();
+    // rustls only parses the certificates when a client is built.
+    let mut check = reqwest::Client::builder();
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find(BEGIN) {
+        let body = &rest[start + BEGIN.len()..];
+        let end = body
+            .find(END)
+            .ok_or_else(|| format!("{}: certificate {} has no END line", path, certs.len() + 1))?;
+        let encoded: String = body[..end].split_whitespace().collect();
+        let der = STANDARD
+            .decode(encoded)
+            .map_err(|e| format!("{}: certificate {} is not valid base64: {}", path, certs.len() + 1, e))?;
+        let cert = reqwest::Certificate::from_der(&der)
+            .map_err(|e| format!("{}: certificate {} is invalid: {}", path, certs.len() + 1, e))?;
+        check = check.add_root_certificate(cert);
+        certs.push(der);
+        rest = &body[end + END.len()..];
+    }
+    if certs.is_empty() {
+        return Err(format!("{} contains no PEM certificates", path));
+    }
+    check
+        .build()
+        .map_err(|e| format!("{}: certificates were rejected: {}", path, e))?;
+    Ok(certs)
+}
+
+/// DER bytes of the configured extra roots; empty when none are set.
+pub fn configured(app: &tauri::AppHandle) -> Result<Vec<Vec<u8>>, String> {
+    match app.state::<SettingsState>().get().network.ca_certificates {
+        Some(path) if !path.trim().is_empty() => load(path.trim()),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// The configured extra roots as `reqwest` certificates.
+pub fn reqwest_certs(app: &tauri::AppHandle) -> Result<Vec<reqwest::Certificate>, String> {
+    configured(app)?
+        .iter()
+        .map(|der| reqwest::Certificate::from_der(der).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// Log the extra roots in effect; called once at startup.
+pub fn log_ca_certificates(app: &tauri::AppHandle) {
+    match configured(app) {
+        Ok(certs) if certs.is_empty() => {}
+        Ok(certs) => info!("🔒 Loaded {} extra root certificate(s) for outbound TLS", certs.len()),
+        Err(e) => error!("❌ Extra root certificates not loaded; update checks will fail: {}", e),
+    }
+}
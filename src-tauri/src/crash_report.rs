@@ -0,0 +1,131 @@
+//! Panic reports for the shell.
+//!
+//! Release builds have no console, so a panic's stderr output goes nowhere.
+//! The hook installed at the top of `main` logs the message, location and a
+//! backtrace through `log`, and once the data dir is known also writes them
+//! with the app version and OS to `crash-<ts>.txt` in it.
+//!
+//! At the next start, reports left in the data dir are moved to `crashes/`
+//! and the user is offered to open that folder, so each crash is raised once.
+
+use std::backtrace::Backtrace;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use log::{error, info, warn};
+
+use crate::paths::AppPaths;
+
+/// Where new reports are written; unset until setup resolves the data dir.
+static CRASH_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+const REPORT_PREFIX: &str = "crash-";
+const ARCHIVE_DIR: &str = "crashes";
+
+fn payload_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+fn report(message: &str, location: &str) -> String {
+    let thread = std::thread::current().name().unwrap_or("unnamed").to_string();
+    format!(
+        "Siri Billing {} on {} ({})\nSession: {}\nTime: {}\nThread: {}\nPanic at {}: {}\n\nBacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        crate::session::session_id(),
+        chrono::Utc::now().to_rfc3339(),
+        thread,
+        location,
+        message,
+        Backtrace::force_capture(),
+    )
+}
+
+/// Install the panic hook. Call before anything that could panic.
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let location = info.location().map_or("unknown location".to_string(), |l| l.to_string());
+        let report = report(&payload_message(info.payload()), &location);
+        error!("💥 Panic: {}", report);
+        if let Some(dir) = CRASH_DIR.get() {
+            let path = dir.join(format!("{}{}.txt", REPORT_PREFIX, crate::timestamps::file_stamp()));
+            match fs::write(&path, &report) {
+                Ok(()) => error!("💥 Crash report written to {:?}", path),
+                Err(e) => error!("❌ Failed to write crash report {:?}: {}", path, e),
+            }
+        }
+        default_hook(info);
+    }));
+}
+
+/// Start writing reports to the data dir.
+pub fn set_dir(paths: &AppPaths) {
+    let _ = CRASH_DIR.set(paths.root.clone());
+}
+
+/// Reports from earlier runs that haven't been raised yet.
+fn pending_reports(dir: &Path) -> Vec<PathBuf> {
+    let Ok(read_dir) = fs::read_dir(dir) else { return Vec::new() };
+    read_dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path.extension().is_some_and(|ext| ext == "txt")
+                && path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with(REPORT_PREFIX))
+        })
+        .collect()
+}
+
+/// Move reports left by earlier runs to `crashes/` and offer to open it.
+pub fn offer_reports(app: &tauri::AppHandle) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    let Some(dir) = CRASH_DIR.get() else { return };
+    let reports = pending_reports(dir);
+    if reports.is_empty() {
+        return;
+    }
+
+    let archive = dir.join(ARCHIVE_DIR);
+    if let Err(e) = fs::create_dir_all(&archive) {
+        warn!("⚠️ Failed to create {:?}: {}", archive, e);
+        return;
+    }
+    for path in &reports {
+        let dest = archive.join(path.file_name().unwrap_or_default());
+        if let Err(e) = fs::rename(path, &dest) {
+            warn!("⚠️ Failed to move crash report {:?}: {}", path, e);
+        }
+    }
+    warn!("💥 {} crash report(s) from earlier runs moved to {:?}", reports.len(), archive);
+
+    app.dialog()
+        .message(format!(
+            "Siri Billing closed unexpectedly last time. {} crash report(s) were saved to:\n\n{}\n\n\
+             Please send them to support.",
+            reports.len(),
+            archive.display()
+        ))
+        .title("Siri Billing")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom("Open folder".to_string(), "Close".to_string()))
+        .show(move |open| {
+            if !open {
+                return;
+            }
+            info!("📂 Opening crash reports folder {:?}", archive);
+            if let Err(e) = crate::open_folder(&archive) {
+                warn!("⚠️ {}", e);
+            }
+        });
+}
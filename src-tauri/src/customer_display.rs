@@ -0,0 +1,169 @@
+//! Customer-facing display on a second monitor.
+//!
+//! `enable_customer_display` opens a borderless fullscreen window showing
+//! `customer-display.html` on the chosen monitor and persists the choice in
+//! `window.customer_display`, so it comes back after a restart. The main
+//! frontend drives it by emitting `customer-display-update` with the cart
+//! (items, total); the shell keeps the last payload and replays it whenever
+//! the display page (re)loads.
+//!
+//! A watcher checks the monitor every few seconds. When it disappears the
+//! window is hidden rather than left stranded on a missing screen, and it is
+//! shown again once the monitor is back.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde_json::Value;
+use tauri::webview::PageLoadEvent;
+use tauri::window::Monitor;
+use tauri::{Emitter, Listener, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::error::AppError;
+use crate::settings::SettingsState;
+
+pub const CUSTOMER_DISPLAY_LABEL: &str = "customer-display";
+pub const UPDATE_EVENT: &str = "customer-display-update";
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+pub struct CustomerDisplayState {
+    /// Last `customer-display-update` payload, replayed on page load.
+    last: Mutex<Option<Value>>,
+    /// Bumped on every enable/disable so a stale watcher exits.
+    generation: AtomicU64,
+}
+
+/// Cache cart updates from the main frontend. Call once during setup.
+pub fn init(app: &tauri::AppHandle) {
+    let handle = app.clone();
+    app.listen(UPDATE_EVENT, move |event| {
+        if let Ok(payload) = serde_json::from_str::<Value>(event.payload()) {
+            *handle.state::<CustomerDisplayState>().last.lock().unwrap() = Some(payload);
+        }
+    });
+}
+
+fn monitor_at(app: &tauri::AppHandle, index: usize) -> Option<Monitor> {
+    app.available_monitors().ok()?.into_iter().nth(index)
+}
+
+fn place(window: &WebviewWindow, monitor: &Monitor) -> tauri::Result<()> {
+    window.set_fullscreen(false)?;
+    window.set_position(*monitor.position())?;
+    window.set_size(*monitor.size())?;
+    window.set_fullscreen(true)?;
+    window.show()
+}
+
+fn open(app: &tauri::AppHandle, index: usize) -> Result<(), AppError> {
+    let monitor = monitor_at(app, index)
+        .ok_or_else(|| AppError::NotFound(format!("Monitor {} is not connected.", index)))?;
+    let window = match app.get_webview_window(CUSTOMER_DISPLAY_LABEL) {
+        Some(existing) => existing,
+        None => build(app)?,
+    };
+    place(&window, &monitor).map_err(|e| format!("Failed to place customer display: {}", e))?;
+
+    let generation = app.state::<CustomerDisplayState>().generation.fetch_add(1, Ordering::SeqCst) + 1;
+    watch(app.clone(), index, generation);
+    info!(
+        "🖥️ Customer display on monitor {} ({})",
+        index,
+        monitor.name().map(String::as_str).unwrap_or("unnamed")
+    );
+    Ok(())
+}
+
+fn build(app: &tauri::AppHandle) -> Result<WebviewWindow, String> {
+    WebviewWindowBuilder::new(
+        app,
+        CUSTOMER_DISPLAY_LABEL,
+        WebviewUrl::App("customer-display.html".into()),
+    )
+    .title("Customer Display")
+    .visible(false)
+    .decorations(false)
+    .resizable(false)
+    .focused(false)
+    .skip_taskbar(true)
+    .on_page_load(|window, payload| {
+        if payload.event() == PageLoadEvent::Finished {
+            let app = window.app_handle();
+            let last = app.state::<CustomerDisplayState>().last.lock().unwrap().clone();
+            if let Some(last) = last {
+                let _ = app.emit_to(CUSTOMER_DISPLAY_LABEL, UPDATE_EVENT, last);
+            }
+        }
+    })
+    .build()
+    .map_err(|e| format!("Failed to create customer display window: {}", e))
+}
+
+/// Hide the window while its monitor is gone and bring it back when the
+/// monitor returns.
+fn watch(app: tauri::AppHandle, index: usize, generation: u64) {
+    thread::spawn(move || {
+        let mut connected = true;
+        loop {
+            thread::sleep(WATCH_INTERVAL);
+            if app.state::<CustomerDisplayState>().generation.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            let Some(window) = app.get_webview_window(CUSTOMER_DISPLAY_LABEL) else { return };
+            match (monitor_at(&app, index), connected) {
+                (None, true) => {
+                    warn!("⚠️ Customer display monitor {} disconnected; hiding the display", index);
+                    let _ = window.hide();
+                    connected = false;
+                }
+                (Some(monitor), false) => {
+                    info!("🖥️ Customer display monitor {} reconnected", index);
+                    if let Err(e) = place(&window, &monitor) {
+                        warn!("⚠️ Failed to restore customer display: {}", e);
+                    }
+                    connected = true;
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+/// Reopen the customer display if one was configured. Call during setup.
+pub fn restore(app: &tauri::AppHandle) {
+    let Some(index) = app.state::<SettingsState>().get().window.customer_display else { return };
+    if let Err(e) = open(app, index) {
+        warn!("⚠️ Customer display not restored: {}", e);
+    }
+}
+
+/// Show the customer display on monitor `monitor_index` (as ordered by the
+/// OS) and remember the choice.
+#[tauri::command]
+pub async fn enable_customer_display(
+    app_handle: tauri::AppHandle,
+    monitor_index: usize,
+) -> Result<(), AppError> {
+    open(&app_handle, monitor_index)?;
+    app_handle
+        .state::<SettingsState>()
+        .update(serde_json::json!({ "window": { "customer_display": monitor_index } }))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn disable_customer_display(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    app_handle.state::<CustomerDisplayState>().generation.fetch_add(1, Ordering::SeqCst);
+    if let Some(window) = app_handle.get_webview_window(CUSTOMER_DISPLAY_LABEL) {
+        window.close().map_err(|e| format!("Failed to close customer display: {}", e))?;
+    }
+    app_handle
+        .state::<SettingsState>()
+        .update(serde_json::json!({ "window": { "customer_display": null } }))?;
+    info!("🖥️ Customer display disabled");
+    Ok(())
+}
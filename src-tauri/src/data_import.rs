@@ -0,0 +1,155 @@
+//! Bulk import of products and customers from a previous POS export.
+//!
+//! The shell only validates the file and streams it to the backend's
+//! `POST /api/import/<kind>` endpoint, which parses rows and writes them;
+//! the file is never loaded into memory here, so large exports are fine.
+
+use std::path::Path;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::backend::BackendState;
+use crate::backend_transport::{BackendRequest, BackendTransport, FileBody};
+use crate::error::AppError;
+
+const IMPORT_KINDS: [&str; 2] = ["products", "customers"];
+/// Imports of tens of thousands of rows can take a while server-side.
+const IMPORT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportReport {
+    pub inserted: u64,
+    pub skipped: u64,
+    /// Per-row problems reported by the backend, e.g. `row 12: missing price`.
+    pub errors: Vec<String>,
+}
+
+fn content_type(path: &Path) -> Result<&'static str, String> {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "csv" => Ok("text/csv"),
+        "xlsx" => Ok("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        "xls" => Ok("application/vnd.ms-excel"),
+        _ => Err(format!("Unsupported file type '.{}' (expected .csv, .xlsx or .xls)", ext)),
+    }
+}
+
+fn upload(transport: &dyn BackendTransport, path: &Path, kind: &str) -> Result<ImportReport, String> {
+    let content_type = content_type(path)?;
+    let size = std::fs::metadata(path).map_err(|e| format!("Cannot open {:?}: {}", path, e))?.len();
+    if size == 0 {
+        return Err("The import file is empty.".to_string());
+    }
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    info!("📥 Importing {} from {:?} ({} bytes)", kind, path, size);
+    let file = FileBody { path: path.to_path_buf(), content_type, file_name };
+    let response = transport
+        .request(&BackendRequest::upload(&format!("/api/import/{}", kind), file, IMPORT_TIMEOUT))
+        .map_err(|e| format!("Backend import request failed: {}", e))?;
+
+    if !response.is_success() {
+        return Err(format!("Backend rejected the import (HTTP {}): {}", response.status, response.body.trim()));
+    }
+    serde_json::from_str(&response.body).map_err(|e| format!("Unexpected import response: {}", e))
+}
+
+async fn import(app_handle: tauri::AppHandle, path: String, kind: String) -> Result<ImportReport, AppError> {
+    if !IMPORT_KINDS.contains(&kind.as_str()) {
+        return Err(AppError::Validation(format!(
+            "Unknown import kind '{}' (expected products or customers)",
+            kind
+        )));
+    }
+    let path = Path::new(&path).to_path_buf();
+    if !path.is_file() {
+        return Err(AppError::NotFound(format!("File not found: {:?}", path)));
+    }
+    content_type(&path).map_err(AppError::Validation)?;
+
+    let transport = app_handle.state::<BackendState>().transport();
+    let report = tauri::async_runtime::spawn_blocking(move || upload(&*transport, &path, &kind))
+        .await
+        .map_err(|e| format!("Import failed: {}", e))?;
+
+    match &report {
+        Ok(r) => info!(
+            "✅ Import finished: {} inserted, {} skipped, {} error(s)",
+            r.inserted, r.skipped, r.errors.len()
+        ),
+        Err(e) => warn!("⚠️ Import failed: {}", e),
+    }
+    report.map_err(AppError::Backend)
+}
+
+/// Import `path` as `kind` (`products` or `customers`).
+#[tauri::command]
+pub async fn import_data(
+    app_handle: tauri::AppHandle,
+    path: String,
+    kind: String,
+) -> Result<ImportReport, AppError> {
+    let result = import(app_handle.clone(), path, kind).await;
+    crate::audit_log::privileged(&app_handle, "import_data", result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::backend_transport::{BackendResponse, MockTransport};
+
+    use super::*;
+
+    /// `contents` written to a fresh `name` under the system temp dir.
+    fn import_file(name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("siri-import-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn upload_streams_the_file_and_parses_the_report() {
+        let path = import_file("new products.csv", "name,price\nSaree,1499\n");
+        let transport = MockTransport::new(|_| {
+            Ok(BackendResponse { status: 200, body: r#"{"inserted":1,"skipped":0,"errors":[]}"#.to_string() })
+        });
+        let report = upload(&transport, &path, "products").unwrap();
+        assert_eq!(report.inserted, 1);
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/api/import/products");
+        let file = requests[0].file.as_ref().unwrap();
+        assert_eq!(file.path, path);
+        assert_eq!(file.content_type, "text/csv");
+        assert_eq!(file.file_name, "new products.csv");
+    }
+
+    #[test]
+    fn upload_reports_a_rejected_import() {
+        let path = import_file("customers.csv", "name\nRavi\n");
+        let transport =
+            MockTransport::new(|_| Ok(BackendResponse { status: 422, body: "bad header\n".to_string() }));
+        let error = upload(&transport, &path, "customers").unwrap_err();
+        assert_eq!(error, "Backend rejected the import (HTTP 422): bad header");
+    }
+
+    #[test]
+    fn upload_refuses_an_empty_file_without_a_request() {
+        let path = import_file("empty.csv", "");
+        let transport = MockTransport::new(|_| panic!("no request expected"));
+        assert!(upload(&transport, &path, "products").is_err());
+        assert!(transport.requests().is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+//! Checkpointing the backend's database before it is copied.
+//!
+//! A database in WAL mode keeps recent writes in its `-wal` file, so a
+//! plain file copy of the main file can miss them. `checkpoint_db` asks the
+//! backend (`POST /api/db/checkpoint`) to run
+//! `PRAGMA wal_checkpoint(TRUNCATE)`, which folds the WAL into the main
+//! file and empties it. The backend answers with the pragma's result
+//! `{ busy, log_frames, checkpointed_frames }`; a 404 means the backend
+//! keeps no database (today's backend stores JSON files) and there is
+//! nothing to checkpoint.
+//!
+//! The checkpoint runs automatically before `create_backup` and before the
+//! pre-migration backup. There, a failure is only logged: the backup still
+//! goes ahead.
+
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::backend::BackendState;
+use crate::backend_transport::BackendRequest;
+use crate::error::AppError;
+
+const CHECKPOINT_PATH: &str = "/api/db/checkpoint";
+/// Truncating waits for readers to finish, so allow more than a ping.
+const CHECKPOINT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Checkpoint {
+    /// Non-zero when readers or writers kept the checkpoint from finishing.
+    pub busy: i64,
+    /// Frames in the WAL before the checkpoint.
+    pub log_frames: i64,
+    /// Frames written back to the main database file.
+    pub checkpointed_frames: i64,
+}
+
+/// Run the checkpoint. `Ok(None)` when the backend has no database.
+pub fn checkpoint(state: &BackendState) -> Result<Option<Checkpoint>, String> {
+    let response = state.transport().request(&BackendRequest::post(
+        CHECKPOINT_PATH,
+        Some(serde_json::json!({ "mode": "TRUNCATE" })),
+        CHECKPOINT_TIMEOUT,
+    ))?;
+    if response.status == 404 {
+        return Ok(None);
+    }
+    if !response.is_success() {
+        return Err(format!("HTTP {}: {}", response.status, response.body.trim()));
+    }
+    let result: Checkpoint = serde_json::from_str(&response.body)
+        .map_err(|e| format!("Invalid checkpoint response: {}", e))?;
+
+    if result.busy != 0 {
+        warn!(
+            "⚠️ WAL checkpoint incomplete: {}/{} frames written, database busy",
+            result.checkpointed_frames, result.log_frames
+        );
+    } else {
+        info!("🗄️ WAL checkpoint: {} frame(s) written back", result.checkpointed_frames);
+    }
+    Ok(Some(result))
+}
+
+/// Checkpoint before a backup copies the data. Never fails the backup.
+pub fn checkpoint_before_backup(app: &tauri::AppHandle) {
+    let state = app.state::<BackendState>();
+    if crate::backend::phase(app) != "ready" {
+        return;
+    }
+    match checkpoint(&state) {
+        Ok(Some(_)) => {}
+        Ok(None) => info!("🗄️ Backend has no database to checkpoint"),
+        Err(e) => warn!("⚠️ WAL checkpoint before backup failed; backing up anyway: {}", e),
+    }
+}
+
+/// Fold the backend's WAL into its main database file.
+#[tauri::command]
+pub async fn checkpoint_db(app_handle: tauri::AppHandle) -> Result<Option<Checkpoint>, AppError> {
+    crate::backend::ensure_ready(&app_handle, "checkpoint_db")?;
+    tauri::async_runtime::spawn_blocking(move || checkpoint(&app_handle.state::<BackendState>()))
+        .await
+        .map_err(|e| format!("Checkpoint task failed: {}", e))?
+        .map_err(AppError::Backend)
+}
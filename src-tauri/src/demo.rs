@@ -0,0 +1,267 @@
+//! Demo/sandbox mode for sales demos.
+//!
+//! The backend keeps its data as JSON files in the profile's
+//! `backend-data/`. `start_demo_mode` snapshots that live `json/` into
+//! `demo/snapshot/`, seeds `demo/data/json` with sample products and
+//! restarts the backend with `SIRI_DATA_DIR` pointing at the demo copy and
+//! `SUPABASE_URL` blanked, so demo bills never reach the cloud.
+//! If anything fails once the backend is stopped, it is started again on
+//! the live data. The live data isn't touched during a demo, so
+//! `end_demo_mode` only stops the backend, discards everything under
+//! `demo/` and starts it again; the snapshot is copied back only if the
+//! live `json/` has gone missing.
+//!
+//! `demo.json` in the data root marks demo mode as active, so a crash or
+//! restart mid-demo comes back in demo mode rather than silently live.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::{error, info, warn};
+use serde::Serialize;
+use serde_json::json;
+use tauri::{Emitter, Manager};
+
+use crate::backend::{self, BackendState};
+use crate::error::AppError;
+use crate::paths::{self, AppPaths};
+use crate::restart_schedule::RestartScheduler;
+
+const DEMO_DIR: &str = "demo";
+const DEMO_MARKER: &str = "demo.json";
+/// Backend queues of transactions not yet synced; demo mode waits for them.
+const PENDING_QUEUES: [&str; 4] = [
+    "offline_bill_queue.json",
+    "offline_damage_return_queue.json",
+    "offline_return_order_queue.json",
+    "offline_transfer_verification_queue.json",
+];
+
+#[derive(Default)]
+pub struct DemoState {
+    active: AtomicBool,
+}
+
+#[derive(Clone, Serialize)]
+struct DemoModeChanged {
+    active: bool,
+}
+
+fn demo_root(app: &tauri::AppHandle) -> PathBuf {
+    app.state::<AppPaths>().root.join(DEMO_DIR)
+}
+
+fn marker_path(app: &tauri::AppHandle) -> PathBuf {
+    app.state::<AppPaths>().root.join(DEMO_MARKER)
+}
+
+/// The backend's live `json/` store for the active profile.
+fn live_json_dir(app: &tauri::AppHandle) -> PathBuf {
+    app.state::<AppPaths>().backend_data_dir().join("json")
+}
+
+pub fn is_active(app: &tauri::AppHandle) -> bool {
+    app.state::<DemoState>().active.load(Ordering::SeqCst)
+}
+
+/// The backend's data dir while demo mode is active.
+pub fn data_dir(app: &tauri::AppHandle) -> Option<PathBuf> {
+    is_active(app).then(|| demo_root(app).join("data"))
+}
+
+/// Environment for the backend sidecar while demo mode is active.
+pub fn backend_env(app: &tauri::AppHandle) -> Vec<(&'static str, String)> {
+    if !is_active(app) {
+        return Vec::new();
+    }
+    vec![("SUPABASE_URL", String::new())]
+}
+
+/// Pick up demo mode left active by a previous run. Call before the
+/// backend starts.
+pub fn restore_state(app: &tauri::AppHandle) {
+    if marker_path(app).exists() {
+        app.state::<DemoState>().active.store(true, Ordering::SeqCst);
+        warn!("🎭 DEMO MODE ACTIVE — backend will use demo data, not live data");
+    }
+}
+
+/// Number of queued transactions in the live data not yet synced.
+fn pending_transactions(live: &Path) -> usize {
+    PENDING_QUEUES
+        .iter()
+        .filter_map(|name| fs::read_to_string(live.join(name)).ok())
+        .filter_map(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+        .map(|queue| queue.as_array().map_or(0, Vec::len))
+        .sum()
+}
+
+fn sample_products() -> serde_json::Value {
+    let product = |id: &str, name: &str, barcode: &str, price: f64, stock: u32| {
+        json!({
+            "id": id,
+            "name": name,
+            "barcode": barcode,
+            "price": price,
+            "selling_price": price,
+            "stock": stock,
+            "tax": 18,
+        })
+    };
+    json!([
+        product("demo-1", "Demo Cotton Saree", "8900000000011", 1499.0, 25),
+        product("demo-2", "Demo Silk Kurta", "8900000000028", 899.0, 40),
+        product("demo-3", "Demo Leggings", "8900000000035", 349.0, 60),
+        product("demo-4", "Demo Dupatta", "8900000000042", 299.0, 50),
+        product("demo-5", "Demo Gift Box", "8900000000059", 99.0, 100),
+    ])
+}
+
+fn set_active(app: &tauri::AppHandle, active: bool) {
+    app.state::<DemoState>().active.store(active, Ordering::SeqCst);
+    let _ = app.emit("demo-mode-changed", DemoModeChanged { active });
+    crate::window_title::refresh(app);
+}
+
+/// Snapshot the live data and seed the demo copy.
+fn prepare_demo_data(app: &tauri::AppHandle, live: &Path) -> Result<(), AppError> {
+    let root = demo_root(app);
+    if root.exists() {
+        fs::remove_dir_all(&root).map_err(|e| format!("Failed to clear {:?}: {}", root, e))?;
+    }
+    if live.is_dir() {
+        paths::copy_dir(live, &root.join("snapshot"))
+            .map_err(|e| format!("Failed to snapshot live data: {}", e))?;
+    }
+    let demo_json = root.join("data").join("json");
+    fs::create_dir_all(&demo_json).map_err(|e| format!("Failed to create {:?}: {}", demo_json, e))?;
+    let products = serde_json::to_vec_pretty(&sample_products())?;
+    paths::write_atomic(&demo_json.join("products.json"), &products)
+        .map_err(|e| format!("Failed to seed demo products: {}", e))?;
+    paths::write_atomic(&marker_path(app), b"{}")
+        .map_err(|e| format!("Failed to mark demo mode: {}", e))?;
+    Ok(())
+}
+
+/// Undo a half-entered demo and bring the backend back up on live data.
+fn fall_back_to_live(app: &tauri::AppHandle) {
+    app.state::<DemoState>().active.store(false, Ordering::SeqCst);
+    let _ = fs::remove_file(marker_path(app));
+    let root = demo_root(app);
+    if let Err(e) = fs::remove_dir_all(&root) {
+        warn!("⚠️ Failed to remove demo data {:?}: {}", root, e);
+    }
+    match backend::start_backend(app) {
+        Ok(()) => info!("🔁 Backend restarted on live data"),
+        Err(e) => error!("❌ Failed to restart backend on live data: {}", e),
+    }
+}
+
+fn enter(app: &tauri::AppHandle) -> Result<(), AppError> {
+    let live = live_json_dir(app);
+    let pending = pending_transactions(&live);
+    if pending > 0 {
+        return Err(AppError::Conflict(format!(
+            "{} transaction(s) are still waiting to sync. Let them sync before starting a demo.",
+            pending
+        )));
+    }
+
+    backend::stop_backend(&app.state::<BackendState>()).map_err(AppError::Backend)?;
+
+    if let Err(e) = prepare_demo_data(app, &live) {
+        fall_back_to_live(app);
+        return Err(e);
+    }
+    // The flag picks the demo data dir for the spawn; demo mode is only
+    // announced once the backend is up on it.
+    app.state::<DemoState>().active.store(true, Ordering::SeqCst);
+    if let Err(e) = backend::start_backend(app) {
+        fall_back_to_live(app);
+        return Err(AppError::Backend(e));
+    }
+    set_active(app, true);
+    Ok(())
+}
+
+fn leave(app: &tauri::AppHandle) -> Result<(), AppError> {
+    backend::stop_backend(&app.state::<BackendState>()).map_err(AppError::Backend)?;
+
+    let root = demo_root(app);
+    let live = live_json_dir(app);
+    let snapshot = root.join("snapshot");
+    if !live.is_dir() && snapshot.is_dir() {
+        warn!("⚠️ Live data {:?} is missing; restoring it from the demo snapshot", live);
+        if let Err(e) = paths::copy_dir(&snapshot, &live) {
+            // Stay in demo mode rather than start on empty live data.
+            if let Err(e) = backend::start_backend(app) {
+                error!("❌ Failed to restart backend on demo data: {}", e);
+            }
+            return Err(format!("Failed to restore live data snapshot: {}", e).into());
+        }
+    }
+    let _ = fs::remove_file(marker_path(app));
+    set_active(app, false);
+    if let Err(e) = fs::remove_dir_all(&root) {
+        warn!("⚠️ Failed to remove demo data {:?}: {}", root, e);
+    }
+    backend::start_backend(app).map_err(AppError::Backend)
+}
+
+async fn start(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    if is_active(&app_handle) {
+        return Err(AppError::Conflict("Demo mode is already active.".to_string()));
+    }
+    if app_handle.state::<RestartScheduler>().is_sale_in_progress() {
+        return Err(AppError::Conflict("Finish the current sale before starting a demo.".to_string()));
+    }
+
+    info!("=================================================");
+    info!("🎭 Entering demo mode — live data is snapshotted and set aside");
+    info!("=================================================");
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || enter(&app))
+        .await
+        .map_err(|e| format!("Demo mode task failed: {}", e))?
+        .inspect_err(|e| error!("❌ Failed to enter demo mode: {}", e))?;
+    info!("🎭 DEMO MODE ACTIVE");
+    Ok(())
+}
+
+/// Switch the backend to a throwaway demo dataset. Refused while a sale is
+/// open or live transactions are waiting to sync.
+#[tauri::command]
+pub async fn start_demo_mode(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    let result = start(app_handle.clone()).await;
+    crate::audit_log::privileged(&app_handle, "start_demo_mode", result)
+}
+
+async fn end(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    if !is_active(&app_handle) {
+        return Ok(());
+    }
+
+    info!("=================================================");
+    info!("🎭 Leaving demo mode — discarding demo data, restoring live data");
+    info!("=================================================");
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || leave(&app))
+        .await
+        .map_err(|e| format!("Demo mode task failed: {}", e))?
+        .inspect_err(|e| error!("❌ Failed to leave demo mode: {}", e))?;
+    info!("✅ Live data restored; demo mode off");
+    Ok(())
+}
+
+/// Discard the demo dataset and go back to the live data.
+#[tauri::command]
+pub async fn end_demo_mode(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    let result = end(app_handle.clone()).await;
+    crate::audit_log::privileged(&app_handle, "end_demo_mode", result)
+}
+
+#[tauri::command]
+pub fn demo_mode_status(app_handle: tauri::AppHandle) -> bool {
+    is_active(&app_handle)
+}
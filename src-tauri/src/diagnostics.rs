@@ -0,0 +1,289 @@
+//! Support bundles: logs plus basic app/OS info, packed into a zip.
+//!
+//! `export_support_bundle` encrypts the zip with a password so billing data
+//! in the logs never leaves the machine in plaintext. Encrypted bundle format
+//! (all integers big-endian):
+//!
+//! ```text
+//! offset  size  field
+//! 0       8     magic "SIRIBNDL"
+//! 8       1     format version (1)
+//! 9       4     PBKDF2 iteration count
+//! 13      16    PBKDF2 salt
+//! 29      12    AES-GCM nonce
+//! 41      ..    AES-256-GCM ciphertext of the zip, with the 16-byte tag appended
+//! ```
+//!
+//! The key is PBKDF2-HMAC-SHA256(password, salt, iterations) → 32 bytes.
+//! `decrypt_support_bundle` is the companion routine support uses to turn a
+//! `.enc` file back into the original zip.
+//!
+//! `collect_diagnostics` writes the same zip unencrypted as
+//! `diagnostics-<ts>.zip`, for when the operator is handing it to support
+//! directly.
+
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use log::{info, warn};
+use serde::Serialize;
+use sha2::Sha256;
+use tauri::Manager;
+use zip::write::SimpleFileOptions;
+
+use crate::error::AppError;
+use crate::paths::AppPaths;
+use crate::screenshot;
+
+const BUNDLE_MAGIC: &[u8; 8] = b"SIRIBNDL";
+const BUNDLE_VERSION: u8 = 1;
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = 8 + 1 + 4 + SALT_LEN + NONCE_LEN;
+/// Shortest password accepted for an encrypted bundle.
+const MIN_PASSWORD_LEN: usize = 8;
+
+#[derive(Serialize)]
+struct BundleInfo {
+    app_version: String,
+    backend_version: Option<String>,
+    identifier: String,
+    session_id: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    created_at: u64,
+    /// UTC offset of the terminal when the bundle was made.
+    tz: String,
+    system: crate::system_info::SystemInfo,
+}
+
+fn unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Directories that may hold our log files: the data-dir `logs/` and the
+/// `logs/` folder the file log target writes to relative to the CWD.
+pub fn log_dirs(paths: &AppPaths) -> Vec<PathBuf> {
+    let mut dirs = vec![paths.logs_dir()];
+    if let Ok(cwd_logs) = std::env::current_dir().map(|d| d.join("logs")) {
+        if !dirs.contains(&cwd_logs) {
+            dirs.push(cwd_logs);
+        }
+    }
+    dirs
+}
+
+/// Gather the files that go into a support bundle as `(name, bytes)` pairs.
+/// Files that can't be read are skipped rather than failing the bundle.
+pub fn collect_entries(app: &tauri::AppHandle) -> Vec<(String, Vec<u8>)> {
+    let mut entries = Vec::new();
+
+    let info = BundleInfo {
+        app_version: app.package_info().version.to_string(),
+        backend_version: app.state::<crate::backend::BackendState>().version(),
+        identifier: app.config().identifier.clone(),
+        session_id: crate::session::session_id(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        created_at: unix_secs(),
+        tz: crate::timestamps::local_offset(),
+        system: crate::system_info::collect(&app.state::<AppPaths>()),
+    };
+    if let Ok(json) = serde_json::to_vec_pretty(&info) {
+        entries.push(("info.json".to_string(), json));
+    }
+
+    let exits = app.state::<crate::backend::BackendState>().exit_history();
+    if let Ok(json) = serde_json::to_vec_pretty(&exits) {
+        entries.push(("backend-exit-history.json".to_string(), json));
+    }
+
+    let status = crate::backend::backend_status(app.state());
+    if let Ok(json) = serde_json::to_vec_pretty(&status) {
+        entries.push(("backend-status.json".to_string(), json));
+    }
+
+    let paths = app.state::<AppPaths>();
+    for (i, dir) in log_dirs(&paths).iter().enumerate() {
+        let Ok(read_dir) = fs::read_dir(dir) else { continue };
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            match fs::read(&path) {
+                Ok(bytes) => entries.push((format!("logs{}/{}", i, name), bytes)),
+                Err(e) => warn!("⚠️ Skipping unreadable {:?}: {}", path, e),
+            }
+        }
+    }
+
+    for path in screenshot::latest(&paths, screenshot::BUNDLE_SCREENSHOTS) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        match fs::read(&path) {
+            Ok(bytes) => entries.push((format!("screenshots/{}", name), bytes)),
+            Err(e) => warn!("⚠️ Skipping unreadable {:?}: {}", path, e),
+        }
+    }
+
+    entries
+}
+
+/// Pack entries into an in-memory zip.
+pub fn build_zip(entries: &[(String, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    for (name, bytes) in entries {
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to bundle: {}", name, e))?;
+        zip.write_all(bytes)
+            .map_err(|e| format!("Failed to write {} to bundle: {}", name, e))?;
+    }
+
+    let cursor = zip.finish().map_err(|e| format!("Failed to finish bundle: {}", e))?;
+    Ok(cursor.into_inner())
+}
+
+fn derive_key(password: &str, salt: &[u8], iterations: u32) -> Key<Aes256Gcm> {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut key);
+    key.into()
+}
+
+/// Encrypt `plaintext` into the bundle format described in the module docs.
+pub fn encrypt_bundle(plaintext: &[u8], password: &str) -> Result<Vec<u8>, String> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let cipher = Aes256Gcm::new(&derive_key(password, &salt, PBKDF2_ITERATIONS));
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| "Failed to encrypt support bundle".to_string())?;
+
+    let mut out = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    out.extend_from_slice(BUNDLE_MAGIC);
+    out.push(BUNDLE_VERSION);
+    out.extend_from_slice(&PBKDF2_ITERATIONS.to_be_bytes());
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Reverse of `encrypt_bundle`. Fails on a wrong password or tampered file.
+pub fn decrypt_bundle(data: &[u8], password: &str) -> Result<Vec<u8>, String> {
+    if data.len() < HEADER_LEN || &data[..8] != BUNDLE_MAGIC {
+        return Err("Not a Siri Billing support bundle".to_string());
+    }
+    if data[8] != BUNDLE_VERSION {
+        return Err(format!("Unsupported bundle version {}", data[8]));
+    }
+
+    let iterations = u32::from_be_bytes([data[9], data[10], data[11], data[12]]);
+    let salt = &data[13..13 + SALT_LEN];
+    let nonce = Nonce::from_slice(&data[13 + SALT_LEN..HEADER_LEN]);
+
+    let cipher = Aes256Gcm::new(&derive_key(password, salt, iterations));
+    cipher
+        .decrypt(nonce, &data[HEADER_LEN..])
+        .map_err(|_| "Wrong password or corrupted bundle".to_string())
+}
+
+/// Where exported bundles are written: the user's Downloads folder, or the
+/// app data dir when that can't be resolved.
+pub fn export_dir(app: &tauri::AppHandle) -> PathBuf {
+    app.path()
+        .download_dir()
+        .unwrap_or_else(|_| app.state::<AppPaths>().root.clone())
+}
+
+/// Build the diagnostics zip, encrypt it with `password` and write
+/// `support-<ts>.enc`. Returns the written path.
+#[tauri::command]
+pub async fn export_support_bundle(
+    app_handle: tauri::AppHandle,
+    password: String,
+) -> Result<String, AppError> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(AppError::Validation(format!(
+            "Password must be at least {} characters.",
+            MIN_PASSWORD_LEN
+        )));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let entries = collect_entries(&app_handle);
+        let zip = build_zip(&entries)?;
+        let encrypted = encrypt_bundle(&zip, &password)?;
+
+        let dir = export_dir(&app_handle);
+        crate::disk::ensure_space(&app_handle, &dir, encrypted.len() as u64 * 2, "support bundle export")?;
+
+        let path = dir.join(format!("support-{}.enc", crate::timestamps::file_stamp()));
+        fs::write(&path, encrypted)
+            .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+
+        info!("🔐 Encrypted support bundle written to {:?} ({} files)", path, entries.len());
+        crate::recent_exports::record(&app_handle, &path, "support_bundle");
+        Ok(path.display().to_string())
+    })
+    .await
+    .map_err(|e| format!("Support bundle task failed: {}", e))?
+    .map_err(AppError::Io)
+}
+
+/// Build the diagnostics zip and write it to Downloads as
+/// `diagnostics-<ts>.zip`. Returns the written path.
+#[tauri::command]
+pub async fn collect_diagnostics(app_handle: tauri::AppHandle) -> Result<String, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let entries = collect_entries(&app_handle);
+        let zip = build_zip(&entries)?;
+
+        let dir = export_dir(&app_handle);
+        crate::disk::ensure_space(&app_handle, &dir, zip.len() as u64 * 2, "diagnostics export")?;
+
+        let path = dir.join(format!("diagnostics-{}.zip", crate::timestamps::file_stamp()));
+        fs::write(&path, zip).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+
+        let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        info!("🩺 Diagnostics written to {:?} ({} files): {}", path, names.len(), names.join(", "));
+        crate::recent_exports::record(&app_handle, &path, "diagnostics");
+        Ok(path.display().to_string())
+    })
+    .await
+    .map_err(|e| format!("Diagnostics task failed: {}", e))?
+    .map_err(AppError::Io)
+}
+
+/// Decrypt a `.enc` support bundle next to itself as `.zip`. Returns the
+/// path of the decrypted zip.
+#[tauri::command]
+pub async fn decrypt_support_bundle(path: String, password: String) -> Result<String, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let src = Path::new(&path);
+        let data = fs::read(src).map_err(|e| format!("Failed to read {:?}: {}", src, e))?;
+        let zip = decrypt_bundle(&data, &password)?;
+
+        let dest = src.with_extension("zip");
+        fs::write(&dest, zip).map_err(|e| format!("Failed to write {:?}: {}", dest, e))?;
+        info!("🔓 Support bundle decrypted to {:?}", dest);
+        Ok(dest.display().to_string())
+    })
+    .await
+    .map_err(|e| format!("Decrypt task failed: {}", e))?
+    .map_err(AppError::from)
+}
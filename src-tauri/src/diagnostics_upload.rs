@@ -0,0 +1,251 @@
+//! Automatic diagnostics upload for managed fleets.
+//!
+//! Nothing is ever uploaded unless `diagnostics_upload.consent` is on and
+//! `diagnostics_upload.url` is set. Then, when the backend crashes or the
+//! app hits a fatal startup error, the support bundle entries are zipped
+//! into `upload-queue/diag-<ts>-<reason>.zip` and POSTed to the URL as
+//! `application/zip` with `X-Device-Id`, `X-App-Version` and
+//! `X-Upload-Reason` headers. Bundles larger than `max_mb` drop their
+//! screenshots and then keep only the tail of each log.
+//!
+//! Each upload is retried with backoff. A bundle that still can't be sent
+//! (e.g. the terminal is offline) stays queued on disk and is sent by the
+//! next flush: at startup, every `FLUSH_INTERVAL` and on
+//! `upload_diagnostics_now`. The queue keeps at most `MAX_QUEUED` bundles.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use log::{debug, info, warn};
+use reqwest::blocking::Client;
+use serde::Serialize;
+use tauri::Manager;
+
+use crate::error::AppError;
+use crate::event_bus::LifecycleEvent;
+use crate::paths::AppPaths;
+use crate::settings::{DiagnosticsUploadSettings, SettingsState};
+use crate::{ca_certs, diagnostics, proxy};
+
+const QUEUE_DIR: &str = "upload-queue";
+const MAX_QUEUED: usize = 10;
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+const RETRY_DELAYS: [Duration; 3] = [Duration::from_secs(2), Duration::from_secs(10), Duration::from_secs(30)];
+const FLUSH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Default)]
+pub struct DiagnosticsUpload {
+    /// Serializes queue flushes so a bundle is never sent twice.
+    flushing: Mutex<()>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct UploadReport {
+    pub uploaded: usize,
+    /// Bundles still waiting in the queue.
+    pub queued: usize,
+}
+
+fn queue_dir(app: &tauri::AppHandle) -> PathBuf {
+    app.state::<AppPaths>().root.join(QUEUE_DIR)
+}
+
+/// The settings, if uploading is allowed.
+fn allowed(app: &tauri::AppHandle) -> Option<DiagnosticsUploadSettings> {
+    let settings = app.state::<SettingsState>().get().diagnostics_upload;
+    let configured = settings.url.as_deref().is_some_and(|u| !u.trim().is_empty());
+    (settings.consent && configured).then_some(settings)
+}
+
+fn queued(app: &tauri::AppHandle) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(queue_dir(app)) else { return Vec::new() };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "zip"))
+        .collect();
+    // Names start with a sortable timestamp.
+    files.sort();
+    files
+}
+
+/// Zip the bundle entries, shrinking them to fit `cap` bytes.
+fn build_capped(app: &tauri::AppHandle, cap: u64) -> Result<Vec<u8>, String> {
+    let mut entries = diagnostics::collect_entries(app);
+    let zip = diagnostics::build_zip(&entries)?;
+    if zip.len() as u64 <= cap {
+        return Ok(zip);
+    }
+
+    entries.retain(|(name, _)| !name.starts_with("screenshots/"));
+    let zip = diagnostics::build_zip(&entries)?;
+    if zip.len() as u64 <= cap {
+        return Ok(zip);
+    }
+
+    let logs = entries.iter().filter(|(name, _)| name.starts_with("logs")).count().max(1);
+    let per_log = (cap / logs as u64) as usize;
+    for (name, bytes) in entries.iter_mut() {
+        if name.starts_with("logs") && bytes.len() > per_log {
+            bytes.drain(..bytes.len() - per_log);
+        }
+    }
+    let zip = diagnostics::build_zip(&entries)?;
+    if zip.len() as u64 > cap {
+        return Err(format!("bundle is {} bytes even after trimming; cap is {}", zip.len(), cap));
+    }
+    Ok(zip)
+}
+
+/// Build a bundle and add it to the queue. Does nothing without consent.
+pub fn enqueue(app: &tauri::AppHandle, reason: &str) -> Result<(), String> {
+    let Some(settings) = allowed(app) else {
+        debug!("Diagnostics upload not enabled; not queuing a {} bundle", reason);
+        return Ok(());
+    };
+    let zip = build_capped(app, settings.max_mb * 1024 * 1024)?;
+
+    let dir = queue_dir(app);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    let path = dir.join(format!("diag-{}-{}.zip", crate::timestamps::file_stamp(), reason));
+    crate::paths::write_atomic(&path, &zip).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    info!("📦 Diagnostics bundle queued for upload: {:?} ({} bytes)", path, zip.len());
+
+    let files = queued(app);
+    for old in files.iter().take(files.len().saturating_sub(MAX_QUEUED)) {
+        warn!("⚠️ Upload queue full; dropping {:?}", old);
+        let _ = fs::remove_file(old);
+    }
+    Ok(())
+}
+
+fn client(app: &tauri::AppHandle) -> Result<Client, String> {
+    let builder = Client::builder().timeout(UPLOAD_TIMEOUT);
+    let builder = match proxy::reqwest_proxy(app) {
+        Some(proxy) => builder.proxy(proxy),
+        None => builder.no_proxy(),
+    };
+    ca_certs::reqwest_certs(app)?
+        .into_iter()
+        .fold(builder, |b, cert| b.add_root_certificate(cert))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+/// Reason from a `diag-<ts>-<reason>.zip` name.
+fn reason(path: &Path) -> String {
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    stem.rsplit('-').next().unwrap_or("manual").to_string()
+}
+
+fn upload(app: &tauri::AppHandle, client: &Client, url: &str, path: &Path) -> Result<(), String> {
+    let body = fs::read(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let mut attempt = 0;
+    loop {
+        let result = client
+            .post(url)
+            .header("Content-Type", "application/zip")
+            .header("X-Device-Id", crate::session::device_id(&app.state::<AppPaths>()))
+            .header("X-App-Version", app.package_info().version.to_string())
+            .header("X-Upload-Reason", reason(path))
+            .body(body.clone())
+            .send()
+            .map_err(|e| e.to_string())
+            .and_then(|r| match r.status() {
+                status if status.is_success() => Ok(()),
+                status => Err(format!("HTTP {}", status)),
+            });
+        match (result, RETRY_DELAYS.get(attempt)) {
+            (Ok(()), _) => return Ok(()),
+            (Err(e), Some(delay)) => {
+                warn!("⚠️ Diagnostics upload failed ({}); retrying in {:?}", e, delay);
+                thread::sleep(*delay);
+                attempt += 1;
+            }
+            (Err(e), None) => return Err(e),
+        }
+    }
+}
+
+/// Send every queued bundle, oldest first, stopping at the first that
+/// can't be sent. Blocking.
+pub fn flush(app: &tauri::AppHandle) -> UploadReport {
+    let _flushing = app.state::<DiagnosticsUpload>().flushing.lock().unwrap();
+    let mut report = UploadReport { uploaded: 0, queued: queued(app).len() };
+    let Some(settings) = allowed(app) else { return report };
+    let Some(url) = settings.url.map(|u| u.trim().to_string()) else { return report };
+    if report.queued == 0 {
+        return report;
+    }
+
+    let client = match client(app) {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("⚠️ Diagnostics upload skipped: {}", e);
+            return report;
+        }
+    };
+    for path in queued(app) {
+        match upload(app, &client, &url, &path) {
+            Ok(()) => {
+                info!("📤 Diagnostics bundle {:?} uploaded", path.file_name().unwrap_or_default());
+                let _ = fs::remove_file(&path);
+                report.uploaded += 1;
+                report.queued -= 1;
+            }
+            Err(e) => {
+                warn!("⚠️ Diagnostics upload failed; {} bundle(s) stay queued: {}", report.queued, e);
+                break;
+            }
+        }
+    }
+    report
+}
+
+fn enqueue_and_flush(app: tauri::AppHandle, reason: &'static str) {
+    thread::spawn(move || {
+        match enqueue(&app, reason) {
+            Ok(()) => {
+                flush(&app);
+            }
+            Err(e) => warn!("⚠️ Failed to queue diagnostics bundle: {}", e),
+        }
+    });
+}
+
+/// Event-bus subscriber: queue and send a bundle when the backend crashes.
+pub fn on_lifecycle(app: &tauri::AppHandle, event: &LifecycleEvent) {
+    if let LifecycleEvent::BackendExited { code, signal, expected: false } = event {
+        if (*code != Some(0) || signal.is_some()) && allowed(app).is_some() {
+            enqueue_and_flush(app.clone(), "backend_crash");
+        }
+    }
+}
+
+/// Send whatever is queued now and then every `FLUSH_INTERVAL`.
+pub fn start_upload_queue(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        flush(&app);
+        thread::sleep(FLUSH_INTERVAL);
+    });
+}
+
+/// Build a bundle and upload it (with anything already queued) now.
+#[tauri::command]
+pub async fn upload_diagnostics_now(app_handle: tauri::AppHandle) -> Result<UploadReport, AppError> {
+    if allowed(&app_handle).is_none() {
+        return Err(AppError::Conflict(
+            "Diagnostics upload needs consent and an upload URL in settings.".to_string(),
+        ));
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        enqueue(&app_handle, "manual")?;
+        Ok::<_, String>(flush(&app_handle))
+    })
+    .await
+    .map_err(|e| format!("Diagnostics upload task failed: {}", e))?
+    .map_err(AppError::Io)
+}
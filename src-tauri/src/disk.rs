@@ -0,0 +1,92 @@
+//! Free-space checks so backups, exports and update downloads fail up front
+//! instead of producing truncated files on a full disk.
+
+use std::path::Path;
+
+use log::{error, warn};
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::error::AppError;
+use crate::paths::AppPaths;
+
+/// Free space required before downloading an update, when its size is
+/// not known in advance.
+pub const MIN_UPDATE_FREE_SPACE: u64 = 500 * 1024 * 1024;
+
+/// Bytes available to the current user on the volume holding `path`.
+pub fn available_space(path: &Path) -> std::io::Result<u64> {
+    fs2::available_space(path)
+}
+
+#[derive(Clone, Serialize)]
+struct LowDiskSpace {
+    operation: String,
+    path: String,
+    required_bytes: u64,
+    available_bytes: u64,
+}
+
+/// Make sure at least `required` bytes are free at `path` before running
+/// `operation`. Emits `low-disk-space` and returns an error when not. If the
+/// free space can't be determined we log it and let the operation proceed.
+pub fn ensure_space<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    path: &Path,
+    required: u64,
+    operation: &str,
+) -> Result<(), String> {
+    let available = match available_space(path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("⚠️ Could not check free space at {:?}: {}", path, e);
+            return Ok(());
+        }
+    };
+
+    if available >= required {
+        return Ok(());
+    }
+
+    error!(
+        "💾 Skipping {}: {} bytes free at {:?}, {} required",
+        operation, available, path, required
+    );
+    let _ = app.emit(
+        "low-disk-space",
+        LowDiskSpace {
+            operation: operation.to_string(),
+            path: path.display().to_string(),
+            required_bytes: required,
+            available_bytes: available,
+        },
+    );
+    Err(format!(
+        "Not enough disk space for {} ({} MB free, {} MB needed).",
+        operation,
+        available / (1024 * 1024),
+        required.div_ceil(1024 * 1024)
+    ))
+}
+
+#[derive(Clone, Serialize)]
+pub struct DiskStatus {
+    pub path: String,
+    pub free_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Free/total bytes on the volume holding the app data dir.
+#[tauri::command]
+pub fn disk_status(app_paths: tauri::State<'_, AppPaths>) -> Result<DiskStatus, AppError> {
+    let root = &app_paths.root;
+    let free_bytes = available_space(root)
+        .map_err(|e| AppError::Io(format!("Failed to read free space for {:?}: {}", root, e)))?;
+    let total_bytes = fs2::total_space(root)
+        .map_err(|e| AppError::Io(format!("Failed to read disk size for {:?}: {}", root, e)))?;
+    Ok(DiskStatus {
+        path: root.display().to_string(),
+        free_bytes,
+        total_bytes,
+    })
+}
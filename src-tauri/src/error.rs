@@ -0,0 +1,151 @@
+//! Error type returned by every command.
+//!
+//! Serialized to the frontend as `{ code, message, details }`, so the UI
+//! can branch on `code` instead of matching message text. `details` is
+//! `null` unless the error carries extra data (e.g. the list of issues from
+//! a failed validation).
+//!
+//! Internal helpers keep returning `Result<_, String>`; a bare `String`
+//! converts to `Internal`, so commands only need to pick a variant where
+//! the distinction matters to the UI.
+
+use std::fmt;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+#[derive(Debug)]
+pub enum AppError {
+    /// Reading or writing a file failed.
+    Io(String),
+    /// Checking, downloading or installing an update failed.
+    Updater(String),
+    /// The backend sidecar is unavailable or the request to it failed.
+    Backend(String),
+    /// The backend hasn't finished starting, or isn't running;
+    /// `details.phase` says which.
+    BackendNotReady(String),
+    /// The input was rejected; nothing was changed.
+    Validation(String),
+    NotFound(String),
+    /// Wrong PIN or license, or the action is locked.
+    PermissionDenied(String),
+    /// Refused because of the app's current state (a sale or download in
+    /// progress, a mode already active).
+    Conflict(String),
+    /// The printer is missing, offline or the print job failed.
+    Printer(String),
+    /// Not available on this platform.
+    Unsupported(String),
+    Internal(String),
+    /// One of the above with structured `details`.
+    Detailed(Box<AppError>, Value),
+}
+
+impl AppError {
+    pub fn with_details(self, details: Value) -> Self {
+        match self {
+            AppError::Detailed(inner, _) => AppError::Detailed(inner, details),
+            other => AppError::Detailed(Box::new(other), details),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Io(_) => "io",
+            AppError::Updater(_) => "updater",
+            AppError::Backend(_) => "backend",
+            AppError::BackendNotReady(_) => "backend_not_ready",
+            AppError::Validation(_) => "validation",
+            AppError::NotFound(_) => "not_found",
+            AppError::PermissionDenied(_) => "permission_denied",
+            AppError::Conflict(_) => "conflict",
+            AppError::Printer(_) => "printer",
+            AppError::Unsupported(_) => "unsupported",
+            AppError::Internal(_) => "internal",
+            AppError::Detailed(inner, _) => inner.code(),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::Io(m)
+            | AppError::Updater(m)
+            | AppError::Backend(m)
+            | AppError::BackendNotReady(m)
+            | AppError::Validation(m)
+            | AppError::NotFound(m)
+            | AppError::PermissionDenied(m)
+            | AppError::Conflict(m)
+            | AppError::Printer(m)
+            | AppError::Unsupported(m)
+            | AppError::Internal(m) => m,
+            AppError::Detailed(inner, _) => inner.message(),
+        }
+    }
+
+    pub fn details(&self) -> Option<&Value> {
+        match self {
+            AppError::Detailed(_, details) => Some(details),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("AppError", 3)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("message", self.message())?;
+        s.serialize_field("details", &self.details())?;
+        s.end()
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Internal(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Internal(message.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => AppError::NotFound(e.to_string()),
+            _ => AppError::Io(e.to_string()),
+        }
+    }
+}
+
+impl From<tauri_plugin_updater::Error> for AppError {
+    fn from(e: tauri_plugin_updater::Error) -> Self {
+        AppError::Updater(e.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(e: tauri::Error) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
@@ -0,0 +1,186 @@
+//! In-process bus for backend and update lifecycle events.
+//!
+//! Lifecycle code calls `publish` once per event instead of emitting to the
+//! frontend, counting and logging at each site. Subscribers started by
+//! `start_subscribers` each get every event:
+//!
+//! - the frontend emitter, which sends the same Tauri events as before
+//!   (`backend-port`, `backend-ready`, `update-ready`, `update-cancelled`)
+//!   so existing listeners see no change;
+//! - `LifecycleCounters`, read with `lifecycle_counters`;
+//! - the audit log, one `events.jsonl` record per event;
+//! - crash-loop detection for safe mode (`safe_mode.rs`);
+//! - the diagnostics upload on backend crashes (`diagnostics_upload.rs`);
+//! - restarting a crashed backend (`backend_supervisor.rs`).
+//!
+//! A new reactor is one more `subscribe` loop here. The bus is a
+//! `tokio::sync::broadcast` channel; a subscriber that falls behind by more
+//! than `CAPACITY` events skips the oldest and logs how many it missed.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use log::warn;
+use serde::Serialize;
+use serde_json::json;
+use tauri::{Emitter, Manager};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+const CAPACITY: usize = 64;
+
+#[derive(Clone, Debug)]
+pub enum LifecycleEvent {
+    BackendSpawned { pid: u32 },
+    /// The backend announced its port.
+    BackendReady { port: u16 },
+    /// `expected` is whether the backend was being stopped when it exited.
+    BackendExited { code: Option<i32>, signal: Option<i32>, expected: bool },
+    /// An update is downloaded, verified and waiting for a restart.
+    UpdateReady { version: String },
+    UpdateCancelled { version: String },
+}
+
+impl LifecycleEvent {
+    fn kind(&self) -> &'static str {
+        match self {
+            LifecycleEvent::BackendSpawned { .. } => "backend_spawned",
+            LifecycleEvent::BackendReady { .. } => "backend_ready",
+            LifecycleEvent::BackendExited { .. } => "backend_exited",
+            LifecycleEvent::UpdateReady { .. } => "update_ready",
+            LifecycleEvent::UpdateCancelled { .. } => "update_cancelled",
+        }
+    }
+
+    fn data(&self) -> serde_json::Value {
+        match self {
+            LifecycleEvent::BackendSpawned { pid } => json!({ "pid": pid }),
+            LifecycleEvent::BackendReady { port } => json!({ "port": port }),
+            LifecycleEvent::BackendExited { code, signal, expected } => {
+                json!({ "code": code, "signal": signal, "expected": expected })
+            }
+            LifecycleEvent::UpdateReady { version } | LifecycleEvent::UpdateCancelled { version } => {
+                json!({ "version": version })
+            }
+        }
+    }
+}
+
+pub struct EventBus {
+    tx: broadcast::Sender<LifecycleEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self { tx: broadcast::channel(CAPACITY).0 }
+    }
+}
+
+/// Send `event` to every subscriber. Never blocks.
+pub fn publish<R: tauri::Runtime>(app: &tauri::AppHandle<R>, event: LifecycleEvent) {
+    // No receivers only means the subscribers haven't started yet.
+    let _ = app.state::<EventBus>().tx.send(event);
+}
+
+#[derive(Default)]
+pub struct LifecycleCounters {
+    backend_spawns: AtomicU64,
+    backend_exits: AtomicU64,
+    /// Exits with a non-zero code or a signal.
+    backend_crashes: AtomicU64,
+    updates_ready: AtomicU64,
+    updates_cancelled: AtomicU64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct LifecycleCountersSnapshot {
+    pub backend_spawns: u64,
+    pub backend_exits: u64,
+    pub backend_crashes: u64,
+    pub updates_ready: u64,
+    pub updates_cancelled: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct BackendPort {
+    port: u16,
+}
+
+#[derive(Clone, Serialize)]
+struct UpdateVersion {
+    version: String,
+}
+
+fn emit_to_frontend(app: &tauri::AppHandle, event: &LifecycleEvent) {
+    match event {
+        LifecycleEvent::BackendReady { port } => {
+            let _ = app.emit("backend-port", BackendPort { port: *port });
+            let _ = app.emit("backend-ready", ());
+        }
+        LifecycleEvent::UpdateReady { version } => {
+            let _ = app.emit("update-ready", UpdateVersion { version: version.clone() });
+        }
+        LifecycleEvent::UpdateCancelled { version } => {
+            let _ = app.emit("update-cancelled", UpdateVersion { version: version.clone() });
+        }
+        LifecycleEvent::BackendSpawned { .. } | LifecycleEvent::BackendExited { .. } => {}
+    }
+}
+
+fn count(app: &tauri::AppHandle, event: &LifecycleEvent) {
+    let counters = app.state::<LifecycleCounters>();
+    let counter = match event {
+        LifecycleEvent::BackendSpawned { .. } => &counters.backend_spawns,
+        LifecycleEvent::BackendReady { .. } => return,
+        LifecycleEvent::BackendExited { code, signal, .. } => {
+            if *code != Some(0) || signal.is_some() {
+                counters.backend_crashes.fetch_add(1, Ordering::Relaxed);
+            }
+            &counters.backend_exits
+        }
+        LifecycleEvent::UpdateReady { .. } => &counters.updates_ready,
+        LifecycleEvent::UpdateCancelled { .. } => &counters.updates_cancelled,
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+fn audit(app: &tauri::AppHandle, event: &LifecycleEvent) {
+    crate::audit_log::record(app, event.kind(), event.data());
+}
+
+/// Run `handle` for every event on the bus until the app exits.
+fn subscribe(app: &tauri::AppHandle, name: &'static str, handle: fn(&tauri::AppHandle, &LifecycleEvent)) {
+    let mut rx = app.state::<EventBus>().tx.subscribe();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => handle(&app, &event),
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("⚠️ Lifecycle subscriber '{}' fell behind and missed {} event(s)", name, missed);
+                }
+                Err(RecvError::Closed) => return,
+            }
+        }
+    });
+}
+
+/// Attach the built-in subscribers. Call during setup, before the backend
+/// starts, so no event is missed.
+pub fn start_subscribers(app: &tauri::AppHandle) {
+    subscribe(app, "frontend", emit_to_frontend);
+    subscribe(app, "counters", count);
+    subscribe(app, "audit_log", audit);
+    subscribe(app, "safe_mode", crate::safe_mode::on_lifecycle);
+    subscribe(app, "diagnostics_upload", crate::diagnostics_upload::on_lifecycle);
+    subscribe(app, "backend_supervisor", crate::backend_supervisor::on_lifecycle);
+}
+
+#[tauri::command]
+pub fn lifecycle_counters(counters: tauri::State<'_, LifecycleCounters>) -> LifecycleCountersSnapshot {
+    LifecycleCountersSnapshot {
+        backend_spawns: counters.backend_spawns.load(Ordering::Relaxed),
+        backend_exits: counters.backend_exits.load(Ordering::Relaxed),
+        backend_crashes: counters.backend_crashes.load(Ordering::Relaxed),
+        updates_ready: counters.updates_ready.load(Ordering::Relaxed),
+        updates_cancelled: counters.updates_cancelled.load(Ordering::Relaxed),
+    }
+}
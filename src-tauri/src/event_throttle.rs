@@ -0,0 +1,161 @@
+//! Backpressure for high-frequency events to the frontend.
+//!
+//! A chatty backend can send hundreds of lines a second, and a slow webview
+//! handling an event per line janks the UI. Instead:
+//!
+//! - backend output is logged under the `backend_output` target, which the
+//!   log plugin's webview target skips; the lines are collected here and
+//!   sent every `events.log_batch_ms` as one `backend-log-batch
+//!   { seq, lines, dropped }` event;
+//! - once the frontend has acknowledged a batch with `ack_events`, at most
+//!   `events.max_in_flight` batches are sent ahead of its acks. While it is
+//!   behind, lines are held back, and beyond `events.max_batch_lines` new
+//!   ones are dropped and counted in the next batch's `dropped`;
+//! - progress events sent through `emit_coalesced` go out at most every
+//!   `events.progress_interval_ms`, latest payload wins, and the last one
+//!   is always delivered.
+//!
+//! Everything else — crashes, update results, mode changes — is emitted
+//! directly and never throttled. The full output stays in the log files
+//! and `get_backend_logs` either way.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::warn;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{Emitter, Manager};
+
+use crate::backend::BackendLogLine;
+use crate::settings::SettingsState;
+
+/// Log target for backend output lines; see the module docs.
+pub const BACKEND_OUTPUT_TARGET: &str = "backend_output";
+
+/// `events.max_batch_lines`, cached so queuing a line doesn't clone the
+/// settings; refreshed by `settings::apply`.
+static MAX_BATCH_LINES: AtomicUsize = AtomicUsize::new(500);
+
+pub fn set_max_batch_lines(lines: usize) {
+    MAX_BATCH_LINES.store(lines, Ordering::Relaxed);
+}
+
+#[derive(Default)]
+pub struct EventThrottle {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    lines: Vec<BackendLogLine>,
+    dropped: u64,
+    /// Last batch sent.
+    seq: u64,
+    /// Last batch the frontend acknowledged; `None` until it first does,
+    /// so a frontend that never acks is never held back.
+    acked: Option<u64>,
+    /// Latest unsent payload of each coalesced event.
+    pending: HashMap<&'static str, Value>,
+    last_sent: HashMap<&'static str, Instant>,
+}
+
+#[derive(Clone, Serialize)]
+struct BackendLogBatch {
+    seq: u64,
+    lines: Vec<BackendLogLine>,
+    /// Lines dropped since the previous batch because the frontend was behind.
+    dropped: u64,
+}
+
+/// Queue a backend line for the next `backend-log-batch`.
+pub fn queue_log_line(app: &tauri::AppHandle, line: BackendLogLine) {
+    let max_lines = MAX_BATCH_LINES.load(Ordering::Relaxed);
+    let mut inner = app.state::<EventThrottle>().inner.lock().unwrap();
+    if inner.lines.len() >= max_lines {
+        inner.dropped += 1;
+    } else {
+        inner.lines.push(line);
+    }
+}
+
+/// Emit `event` now, or hold it until `events.progress_interval_ms` has
+/// passed since the last one, replacing any payload still held.
+pub fn emit_coalesced<S: Serialize>(app: &tauri::AppHandle, event: &'static str, payload: S) {
+    let interval = Duration::from_millis(app.state::<SettingsState>().get().events.progress_interval_ms);
+    let payload = match serde_json::to_value(payload) {
+        Ok(payload) => payload,
+        Err(e) => {
+            warn!("⚠️ Failed to serialize {} payload: {}", event, e);
+            return;
+        }
+    };
+    let mut inner = app.state::<EventThrottle>().inner.lock().unwrap();
+    let due = inner.last_sent.get(event).map_or(true, |sent| sent.elapsed() >= interval);
+    if due {
+        inner.pending.remove(event);
+        inner.last_sent.insert(event, Instant::now());
+        drop(inner);
+        let _ = app.emit(event, payload);
+    } else {
+        inner.pending.insert(event, payload);
+    }
+}
+
+fn flush(app: &tauri::AppHandle) {
+    let settings = app.state::<SettingsState>().get().events;
+    let interval = Duration::from_millis(settings.progress_interval_ms);
+    let mut inner = app.state::<EventThrottle>().inner.lock().unwrap();
+
+    let due: Vec<&'static str> = inner
+        .pending
+        .keys()
+        .copied()
+        .filter(|event| inner.last_sent.get(event).map_or(true, |sent| sent.elapsed() >= interval))
+        .collect();
+    let mut events = Vec::with_capacity(due.len() + 1);
+    for event in due {
+        if let Some(payload) = inner.pending.remove(event) {
+            inner.last_sent.insert(event, Instant::now());
+            events.push((event, payload));
+        }
+    }
+
+    let behind = inner.acked.is_some_and(|acked| inner.seq - acked >= settings.max_in_flight);
+    if !behind && (!inner.lines.is_empty() || inner.dropped > 0) {
+        inner.seq += 1;
+        let batch = BackendLogBatch {
+            seq: inner.seq,
+            lines: std::mem::take(&mut inner.lines),
+            dropped: std::mem::take(&mut inner.dropped),
+        };
+        if batch.dropped > 0 {
+            warn!("🟡 Frontend behind on backend output; {} line(s) dropped from the UI", batch.dropped);
+        }
+        events.push(("backend-log-batch", serde_json::json!(batch)));
+    }
+    drop(inner);
+
+    for (event, payload) in events {
+        let _ = app.emit(event, payload);
+    }
+}
+
+pub fn start_event_flusher(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        let batch_ms = app.state::<SettingsState>().get().events.log_batch_ms;
+        thread::sleep(Duration::from_millis(batch_ms));
+        flush(&app);
+    });
+}
+
+/// Called by the frontend once it has handled `backend-log-batch` `seq`.
+#[tauri::command]
+pub fn ack_events(throttle: tauri::State<'_, EventThrottle>, seq: u64) {
+    let mut inner = throttle.inner.lock().unwrap();
+    let seq = seq.min(inner.seq);
+    inner.acked = Some(inner.acked.map_or(seq, |acked| acked.max(seq)));
+}
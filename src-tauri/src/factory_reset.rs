@@ -0,0 +1,144 @@
+//! Factory reset for reselling or reprovisioning a terminal.
+//!
+//! Two steps so it can't happen by accident: `prepare_factory_reset` hands
+//! out a one-time token that expires after a couple of minutes, and
+//! `factory_reset` only proceeds when given that token back. The reset
+//! stops the backend, zips the whole data dir and the backend's data into
+//! `backups/` as a last resort, deletes everything else (database,
+//! settings, license, logs, device state, the backend's store and offline
+//! queues) and restarts into first-run state. `audit.jsonl` is kept.
+
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use log::{error, info, warn};
+use tauri::Manager;
+
+use crate::audit_log;
+use crate::backend::{self, BackendState};
+use crate::backups::{self, BACKUPS_DIR};
+use crate::error::AppError;
+use crate::paths::AppPaths;
+use crate::profiles;
+
+const TOKEN_TTL: Duration = Duration::from_secs(120);
+
+/// The outstanding confirmation token, if any.
+#[derive(Default)]
+pub struct FactoryResetState(Mutex<Option<(String, Instant)>>);
+
+fn new_token() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    format!("{:08X}", hasher.finish() as u32)
+}
+
+/// Delete `path` recursively, logging each removal. Failures are logged and
+/// counted rather than aborting the reset halfway.
+fn remove_logged(path: &Path, failures: &mut u32) {
+    if path.is_dir() {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                remove_logged(&entry.path(), failures);
+            }
+        }
+        match fs::remove_dir(path) {
+            Ok(_) => info!("🗑️ Removed directory {:?}", path),
+            Err(e) => {
+                warn!("⚠️ Could not remove directory {:?}: {}", path, e);
+                *failures += 1;
+            }
+        }
+    } else {
+        match fs::remove_file(path) {
+            Ok(_) => info!("🗑️ Removed {:?}", path),
+            Err(e) => {
+                warn!("⚠️ Could not remove {:?}: {}", path, e);
+                *failures += 1;
+            }
+        }
+    }
+}
+
+/// Issue a one-time token that `factory_reset` must be called with.
+#[tauri::command]
+pub fn prepare_factory_reset(state: tauri::State<'_, FactoryResetState>) -> String {
+    let token = new_token();
+    *state.0.lock().unwrap() = Some((token.clone(), Instant::now()));
+    warn!("⚠️ Factory reset requested; confirmation token issued");
+    token
+}
+
+async fn reset(app_handle: tauri::AppHandle, confirm_token: String) -> Result<(), AppError> {
+    {
+        let state = app_handle.state::<FactoryResetState>();
+        let mut pending = state.0.lock().unwrap();
+        match pending.take() {
+            Some((token, issued)) if token == confirm_token && issued.elapsed() < TOKEN_TTL => {}
+            Some((_, issued)) if issued.elapsed() >= TOKEN_TTL => {
+                return Err(AppError::Validation("Confirmation token expired; request a new one.".to_string()));
+            }
+            _ => return Err(AppError::Validation("Invalid confirmation token.".to_string())),
+        }
+    }
+
+    info!("=================================================");
+    info!("🧨 FACTORY RESET");
+    info!("=================================================");
+
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || -> Result<(), String> {
+        backend::stop_backend(&app.state::<BackendState>()).map_err(|e| {
+            error!("❌ {}, aborting reset", e);
+            e
+        })?;
+
+        let paths = app.state::<AppPaths>().inner().clone();
+        // Everything goes into this one, logs and backend data included.
+        let backup = backups::write_backup(&paths, "pre-reset", &[], |_| {}, &mut |_| {}).map_err(|e| {
+            error!("❌ Pre-reset backup failed, aborting reset: {}", e);
+            e
+        })?;
+        info!("💾 Pre-reset backup written to {:?}", backup);
+
+        let mut failures = 0;
+        let entries = fs::read_dir(&paths.root)
+            .map_err(|e| format!("Failed to read {:?}: {}", paths.root, e))?;
+        for entry in entries.flatten() {
+            // Other store profiles, the active-profile record and the
+            // privileged-operation audit trail survive a reset of this profile.
+            let name = entry.file_name();
+            let kept = [BACKUPS_DIR, profiles::PROFILES_DIR, profiles::PROFILES_FILE, audit_log::AUDIT_FILE];
+            if !kept.iter().any(|kept| name == *kept) {
+                remove_logged(&entry.path(), &mut failures);
+            }
+        }
+        if failures > 0 {
+            warn!("⚠️ Factory reset left {} item(s) that could not be removed", failures);
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Factory reset failed: {}", e))?
+    .map_err(AppError::Io)?;
+    Ok(())
+}
+
+/// Wipe the terminal back to first-run state and restart. Requires the
+/// token from `prepare_factory_reset`.
+#[tauri::command]
+pub async fn factory_reset(app_handle: tauri::AppHandle, confirm_token: String) -> Result<(), AppError> {
+    let result = reset(app_handle.clone(), confirm_token).await;
+    crate::audit_log::privileged(&app_handle, "factory_reset", result)?;
+    info!("✅ Factory reset complete, restarting into first-run state");
+    app_handle.restart();
+}
@@ -0,0 +1,133 @@
+//! Per-terminal feature flags for gradual rollouts.
+//!
+//! Flags are named booleans, merged at startup from (last wins):
+//!
+//! 1. `flags-cache.json`, the last answer from `feature_flags_url`;
+//! 2. `flags.json` in the profile dir, provisioned or hand-edited per
+//!    terminal, so a terminal can always opt in or out locally.
+//!
+//! When `feature_flags_url` is set, a background fetch
+//! (`GET <url>?version=<app>&profile=<name>`, answering `{ "<flag>": bool }`)
+//! refreshes the cache every `FLAGS_REFRESH_INTERVAL` and emits
+//! `flags-changed` with the merged set when anything changed. If the
+//! endpoint can't be reached, the cached and local flags stay in effect.
+//!
+//! The frontend reads them with `get_flags`; Rust code checks `enabled`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use log::{info, warn};
+use reqwest::blocking::Client;
+use tauri::{Emitter, Manager, Url};
+
+use crate::paths::{self, AppPaths};
+use crate::settings::SettingsState;
+use crate::{ca_certs, proxy};
+
+const LOCAL_FLAGS_FILE: &str = "flags.json";
+const CACHE_FILE: &str = "flags-cache.json";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+const FLAGS_REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+pub type Flags = BTreeMap<String, bool>;
+
+#[derive(Default)]
+pub struct FeatureFlags {
+    flags: Mutex<Flags>,
+}
+
+fn file(app: &tauri::AppHandle, name: &str) -> PathBuf {
+    app.state::<AppPaths>().root.join(name)
+}
+
+/// `remote` with the local overrides applied.
+fn merged(app: &tauri::AppHandle, remote: Flags) -> Flags {
+    let mut flags = remote;
+    let local: Flags = paths::read_config_or_default(&file(app, LOCAL_FLAGS_FILE));
+    flags.extend(local);
+    flags
+}
+
+/// Whether `name` is on, or `default` when no source sets it.
+pub fn enabled(app: &tauri::AppHandle, name: &str, default: bool) -> bool {
+    app.state::<FeatureFlags>().flags.lock().unwrap().get(name).copied().unwrap_or(default)
+}
+
+/// Load the cached and local flags; called during setup.
+pub fn load(app: &tauri::AppHandle) {
+    let flags = merged(app, paths::read_config_or_default(&file(app, CACHE_FILE)));
+    if !flags.is_empty() {
+        let on: Vec<&str> = flags.iter().filter(|(_, on)| **on).map(|(name, _)| name.as_str()).collect();
+        info!("🚩 {} feature flag(s) loaded; on: {}", flags.len(), on.join(", "));
+    }
+    *app.state::<FeatureFlags>().flags.lock().unwrap() = flags;
+}
+
+fn fetch(app: &tauri::AppHandle, url: &str) -> Result<Flags, String> {
+    let mut url = Url::parse(url).map_err(|e| format!("Invalid feature_flags_url: {}", e))?;
+    url.query_pairs_mut()
+        .append_pair("version", &app.package_info().version.to_string())
+        .append_pair("profile", &app.state::<AppPaths>().profile);
+
+    let builder = Client::builder().timeout(FETCH_TIMEOUT);
+    let builder = match proxy::reqwest_proxy(app) {
+        Some(proxy) => builder.proxy(proxy),
+        None => builder.no_proxy(),
+    };
+    let builder = ca_certs::reqwest_certs(app)?
+        .into_iter()
+        .fold(builder, |b, cert| b.add_root_certificate(cert));
+    let client = builder.build().map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let response = client.get(url).send().map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    let body = response.text().map_err(|e| e.to_string())?;
+    serde_json::from_str(&body).map_err(|e| format!("Invalid flags response: {}", e))
+}
+
+fn refresh(app: &tauri::AppHandle, url: &str) {
+    let remote = match fetch(app, url) {
+        Ok(remote) => remote,
+        Err(e) => {
+            warn!("⚠️ Feature flags endpoint unreachable; using cached and local flags: {}", e);
+            return;
+        }
+    };
+    let cache = file(app, CACHE_FILE);
+    let result = serde_json::to_vec_pretty(&remote)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| paths::write_atomic(&cache, &bytes).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("⚠️ Failed to cache feature flags to {:?}: {}", cache, e);
+    }
+
+    let flags = merged(app, remote);
+    let mut current = app.state::<FeatureFlags>().flags.lock().unwrap();
+    if *current != flags {
+        info!("🚩 Feature flags updated from {}", url);
+        *current = flags.clone();
+        drop(current);
+        let _ = app.emit("flags-changed", flags);
+    }
+}
+
+pub fn start_flags_refresh(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        let url = app.state::<SettingsState>().get().feature_flags_url;
+        if let Some(url) = url.filter(|u| !u.trim().is_empty()) {
+            refresh(&app, url.trim());
+        }
+        thread::sleep(FLAGS_REFRESH_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub fn get_flags(flags: tauri::State<'_, FeatureFlags>) -> Flags {
+    flags.flags.lock().unwrap().clone()
+}
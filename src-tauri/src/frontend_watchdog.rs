@@ -0,0 +1,69 @@
+//! Opt-in watchdog for a hung webview.
+//!
+//! When enabled, the frontend is expected to call `ping_frontend` on a timer.
+//! If no ping arrives for `frontend_timeout_secs`, we log it, emit
+//! `frontend-unresponsive` and, if configured, reload the webview. The
+//! watchdog only arms after the first ping, so a slow initial page load is
+//! never mistaken for a hang.
+//!
+//! This is independent of any backend health checking: the frontend keeps
+//! pinging the shell even while the backend is down, so a dead backend never
+//! trips this watchdog, and a hung webview doesn't affect the backend.
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{debug, error, info};
+use tauri::{Emitter, Manager};
+
+use crate::settings::SettingsState;
+
+const WATCHDOG_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+pub struct FrontendWatchdog {
+    /// Time of the last ping; `None` until the frontend first checks in.
+    last_ping: Mutex<Option<Instant>>,
+}
+
+pub fn start_frontend_watchdog(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(WATCHDOG_POLL_INTERVAL);
+
+        let settings = app.state::<SettingsState>().get().frontend_watchdog;
+        if !settings.enabled {
+            continue;
+        }
+
+        let watchdog = app.state::<FrontendWatchdog>();
+        let mut last_ping = watchdog.last_ping.lock().unwrap();
+        let Some(last) = *last_ping else { continue };
+
+        let silent_for = last.elapsed();
+        if silent_for < Duration::from_secs(settings.timeout_secs) {
+            continue;
+        }
+
+        // Disarm until the next ping so we report each hang once.
+        *last_ping = None;
+        drop(last_ping);
+
+        error!("🧊 Frontend has not pinged for {:?} — webview may be unresponsive", silent_for);
+        let _ = app.emit("frontend-unresponsive", silent_for.as_secs());
+
+        if settings.reload_on_timeout {
+            if let Some(window) = app.get_webview_window("main") {
+                info!("🔄 Reloading main webview after watchdog timeout");
+                let _ = window.reload();
+            }
+        }
+    });
+}
+
+/// Heartbeat from the frontend.
+#[tauri::command]
+pub fn ping_frontend(state: tauri::State<'_, FrontendWatchdog>) {
+    debug!("💓 Frontend ping");
+    *state.last_ping.lock().unwrap() = Some(Instant::now());
+}
@@ -0,0 +1,80 @@
+//! Idle auto-lock for unattended terminals.
+//!
+//! Activity is reported by the frontend through `reset_idle_timer` and by the
+//! main window gaining focus. After `idle_lock_mins` minutes without activity
+//! a `lock-screen` event is emitted and, if `idle_lock_hide_window` is set,
+//! the main window is hidden. The backend keeps running either way. A value
+//! of `0` disables the lock (the default).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{debug, info};
+use tauri::{Emitter, Manager};
+
+use crate::settings::SettingsState;
+
+/// How often the monitor thread re-evaluates idle time.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+pub struct IdleState {
+    last_activity: Mutex<Instant>,
+    locked: AtomicBool,
+}
+
+impl Default for IdleState {
+    fn default() -> Self {
+        Self {
+            last_activity: Mutex::new(Instant::now()),
+            locked: AtomicBool::new(false),
+        }
+    }
+}
+
+impl IdleState {
+    pub fn touch(&self) {
+        *self.last_activity.lock().unwrap() = Instant::now();
+        if self.locked.swap(false, Ordering::SeqCst) {
+            info!("🔓 Activity detected, idle lock cleared");
+        }
+    }
+
+    fn idle_for(&self) -> Duration {
+        self.last_activity.lock().unwrap().elapsed()
+    }
+}
+
+/// Start the background thread that locks the terminal when idle.
+pub fn start_idle_monitor(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(IDLE_POLL_INTERVAL);
+
+        let settings = app.state::<SettingsState>().get();
+        if settings.idle_lock_mins == 0 {
+            continue;
+        }
+
+        let idle = app.state::<IdleState>();
+        let limit = Duration::from_secs(settings.idle_lock_mins * 60);
+        if idle.idle_for() < limit || idle.locked.swap(true, Ordering::SeqCst) {
+            continue;
+        }
+
+        info!("🔒 No activity for {} min — locking screen", settings.idle_lock_mins);
+        let _ = app.emit("lock-screen", ());
+        if settings.idle_lock_hide_window {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.hide();
+            }
+        }
+    });
+}
+
+/// Called by the frontend on user input to postpone the idle lock.
+#[tauri::command]
+pub fn reset_idle_timer(state: tauri::State<'_, IdleState>) {
+    debug!("⏱️ Idle timer reset");
+    state.touch();
+}
@@ -0,0 +1,85 @@
+//! Kiosk lockdown for self-checkout stations.
+//!
+//! With `kiosk` on, the main window is fullscreen and always on top, can't
+//! be minimized or closed (close requests are prevented), and the menu is
+//! hidden. The only way out is `exit_kiosk` with the admin PIN from
+//! `pin.rs`, which unlocks the window for the rest of the session.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::{info, warn};
+use tauri::{Manager, WebviewWindow};
+
+use crate::error::AppError;
+use crate::pin;
+
+#[derive(Default)]
+pub struct KioskState {
+    active: AtomicBool,
+}
+
+impl KioskState {
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+}
+
+fn apply(window: &WebviewWindow, locked: bool) -> tauri::Result<()> {
+    window.set_fullscreen(locked)?;
+    window.set_always_on_top(locked)?;
+    window.set_minimizable(!locked)?;
+    window.set_closable(!locked)?;
+    if locked {
+        window.hide_menu()?;
+    }
+    Ok(())
+}
+
+/// Lock `window` down if kiosk mode is configured.
+pub fn enter_if_configured(window: &WebviewWindow, enabled: bool) {
+    if !enabled {
+        return;
+    }
+    match apply(window, true) {
+        Ok(()) => {
+            window.app_handle().state::<KioskState>().active.store(true, Ordering::SeqCst);
+            info!("🔐 Entered kiosk mode");
+        }
+        Err(e) => warn!("⚠️ Failed to enter kiosk mode: {}", e),
+    }
+}
+
+fn leave_kiosk(app_handle: tauri::AppHandle, admin_pin: String) -> Result<(), AppError> {
+    let state = app_handle.state::<KioskState>();
+    if !state.is_active() {
+        return Ok(());
+    }
+    match pin::check_pin(&app_handle, &admin_pin) {
+        None => {
+            return Err(AppError::Conflict("No admin PIN is set; kiosk mode can't be exited.".to_string()))
+        }
+        Some(false) => {
+            warn!("⚠️ Kiosk exit rejected: wrong admin PIN");
+            return Err(AppError::PermissionDenied("Incorrect admin PIN.".to_string()));
+        }
+        Some(true) => {}
+    }
+
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or_else(|| AppError::NotFound("Main window not found.".to_string()))?;
+    apply(&window, false).map_err(|e| format!("Failed to exit kiosk mode: {}", e))?;
+    state.active.store(false, Ordering::SeqCst);
+    // Back to the cashier's own always-on-top preference.
+    crate::always_on_top::apply(&app_handle);
+    info!("🔓 Exited kiosk mode");
+    crate::audit_log::record(&app_handle, "kiosk_exited", serde_json::Value::Null);
+    Ok(())
+}
+
+/// Leave kiosk mode for this session. Requires the admin PIN.
+#[tauri::command]
+pub fn exit_kiosk(app_handle: tauri::AppHandle, admin_pin: String) -> Result<(), AppError> {
+    let result = leave_kiosk(app_handle.clone(), admin_pin);
+    crate::audit_log::privileged(&app_handle, "exit_kiosk", result)
+}
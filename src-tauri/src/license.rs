@@ -0,0 +1,225 @@
+//! Offline license activation.
+//!
+//! A license key is `<payload>.<signature>`, both base64 (standard alphabet,
+//! padding optional). `payload` is UTF-8 JSON matching `LicensePayload`;
+//! `signature` is the 64-byte ed25519 signature of the raw payload bytes,
+//! made with the vendor's private key. The matching public key is embedded
+//! at build time from `SIRI_LICENSE_PUBKEY` (base64 of the 32 key bytes).
+//!
+//! The validated key is stored verbatim in `license.dat` and re-verified on
+//! every launch, so editing the file can't extend a license.
+
+use std::fs;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD};
+use base64::Engine;
+use ed25519_dalek::{Signature, VerifyingKey};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+use crate::error::AppError;
+use crate::paths::{self, AppPaths};
+
+const LICENSE_FILE: &str = "license.dat";
+const EMBEDDED_PUBLIC_KEY: Option<&str> = option_env!("SIRI_LICENSE_PUBKEY");
+
+#[derive(Clone, Debug, Deserialize)]
+struct LicensePayload {
+    license_id: String,
+    licensee: String,
+    /// Unix seconds; `None` for a perpetual license.
+    expires_at: Option<u64>,
+    seats: u32,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LicenseInfo {
+    pub license_id: String,
+    pub licensee: String,
+    pub expires_at: Option<u64>,
+    pub seats: u32,
+}
+
+/// Managed state with the license verified at startup or by `apply_license`.
+#[derive(Default)]
+pub struct LicenseState {
+    current: Mutex<Option<LicenseInfo>>,
+}
+
+fn decode_b64(part: &str) -> Result<Vec<u8>, String> {
+    let part = part.trim();
+    STANDARD
+        .decode(part)
+        .or_else(|_| STANDARD_NO_PAD.decode(part))
+        .map_err(|_| "License key is not valid base64".to_string())
+}
+
+fn embedded_public_key() -> Result<VerifyingKey, String> {
+    let encoded = EMBEDDED_PUBLIC_KEY
+        .ok_or_else(|| "This build has no license public key configured".to_string())?;
+    let bytes: [u8; 32] = decode_b64(encoded)?
+        .try_into()
+        .map_err(|_| "Embedded license public key has the wrong length".to_string())?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| format!("Embedded license public key is invalid: {}", e))
+}
+
+/// Check a license key's signature, expiry and seat count against
+/// `public_key` at time `now` (unix seconds).
+pub fn verify_license(key: &str, public_key: &VerifyingKey, now: u64) -> Result<LicenseInfo, String> {
+    let (payload_b64, signature_b64) = key
+        .trim()
+        .split_once('.')
+        .ok_or_else(|| "License key is malformed".to_string())?;
+
+    let payload = decode_b64(payload_b64)?;
+    let signature = Signature::from_slice(&decode_b64(signature_b64)?)
+        .map_err(|_| "License signature is malformed".to_string())?;
+    public_key
+        .verify_strict(&payload, &signature)
+        .map_err(|_| "License signature is invalid".to_string())?;
+
+    let payload: LicensePayload = serde_json::from_slice(&payload)
+        .map_err(|e| format!("License payload is invalid: {}", e))?;
+
+    if let Some(expires_at) = payload.expires_at {
+        if expires_at <= now {
+            return Err(format!("License {} has expired", payload.license_id));
+        }
+    }
+    if payload.seats == 0 {
+        return Err(format!("License {} has no seats", payload.license_id));
+    }
+
+    Ok(LicenseInfo {
+        license_id: payload.license_id,
+        licensee: payload.licensee,
+        expires_at: payload.expires_at,
+        seats: payload.seats,
+    })
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Load and verify `license.dat` at startup, emitting `license-invalid`
+/// with the reason when it is missing, expired or doesn't verify.
+pub fn load_license(app: &tauri::AppHandle) {
+    let path = app.state::<AppPaths>().root.join(LICENSE_FILE);
+    let result = fs::read_to_string(&path)
+        .map_err(|_| "No license installed".to_string())
+        .and_then(|key| verify_license(&key, &embedded_public_key()?, now_secs()));
+
+    match result {
+        Ok(info) => {
+            info!("🔑 License {} valid for {} ({} seats)", info.license_id, info.licensee, info.seats);
+            *app.state::<LicenseState>().current.lock().unwrap() = Some(info);
+        }
+        Err(reason) => {
+            warn!("⚠️ License check failed: {}", reason);
+            let _ = app.emit("license-invalid", reason);
+        }
+    }
+}
+
+fn store_license(app_handle: tauri::AppHandle, key: String) -> Result<LicenseInfo, AppError> {
+    let info = verify_license(&key, &embedded_public_key()?, now_secs()).map_err(|e| {
+        warn!("⚠️ Rejected license key: {}", e);
+        AppError::Validation(e)
+    })?;
+
+    let path = app_handle.state::<AppPaths>().root.join(LICENSE_FILE);
+    paths::write_atomic(&path, key.trim().as_bytes())
+        .map_err(|e| AppError::Io(format!("Failed to save license: {}", e)))?;
+
+    info!("🔑 License {} applied for {}", info.license_id, info.licensee);
+    *app_handle.state::<LicenseState>().current.lock().unwrap() = Some(info.clone());
+    Ok(info)
+}
+
+/// Verify `key` and, if valid, persist it to `license.dat`.
+#[tauri::command]
+pub fn apply_license(app_handle: tauri::AppHandle, key: String) -> Result<LicenseInfo, AppError> {
+    let result = store_license(app_handle.clone(), key);
+    crate::audit_log::privileged(&app_handle, "apply_license", result)
+}
+
+/// The currently valid license, if any.
+#[tauri::command]
+pub fn get_license(state: tauri::State<'_, LicenseState>) -> Option<LicenseInfo> {
+    state.current.lock().unwrap().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn make_key(payload: &serde_json::Value) -> String {
+        let payload = serde_json::to_vec(payload).unwrap();
+        let signature = signing_key().sign(&payload);
+        format!("{}.{}", STANDARD.encode(&payload), STANDARD.encode(signature.to_bytes()))
+    }
+
+    fn payload(expires_at: Option<u64>) -> serde_json::Value {
+        serde_json::json!({
+            "license_id": "LIC-1",
+            "licensee": "Siri Stores",
+            "expires_at": expires_at,
+            "seats": 3,
+        })
+    }
+
+    #[test]
+    fn valid_key_is_accepted() {
+        let key = make_key(&payload(Some(NOW + 3600)));
+        let info = verify_license(&key, &signing_key().verifying_key(), NOW).unwrap();
+        assert_eq!(info.license_id, "LIC-1");
+        assert_eq!(info.licensee, "Siri Stores");
+        assert_eq!(info.expires_at, Some(NOW + 3600));
+        assert_eq!(info.seats, 3);
+    }
+
+    #[test]
+    fn tampered_signature_is_rejected() {
+        let key = make_key(&payload(Some(NOW + 3600)));
+        let (payload_b64, signature_b64) = key.split_once('.').unwrap();
+        let mut signature = STANDARD.decode(signature_b64).unwrap();
+        signature[0] ^= 0x01;
+        let tampered = format!("{}.{}", payload_b64, STANDARD.encode(signature));
+
+        let err = verify_license(&tampered, &signing_key().verifying_key(), NOW).unwrap_err();
+        assert_eq!(err, "License signature is invalid");
+    }
+
+    #[test]
+    fn tampered_payload_fails_signature_check() {
+        let key = make_key(&payload(Some(NOW + 3600)));
+        let (_, signature) = key.split_once('.').unwrap();
+        let forged = serde_json::to_vec(&payload(None)).unwrap();
+        let tampered = format!("{}.{}", STANDARD.encode(forged), signature);
+
+        let err = verify_license(&tampered, &signing_key().verifying_key(), NOW).unwrap_err();
+        assert_eq!(err, "License signature is invalid");
+    }
+
+    #[test]
+    fn expired_license_is_rejected() {
+        let key = make_key(&payload(Some(NOW)));
+        let err = verify_license(&key, &signing_key().verifying_key(), NOW).unwrap_err();
+        assert_eq!(err, "License LIC-1 has expired");
+    }
+}
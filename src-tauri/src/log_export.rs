@@ -0,0 +1,119 @@
+//! Export of log lines from a time window, for incident investigation.
+//!
+//! Every shell log line starts with `[YYYY-MM-DD][HH:MM:SS<offset>]` (older
+//! lines have no offset and are read as local time). Lines without that
+//! prefix, like wrapped stack traces, belong to the line above them. Both
+//! plain `.log` files and rotated `.log.gz` archives are read, oldest file
+//! first, and files last written before the window are skipped.
+
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use flate2::read::GzDecoder;
+use log::{info, warn};
+use tauri::Manager;
+
+use crate::diagnostics;
+use crate::error::AppError;
+use crate::paths::AppPaths;
+
+/// Unix millis of a line's `[date][time]` prefix, if it has one.
+pub fn line_timestamp(line: &str) -> Option<i64> {
+    let rest = line.strip_prefix('[')?;
+    let (date, rest) = rest.split_once("][")?;
+    let (time, _) = rest.split_once(']')?;
+    let stamp = format!("{} {}", date, time.replace('Z', "+00:00"));
+    if let Ok(dt) = DateTime::parse_from_str(&stamp, "%Y-%m-%d %H:%M:%S%:z") {
+        return Some(dt.timestamp_millis());
+    }
+    let naive = NaiveDateTime::parse_from_str(&stamp, "%Y-%m-%d %H:%M:%S").ok()?;
+    Local.from_local_datetime(&naive).earliest().map(|dt| dt.timestamp_millis())
+}
+
+fn is_log_file(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.is_file() && (name.ends_with(".log") || name.ends_with(".log.gz"))
+}
+
+fn modified_millis(path: &Path) -> i64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(i64::MAX)
+}
+
+fn open_log(path: &Path) -> std::io::Result<Box<dyn Read>> {
+    let file = File::open(path)?;
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        Ok(Box::new(GzDecoder::new(file)))
+    } else {
+        Ok(Box::new(file))
+    }
+}
+
+/// Copy the lines of `path` that fall in `[from, to]` to `out`.
+fn filter_file(path: &Path, from: i64, to: i64, out: &mut impl Write) -> std::io::Result<usize> {
+    let mut written = 0;
+    let mut in_range = false;
+    for line in BufReader::new(open_log(path)?).lines() {
+        // Tolerate a torn last line in a file still being written.
+        let Ok(line) = line else { break };
+        if let Some(ts) = line_timestamp(&line) {
+            in_range = ts >= from && ts <= to;
+        }
+        if in_range {
+            writeln!(out, "{}", line)?;
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+/// Write log lines between `from` and `to` (Unix millis, inclusive) from all
+/// log files into one file next to exported support bundles. Returns its
+/// path.
+#[tauri::command]
+pub async fn export_logs_range(app_handle: tauri::AppHandle, from: i64, to: i64) -> Result<String, AppError> {
+    if from > to {
+        return Err(AppError::Validation("The start of the range must be before its end.".to_string()));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut files: Vec<(i64, PathBuf)> = diagnostics::log_dirs(&app_handle.state::<AppPaths>())
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten().map(|e| e.path()))
+            .filter(|path| is_log_file(path))
+            .map(|path| (modified_millis(&path), path))
+            .filter(|(modified, _)| *modified >= from)
+            .collect();
+        files.sort();
+
+        let path = diagnostics::export_dir(&app_handle)
+            .join(format!("logs-{}.log", crate::timestamps::file_stamp()));
+        let mut out = std::io::BufWriter::new(
+            File::create(&path).map_err(|e| format!("Failed to create {:?}: {}", path, e))?,
+        );
+
+        let mut lines = 0;
+        for (_, file) in &files {
+            match filter_file(file, from, to, &mut out) {
+                Ok(n) => lines += n,
+                Err(e) => warn!("⚠️ Skipping unreadable log {:?}: {}", file, e),
+            }
+        }
+        out.flush().map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+
+        info!("📤 Exported {} log lines from {} file(s) to {:?}", lines, files.len(), path);
+        crate::recent_exports::record(&app_handle, &path, "logs");
+        Ok(path.display().to_string())
+    })
+    .await
+    .map_err(|e| format!("Log export failed: {}", e))?
+    .map_err(AppError::Io)
+}
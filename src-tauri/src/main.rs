@@ -1,25 +1,90 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::process::Command;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::thread;
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
-use tauri::{Manager, RunEvent, WindowEvent, WebviewWindowBuilder, WebviewUrl};
+use tauri::{Emitter, Manager, RunEvent, WindowEvent, WebviewWindowBuilder, WebviewUrl};
 use tauri::webview::{PageLoadEvent, Url as TauriUrl};
-use tauri_plugin_shell::process::{CommandChild, CommandEvent};
-use tauri_plugin_shell::ShellExt;
 use tauri_plugin_log::{Builder as LogBuilder, Target, TargetKind};
-use tauri_plugin_updater::UpdaterExt;
 use log::{info, error, warn, debug};
 use serde::{Serialize, Deserialize};
-use reqwest::blocking::Client;
+
+use error::AppError;
+
+mod always_on_top;
+mod audit_log;
+mod backend;
+mod backend_affinity;
+mod backend_config;
+mod backend_dump;
+mod backend_health;
+mod backend_log_file;
+mod backend_rpc;
+mod backend_supervisor;
+mod backend_trace;
+mod backend_transport;
+mod backups;
+mod ca_certs;
+mod crash_report;
+mod customer_display;
+mod data_import;
+mod db_checkpoint;
+mod demo;
+mod diagnostics;
+mod diagnostics_upload;
+mod disk;
+mod error;
+mod event_bus;
+mod event_throttle;
+mod factory_reset;
+mod feature_flags;
+mod frontend_watchdog;
+mod idle;
+mod kiosk;
+mod license;
+mod log_export;
+mod maintenance;
+mod menu;
+mod migrations;
+mod network;
+mod onboarding;
+mod paths;
+mod pin;
+mod preflight;
+mod printer_test;
+mod profiles;
+mod proxy;
+mod receipt_template;
+mod recent_exports;
+mod recovery;
+mod resource_monitor;
+mod restart_schedule;
+mod safe_mode;
+mod sales_export;
+mod screenshot;
+mod self_test;
+mod session;
+mod settings;
+mod settings_transfer;
+mod splash;
+mod startup_timing;
+mod system_info;
+mod theme;
+mod timestamps;
+mod tray;
+mod updater;
+mod window_state;
+mod window_title;
+mod z_report;
 
 #[cfg(target_os = "windows")]
 use webview2_com::{
@@ -35,89 +100,247 @@ use windows_core::Interface;
 #[cfg(target_os = "windows")]
 use windows::core::PCWSTR;
 
-#[derive(Clone, Serialize, Deserialize)]
-struct UpdateInfo {
-    available: bool,
-    version: String,
-    current_version: String,
-    notes: String,
-    date: String,
-}
 
 // ============================================================================
-// UPDATER COMMANDS
+// DIAGNOSTIC COMMANDS
 // ============================================================================
 
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[derive(Clone, Serialize)]
+struct PingResponse {
+    /// Echo of the client's nonce so it can match replies to requests.
+    nonce: String,
+    app_version: String,
+    backend_pid: Option<u32>,
+    /// Shell-side time the ping was handled (unix millis).
+    server_ts: u64,
+    /// `server_ts - client_ts` when the client sent its own timestamp. The
+    /// client measures full round-trip latency itself from the echoed nonce.
+    latency_ms: Option<i64>,
+}
+
+/// Cheap IPC round-trip check the frontend can call on boot.
 #[tauri::command]
-async fn check_for_updates(app_handle: tauri::AppHandle) -> Result<String, String> {
-    info!("Checking for updates...");
-    match app_handle.updater() {
-        Ok(updater) => {
-            match updater.check().await {
-                Ok(Some(update)) => {
-                    info!("Update available: {:?}", update.version);
-                    Ok(format!("Update available: {}", update.version))
-                }
-                Ok(None) => {
-                    info!("No update available.");
-                    Ok("No update available.".to_string())
-                }
-                Err(e) => {
-                    error!("Failed to check for updates: {}", e);
-                    Err(format!("Failed to check for updates: {}", e))
-                }
+fn ping(
+    app_handle: tauri::AppHandle,
+    backend_state: tauri::State<'_, backend::BackendState>,
+    nonce: String,
+    client_ts: Option<u64>,
+) -> PingResponse {
+    let server_ts = unix_millis();
+    PingResponse {
+        nonce,
+        app_version: app_handle.package_info().version.to_string(),
+        backend_pid: backend_state.child.lock().unwrap().as_ref().map(|c| c.pid()),
+        server_ts,
+        latency_ms: client_ts.map(|ts| server_ts as i64 - ts as i64),
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct AppInfo {
+    app_version: String,
+    identifier: String,
+    /// Random per-process ID prefixed to every log line.
+    session_id: String,
+    os: &'static str,
+    arch: &'static str,
+    data_dir: String,
+    /// Active store profile; see `profiles.rs`.
+    profile: String,
+    backend_port: u16,
+    backend_ready: bool,
+    backend_version: Option<String>,
+    updates: updater::UpdateHistory,
+    system: system_info::SystemInfo,
+}
+
+/// Basic facts support asks for first.
+#[tauri::command]
+fn get_app_info(
+    app_handle: tauri::AppHandle,
+    app_paths: tauri::State<'_, paths::AppPaths>,
+    backend_state: tauri::State<'_, backend::BackendState>,
+) -> AppInfo {
+    AppInfo {
+        app_version: app_handle.package_info().version.to_string(),
+        identifier: app_handle.config().identifier.clone(),
+        session_id: session::session_id().to_string(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        data_dir: app_paths.root.display().to_string(),
+        profile: app_paths.profile.clone(),
+        backend_port: backend_state.port(),
+        backend_ready: backend_state.ready.load(Ordering::SeqCst),
+        backend_version: backend_state.version(),
+        updates: updater::read_history(&app_handle),
+        system: system_info::collect(&app_paths),
+    }
+}
+
+// ============================================================================
+// CACHE MAINTENANCE
+// ============================================================================
+
+/// Scratch directory under the app data dir that is always safe to wipe.
+const CACHE_TMP_DIR: &str = "tmp";
+
+#[derive(Clone, Default, Serialize)]
+struct ClearReport {
+    files_removed: u64,
+    bytes_freed: u64,
+}
+
+/// Remove a file or directory tree, adding what was deleted to `report`.
+/// Entries that fail to delete are logged and skipped.
+fn remove_counted(path: &Path, report: &mut ClearReport) {
+    let meta = match fs::symlink_metadata(path) {
+        Ok(meta) => meta,
+        Err(_) => return,
+    };
+
+    if meta.is_dir() {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                remove_counted(&entry.path(), report);
             }
         }
-        Err(e) => {
-            error!("Failed to get updater: {}", e);
-            Err(format!("Failed to get updater: {}", e))
+        if let Err(e) = fs::remove_dir(path) {
+            warn!("⚠️ Could not remove directory {:?}: {}", path, e);
+        }
+    } else {
+        match fs::remove_file(path) {
+            Ok(_) => {
+                report.files_removed += 1;
+                report.bytes_freed += meta.len();
+            }
+            Err(e) => warn!("⚠️ Could not remove {:?}: {}", path, e),
         }
     }
 }
 
+/// Delete temp/scratch files and stale update downloads from the app data
+/// dir. Only `tmp/` and the updater's download cache are touched — never
+/// the database, backups, settings or device identity.
 #[tauri::command]
-async fn install_update(app_handle: tauri::AppHandle) -> Result<String, String> {
-    info!("Installing update...");
-    match app_handle.updater() {
-        Ok(updater) => {
-            match updater.check().await {
-                Ok(Some(update)) => {
-                    info!("Update found, downloading and installing...");
-                    let mut downloaded = 0;
-                    match update.download_and_install(
-                        |chunk_length, content_length| {
-                            downloaded += chunk_length;
-                            info!("Downloaded {} from {:?}", downloaded, content_length);
-                        },
-                        || {
-                            info!("Download finished");
-                        },
-                    ).await {
-                        Ok(_) => {
-                            info!("Update installed successfully. Restart required.");
-                            Ok("Update installed. Please restart the app.".to_string())
-                        }
-                        Err(e) => {
-                            error!("Failed to download/install update: {}", e);
-                            Err(format!("Failed to download/install update: {}", e))
-                        }
-                    }
-                }
-                Ok(None) => {
-                    info!("No update available to install.");
-                    Ok("No update available to install.".to_string())
-                }
-                Err(e) => {
-                    error!("Failed to check for updates: {}", e);
-                    Err(format!("Failed to check for updates: {}", e))
-                }
-            }
-        }
-        Err(e) => {
-            error!("Failed to get updater: {}", e);
-            Err(format!("Failed to get updater: {}", e))
+async fn clear_cache(
+    app_handle: tauri::AppHandle,
+    app_paths: tauri::State<'_, paths::AppPaths>,
+    update_state: tauri::State<'_, updater::UpdateState>,
+) -> Result<ClearReport, AppError> {
+    if update_state.downloading.load(Ordering::SeqCst) {
+        return Err(AppError::Conflict("Cannot clear cache while an update is downloading.".to_string()));
+    }
+
+    let app_data_dir = &app_paths.root;
+
+    info!("🧹 Clearing cache in {:?}", app_data_dir);
+    let mut report = ClearReport::default();
+
+    let tmp_dir = app_data_dir.join(CACHE_TMP_DIR);
+    if let Ok(entries) = fs::read_dir(&tmp_dir) {
+        for entry in entries.flatten() {
+            remove_counted(&entry.path(), &mut report);
         }
     }
+
+    for path in updater::cache_files(&app_handle) {
+        remove_counted(&path, &mut report);
+    }
+
+    info!(
+        "✅ Cache cleared: {} files removed, {} bytes freed",
+        report.files_removed, report.bytes_freed
+    );
+    Ok(report)
+}
+
+// ============================================================================
+// REVEAL IN FILE MANAGER
+// ============================================================================
+
+/// Open the OS file manager with `path` selected. Only files under the app
+/// data dir or the export dir (Downloads) may be revealed.
+#[tauri::command]
+async fn reveal_file(app_handle: tauri::AppHandle, path: String) -> Result<(), AppError> {
+    let target = fs::canonicalize(&path)
+        .map_err(|e| AppError::NotFound(format!("File not found: {} ({})", path, e)))?;
+
+    let allowed = [
+        app_handle.state::<paths::AppPaths>().root.clone(),
+        diagnostics::export_dir(&app_handle),
+    ];
+    let permitted = allowed
+        .iter()
+        .filter_map(|dir| fs::canonicalize(dir).ok())
+        .any(|dir| target.starts_with(dir));
+    if !permitted {
+        warn!("⚠️ Refusing to reveal {:?}: outside allowed directories", target);
+        return Err(AppError::PermissionDenied("File is outside the allowed directories.".to_string()));
+    }
+
+    info!("📂 Revealing {:?}", target);
+
+    #[cfg(target_os = "windows")]
+    let result = {
+        // explorer.exe wants `/select,<path>` as a single argument and
+        // cannot handle the `\\?\` prefix canonicalize adds.
+        let display = target.to_string_lossy().trim_start_matches(r"\\?\").to_string();
+        Command::new("explorer").arg(format!("/select,{}", display)).spawn()
+    };
+
+    #[cfg(target_os = "macos")]
+    let result = Command::new("open").arg("-R").arg(&target).spawn();
+
+    // Most Linux file managers can't select a file, so open its folder.
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let result = Command::new("xdg-open")
+        .arg(target.parent().unwrap_or(&target))
+        .spawn();
+
+    result
+        .map(|_| ())
+        .map_err(|e| AppError::Io(format!("Failed to open file manager: {}", e)))
+}
+
+/// Open `dir` in the OS file manager.
+fn open_folder(dir: &Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let opener = "explorer";
+    #[cfg(target_os = "macos")]
+    let opener = "open";
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let opener = "xdg-open";
+
+    Command::new(opener)
+        .arg(dir)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open file manager: {}", e))
+}
+
+/// Where the main log file is.
+fn logs_folder(app_paths: &paths::AppPaths) -> PathBuf {
+    // The file log target writes to `logs/` relative to the working dir.
+    std::env::current_dir()
+        .map(|cwd| cwd.join("logs"))
+        .ok()
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(|| app_paths.logs_dir())
+}
+
+/// Open the folder the shell's log file is written to.
+#[tauri::command]
+fn open_logs_folder(app_paths: tauri::State<'_, paths::AppPaths>) -> Result<(), AppError> {
+    let dir = logs_folder(&app_paths);
+    info!("📂 Opening logs folder {:?}", dir);
+    open_folder(&dir).map_err(AppError::Io)
 }
 
 // ============================================================================
@@ -143,15 +366,36 @@ async fn print_html_native(
     printer_name: String,
     copies: u32,
     paper_size: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     #[cfg(not(target_os = "windows"))]
     {
-        let _ = (html, printer_name, copies, paper_size);
-        return Err("print_html_native is only supported on Windows.".to_string());
+        let _ = (app_handle, html, printer_name, copies, paper_size);
+        return Err(AppError::Unsupported("print_html_native is only supported on Windows.".to_string()));
     }
 
     #[cfg(target_os = "windows")]
     {
+        print_html_webview2(app_handle, html, printer_name, copies, paper_size)
+            .await
+            .map_err(AppError::Printer)
+    }
+}
+
+#[cfg(target_os = "windows")]
+async fn print_html_webview2(
+    app_handle: tauri::AppHandle,
+    html: String,
+    printer_name: String,
+    copies: u32,
+    paper_size: Option<String>,
+) -> Result<String, String> {
+    // Fall back to this terminal's saved printer when none was passed.
+    let printer_name = if printer_name.is_empty() {
+        stored_default_printer(&app_handle).unwrap_or_default()
+    } else {
+        printer_name
+    };
+
     info!("??? [print_html_native] Starting WebView2 native print job...");
     info!("   Printer   : {}", if printer_name.is_empty() { "System Default" } else { &printer_name });
     info!("   Copies    : {}", copies);
@@ -414,13 +658,16 @@ async fn print_html_native(
             Err(msg)
         }
     }
-    }
 }
 
 #[tauri::command]
-async fn list_printers_native() -> Result<Vec<String>, String> {
+async fn list_printers_native() -> Result<Vec<String>, AppError> {
     info!("🖨️ [list_printers_native] Listing printers...");
+    query_printers().map_err(AppError::Printer)
+}
 
+/// Names of the printers installed on this machine, via PowerShell.
+fn query_printers() -> Result<Vec<String>, String> {
     let mut list_cmd = Command::new("powershell");
     list_cmd.args(&[
         "-NonInteractive",
@@ -457,12 +704,79 @@ async fn list_printers_native() -> Result<Vec<String>, String> {
     Ok(printers)
 }
 
+// ============================================================================
+// DEFAULT PRINTER (per terminal, persisted in printer.json)
+// ============================================================================
+
+const PRINTER_FILE: &str = "printer.json";
+
+#[derive(Default, Serialize, Deserialize)]
+struct PrinterPrefs {
+    default_printer: Option<String>,
+}
+
+fn read_printer_prefs(app_handle: &tauri::AppHandle) -> PrinterPrefs {
+    let path = app_handle.state::<paths::AppPaths>().root.join(PRINTER_FILE);
+    paths::read_config_or_default(&path)
+}
+
+fn write_printer_prefs(app_handle: &tauri::AppHandle, prefs: &PrinterPrefs) -> Result<(), String> {
+    let path = app_handle.state::<paths::AppPaths>().root.join(PRINTER_FILE);
+    let text = serde_json::to_string_pretty(prefs).map_err(|e| e.to_string())?;
+    paths::write_atomic(&path, text.as_bytes()).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// The saved default printer, if it is still installed. A printer that has
+/// since been removed is cleared from `printer.json` with a warning.
+fn stored_default_printer(app_handle: &tauri::AppHandle) -> Option<String> {
+    let name = read_printer_prefs(app_handle).default_printer?;
+
+    match query_printers() {
+        Ok(printers) if !printers.iter().any(|p| p == &name) => {
+            warn!("⚠️ Saved default printer '{}' is no longer installed — clearing it", name);
+            if let Err(e) = write_printer_prefs(app_handle, &PrinterPrefs::default()) {
+                warn!("⚠️ {}", e);
+            }
+            None
+        }
+        // If listing fails, trust the saved name; the print path falls back
+        // to the system default when it turns out to be unavailable.
+        _ => Some(name),
+    }
+}
+
+#[tauri::command]
+async fn get_default_printer(app_handle: tauri::AppHandle) -> Result<Option<String>, AppError> {
+    Ok(stored_default_printer(&app_handle))
+}
+
+#[tauri::command]
+async fn set_default_printer(app_handle: tauri::AppHandle, name: Option<String>) -> Result<(), AppError> {
+    let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    write_printer_prefs(&app_handle, &PrinterPrefs { default_printer: name.clone() }).map_err(AppError::Io)?;
+    info!("🖨️ Default printer set to {}", name.as_deref().unwrap_or("<system default>"));
+    Ok(())
+}
+
+/// Payload of `second-instance`: the blocked launch's arguments (without
+/// the executable) and working directory.
+#[derive(Clone, Serialize)]
+struct SecondInstance {
+    args: Vec<String>,
+    cwd: String,
+}
 
 fn main() {
-    let child_handle: Arc<Mutex<Option<CommandChild>>> = Arc::new(Mutex::new(None));
+    crash_report::install_panic_hook();
+    let child_handle: backend::ChildHandle = Arc::new(Mutex::new(None));
 
     tauri::Builder::default()
-        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+        // A second launch exits at once; its arguments are handed to this
+        // instance as `second-instance { args, cwd }` and the window is raised.
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            info!("🪟 Second launch blocked, args {:?}; focusing the running instance", argv);
+            let args = argv.into_iter().skip(1).collect();
+            let _ = app.emit("second-instance", SecondInstance { args, cwd });
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.show();
                 let _ = window.unminimize();
@@ -482,215 +796,418 @@ fn main() {
                         path: PathBuf::from("logs"),
                         file_name: Some("siri-billing-app.log".into()),
                     }),
-                    Target::new(TargetKind::Webview),
+                    // Backend output reaches the UI batched; see `event_throttle.rs`.
+                    Target::new(TargetKind::Webview)
+                        .filter(|metadata| metadata.target() != event_throttle::BACKEND_OUTPUT_TARGET),
                 ])
-                .level(log::LevelFilter::Debug)
+                // Let everything through the plugin; the effective level is
+                // applied from settings via `log::set_max_level` in setup.
+                .level(log::LevelFilter::Trace)
+                // Same layout as the plugin default, plus the session ID.
+                .format(|out, message, record| {
+                    out.finish(format_args!(
+                        "[{}][{}][{}][{}] {}",
+                        timestamps::log_timestamp(),
+                        session::session_id(),
+                        record.target(),
+                        record.level(),
+                        message
+                    ))
+                })
                 .max_file_size(10_000_000)
                 .build(),
         )
         .plugin(tauri_plugin_shell::init())
+        .on_page_load(recovery::on_page_load)
+        .manage(startup_timing::StartupTimings::default())
+        .manage(event_bus::EventBus::default())
+        .manage(event_bus::LifecycleCounters::default())
+        .manage(event_throttle::EventThrottle::default())
+        .manage(updater::UpdateState::default())
+        .manage(idle::IdleState::default())
+        .manage(frontend_watchdog::FrontendWatchdog::default())
+        .manage(restart_schedule::RestartScheduler::default())
+        .manage(backend::BackendState::new(Arc::clone(&child_handle)))
+        .manage(backend::BackendLogBuffer::default())
+        .manage(backend_log_file::BackendLogFile::default())
+        .manage(backend_supervisor::BackendSupervisor::default())
+        .manage(resource_monitor::ResourceMonitor::default())
+        .manage(license::LicenseState::default())
+        .manage(pin::PinState::default())
+        .manage(audit_log::AuditLog::default())
+        .manage(kiosk::KioskState::default())
+        .manage(demo::DemoState::default())
+        .manage(customer_display::CustomerDisplayState::default())
+        .manage(network::NetworkState::default())
+        .manage(factory_reset::FactoryResetState::default())
+        .manage(preflight::PreflightState::default())
+        .manage(maintenance::MaintenanceState::default())
+        .manage(migrations::MigrationState::default())
+        .manage(window_state::WindowStateSaver::default())
+        .manage(backend_trace::BackendTraceState::default())
+        .manage(recent_exports::RecentExports::default())
+        .manage(sales_export::SalesExportState::default())
+        .manage(safe_mode::SafeModeState::default())
+        .manage(feature_flags::FeatureFlags::default())
+        .manage(diagnostics_upload::DiagnosticsUpload::default())
         .invoke_handler(tauri::generate_handler![
-            check_for_updates,
-            install_update,
+            ping,
+            get_app_info,
+            system_info::get_system_info,
+            updater::check_for_updates,
+            updater::get_update_notes,
+            updater::updates_status,
+            updater::update_history,
+            updater::install_update,
+            updater::cancel_update,
+            updater::verify_update_artifact,
+            updater::update_cache_info,
+            updater::clear_update_cache,
+            updater::check_for_update_from,
+            updater::apply_update_and_restart,
+            clear_cache,
+            backups::create_backup,
+            backups::prune_backups_now,
+            db_checkpoint::checkpoint_db,
+            factory_reset::prepare_factory_reset,
+            factory_reset::factory_reset,
+            reveal_file,
+            profiles::list_profiles,
+            profiles::create_profile,
+            profiles::switch_profile,
+            profiles::rename_profile,
+            open_logs_folder,
+            settings::get_settings,
+            settings::update_settings,
+            settings::set_log_level,
+            feature_flags::get_flags,
+            startup_timing::startup_timings,
+            settings_transfer::export_settings,
+            settings_transfer::import_settings,
+            theme::get_theme,
+            theme::set_theme,
+            menu::set_menu_visible,
+            kiosk::exit_kiosk,
+            always_on_top::get_always_on_top,
+            always_on_top::set_always_on_top,
+            customer_display::enable_customer_display,
+            customer_display::disable_customer_display,
+            backend::force_kill_backend,
+            backend::restart_backend,
+            backend::ping_backend,
+            backend::wait_for_backend,
+            event_bus::lifecycle_counters,
+            backend_trace::set_backend_trace,
+            backend_trace::backend_trace_status,
+            backend_dump::dump_backend_state,
+            backend::check_backend_binary,
+            backend::get_backend_logs,
+            backend::backend_exit_history,
+            backend::backend_version,
+            backend::backend_status,
+            backend::get_backend_status,
+            backend::get_backend_port,
+            backend_rpc::backend_invoke,
+            backend::pause_backend,
+            backend::resume_backend,
+            backend_affinity::set_backend_affinity,
+            resource_monitor::backend_resource_usage,
+            backend_config::get_backend_config,
+            backend_config::set_backend_config,
+            backend_config::set_backend_log_level,
+            data_import::import_data,
+            audit_log::record_event,
+            audit_log::verify_log_chain,
+            audit_log::export_audit,
+            diagnostics::export_support_bundle,
+            diagnostics::collect_diagnostics,
+            diagnostics_upload::upload_diagnostics_now,
+            log_export::export_logs_range,
+            sales_export::set_export_schedule,
+            sales_export::trigger_export_now,
+            diagnostics::decrypt_support_bundle,
+            screenshot::capture_screenshot,
+            disk::disk_status,
+            network::network_status,
+            idle::reset_idle_timer,
+            license::apply_license,
+            license::get_license,
+            pin::pin_status,
+            pin::set_pin,
+            pin::verify_pin,
+            onboarding::is_first_run,
+            onboarding::complete_onboarding,
+            preflight::run_preflight,
+            preflight::get_preflight_report,
+            maintenance::enter_maintenance,
+            maintenance::exit_maintenance,
+            migrations::run_migrations,
+            frontend_watchdog::ping_frontend,
+            event_throttle::ack_events,
+            recovery::report_frontend_load,
+            recovery::restart_app,
+            restart_schedule::schedule_restart,
+            restart_schedule::cancel_scheduled_restart,
+            restart_schedule::set_sale_in_progress,
+            demo::start_demo_mode,
+            demo::end_demo_mode,
+            demo::demo_mode_status,
+            safe_mode::start_safe_mode,
+            safe_mode::end_safe_mode,
+            safe_mode::safe_mode_status,
             print_html_native,      // ✅ NEW
             list_printers_native,   // ✅ NEW
+            get_default_printer,
+            set_default_printer,
+            z_report::print_z_report,
+            receipt_template::validate_receipt_template,
+            recent_exports::recent_exports,
+            recent_exports::record_export,
+            recent_exports::open_export,
+            printer_test::test_printer,
+            self_test::run_self_test,
         ])
-        .setup({
-            let child_handle = Arc::clone(&child_handle);
-            move |app| {
-                if let Err(e) = cleanup_old_logs(app.app_handle()) {
-                    eprintln!("⚠️ Failed to cleanup old logs: {}", e);
-                }
+        .setup(|app| {
+            startup_timing::mark(app.handle(), "setup_started");
+            event_bus::start_subscribers(app.handle());
+            let app_paths = paths::AppPaths::resolve(app.app_handle());
+            crash_report::set_dir(&app_paths);
+            let app_data_dir = app_paths.root.clone();
+            if let Err(e) = ensure_app_dirs(&app_data_dir) {
+                error!("❌ Failed to initialize app data directory {:?}: {}", app_data_dir, e);
+            }
+            backend::adopt_legacy_data_dir(&app_paths);
 
-                info!("=================================================");
-                info!("🚀 Siri Billing App Starting");
-                info!("=================================================");
-                info!("📦 App version: {}", app.package_info().version);
-                info!("🔧 Bundle identifier: {}", app.config().identifier);
-
-                let app_data_dir = app.path().app_data_dir()
-                    .unwrap_or_else(|_| PathBuf::from("unknown"));
-                info!("📂 App data directory: {:?}", app_data_dir);
-                info!("📝 Logs directory: {:?}", app_data_dir.join("logs"));
-
-                info!("=================================================");
-                info!("🔄 Updater Configuration");
-                info!("=================================================");
-                info!("✅ Updater plugin initialized successfully");
-
-                let handle = app.app_handle();
-
-                info!("=================================================");
-                info!("🔌 Starting Backend Sidecar");
-                info!("=================================================");
-
-                // Guard against a second backend process: whether this is a
-                // duplicate launch that slipped past single-instance (e.g. a
-                // rapid double-click race) or a leftover orphan from a prior
-                // crash, spawning another Siribilling-backend.exe on top of a
-                // live one causes two writers to hit the same local JSON files
-                // (bills/products/inventory) with no cross-process locking,
-                // which can corrupt them. So before spawning, check if a
-                // backend is already answering on :8080; if so, reuse it
-                // instead of starting a duplicate.
-                let backend_already_running = Client::builder()
-                    .timeout(Duration::from_millis(800))
-                    .build()
-                    .ok()
-                    .and_then(|c| c.get("http://localhost:8080/api/health").send().ok())
-                    .map(|r| r.status().is_success())
-                    .unwrap_or(false);
-
-                if backend_already_running {
-                    info!("✅ Backend already running and healthy on :8080 — reusing it, not spawning a duplicate.");
-                } else {
-                    let cmd = handle.shell().sidecar("Siribilling-backend")?;
-                    let (mut rx, command_child) = cmd.spawn()?;
-                    let pid = command_child.pid();
-
-                    info!("✅ Backend spawned successfully");
-                    info!("🆔 Process ID: {}", pid);
-
-                    *child_handle.lock().unwrap() = Some(command_child);
-
-                    let child_handle_clone = Arc::clone(&child_handle);
-                    tauri::async_runtime::spawn(async move {
-                        while let Some(event) = rx.recv().await {
-                            match event {
-                                CommandEvent::Stdout(line) => {
-                                    let output = String::from_utf8_lossy(&line);
-                                    info!("🔵 [Backend] {}", output.trim());
-                                }
-                                CommandEvent::Stderr(line) => {
-                                    let output = String::from_utf8_lossy(&line);
-                                    error!("🔴 [Backend] {}", output.trim());
-                                }
-                                CommandEvent::Error(err) => {
-                                    error!("❌ [Backend] Error: {}", err);
-                                }
-                                CommandEvent::Terminated(payload) => {
-                                    warn!("⚠️ [Backend] Terminated with code: {:?}", payload.code);
-                                }
-                                _ => {}
-                            }
-                        }
+            let settings_state = settings::SettingsState::load(&app_data_dir);
+            settings::apply(&settings_state.get());
+            settings::apply_env_log_level();
+            let start_maximized = settings_state.get().window.start_maximized;
+            app.manage(settings_state);
+            app.manage(app_paths.clone());
+            feature_flags::load(app.handle());
+            startup_timing::mark(app.handle(), "settings_loaded");
+
+            if let Some(window) = app.get_webview_window("main") {
+                // Restore the last un-maximized geometry first so that
+                // un-maximizing later returns to where the user left it.
+                let _ = window.unmaximize();
+                let saved_maximized = window_state::restore(&window);
+                if start_maximized || saved_maximized.unwrap_or(false) {
+                    let _ = window.maximize();
+                }
+            }
 
-                        let _ = child_handle_clone.lock().unwrap().take();
-                        warn!("🛑 Backend sidecar process ended");
-                    });
+            // The main window starts hidden so the theme is in place
+            // before its first paint. With the splash enabled it stays
+            // hidden until the backend is ready.
+            let splash_shown = splash::show(app.handle());
+            theme::apply(app.handle());
+            if !splash_shown {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    startup_timing::mark(app.handle(), "window_shown");
                 }
+            }
+
+            license::load_license(app.handle());
+            pin::require_pin_on_startup(app.handle());
+            onboarding::announce_first_run(app.handle());
 
-                let main_win = app.get_webview_window("main").unwrap();
+            if let Err(e) = cleanup_old_logs(&app_paths.logs_dir(), 7, 50 * 1024 * 1024) {
+                warn!("⚠️ Failed to cleanup old logs: {}", e);
+            }
 
-                #[cfg(debug_assertions)]
-                {
-                    info!("🔧 Opening DevTools...");
-                    main_win.open_devtools();
+            info!("=================================================");
+            info!("🚀 Siri Billing App Starting");
+            info!("=================================================");
+            info!("📦 App version: {}", app.package_info().version);
+            info!("🆔 Session ID: {}", session::session_id());
+            info!("🔧 Bundle identifier: {}", app.config().identifier);
+            info!("📝 Log level: {}", log::max_level());
+
+            info!("🏪 Store profile: {}", app_paths.profile);
+            info!("📂 App data directory: {:?}", app_data_dir);
+            info!("📝 Logs directory: {:?}", app_paths.logs_dir());
+
+            info!("=================================================");
+            info!("🔄 Updater Configuration");
+            info!("=================================================");
+            info!("✅ Updater plugin initialized successfully");
+            updater::confirm_applied_update(app.handle());
+            proxy::log_proxy(app.handle());
+            ca_certs::log_ca_certificates(app.handle());
+            updater::probe_availability(app.handle());
+            updater::start_update_schedule(app.handle().clone());
+
+            info!("=================================================");
+            info!("🔌 Starting Backend Sidecar");
+            info!("=================================================");
+
+            demo::restore_state(app.handle());
+            if maintenance::is_active(app.handle()) {
+                splash::finish(app.handle());
+                maintenance::wait_for_exit(app.handle());
+            } else if preflight::preflight(app.handle()).passed {
+                // Antivirus removing or locking the bundled binary ends up
+                // here; say so instead of letting setup fail silently.
+                if let Err(e) = backend::start_backend(app.handle()) {
+                    splash::finish(app.handle());
+                    show_fatal_error(
+                        app.handle(),
+                        &format!(
+                            "Siri Billing could not start its backend service.\n\n{}\n\n\
+                             If antivirus software quarantined the app's files, restore them \
+                             or reinstall the app.",
+                            e
+                        ),
+                    );
+                    return Ok(());
                 }
+            } else {
+                error!("❌ Preflight failed; backend not started until the issues are fixed");
+                splash::finish(app.handle());
+            }
 
-                let child_handle_clone = Arc::clone(&child_handle);
-                main_win.on_window_event(move |event| {
-                    match event {
-                        WindowEvent::CloseRequested { .. } => {
-                            info!("=================================================");
-                            info!("🚪 Window Close Requested - Initiating Graceful Shutdown");
-                            info!("=================================================");
-
-                            if let Some(child) = child_handle_clone.lock().unwrap().as_ref() {
-                                let pid = child.pid();
-                                info!("📡 Sending graceful shutdown request to backend PID: {}", pid);
-                                
-                                let client = Client::builder()
-                                    .timeout(Duration::from_secs(5))
-                                    .build()
-                                    .unwrap_or_else(|_| Client::new());
-                                    
-                                match client.post("http://localhost:8080/api/shutdown")
-                                    .body("shutdown from tauri")
-                                    .send() {
-                                        Ok(response) => {
-                                            info!("✅ Backend shutdown signal sent: HTTP {}", response.status());
-                                        }
-                                        Err(e) => {
-                                            warn!("⚠️ Failed to send shutdown signal: {}", e);
-                                        }
-                                }
-                            }
+            let Some(main_win) = main_window(app.handle()) else {
+                splash::finish(app.handle());
+                show_fatal_error(
+                    app.handle(),
+                    "Siri Billing could not open its main window. Please reinstall the app \
+                     or contact support with the log files.",
+                );
+                return Ok(());
+            };
+
+            if let Err(e) = menu::install(&main_win) {
+                warn!("⚠️ Failed to build application menu: {}", e);
+            }
+            if let Err(e) = tray::install(app.handle()) {
+                warn!("⚠️ Failed to install tray icon: {}", e);
+            }
+            let kiosk = app.state::<settings::SettingsState>().get().window.kiosk;
+            kiosk::enter_if_configured(&main_win, kiosk);
+            always_on_top::apply(app.handle());
+            window_title::refresh(app.handle());
+            customer_display::init(app.handle());
+            customer_display::restore(app.handle());
+
+            #[cfg(debug_assertions)]
+            {
+                info!("🔧 Opening DevTools...");
+                main_win.open_devtools();
+            }
 
-                            info!("⏳ Waiting 5 seconds for backend graceful shutdown...");
-                            thread::sleep(Duration::from_secs(5));
-
-                            if let Some(child) = child_handle_clone.lock().unwrap().take() {
-                                let pid = child.pid();
-                                info!("🔄 Force terminating backend process (PID: {})", pid);
-                                
-                                #[cfg(target_os = "windows")]
-                                {
-                                    let _ = Command::new("taskkill")
-                                        .args(&["/PID", &pid.to_string(), "/T", "/F"])
-                                        .status();
-                                }
-                                
-                                #[cfg(not(target_os = "windows"))]
-                                {
-                                    let _ = Command::new("kill")
-                                        .args(&["-9", &pid.to_string()])
-                                        .status();
-                                }
-                                
-                                info!("✅ Backend force terminated");
+            idle::start_idle_monitor(app.handle().clone());
+            frontend_watchdog::start_frontend_watchdog(app.handle().clone());
+            event_throttle::start_event_flusher(app.handle().clone());
+            backend::install_signal_cleanup(Arc::clone(&app.state::<backend::BackendState>().child));
+            restart_schedule::start_restart_scheduler(app.handle().clone());
+            sales_export::start_export_scheduler(app.handle().clone());
+            resource_monitor::start_resource_monitor(app.handle().clone());
+            backend_health::start_health_monitor(app.handle().clone());
+            crash_report::offer_reports(app.handle());
+            network::start_network_monitor(app.handle().clone());
+            feature_flags::start_flags_refresh(app.handle().clone());
+            diagnostics_upload::start_upload_queue(app.handle().clone());
+
+            let window_app = app.handle().clone();
+            let closing = AtomicBool::new(false);
+            main_win.on_window_event(move |event| {
+                match event {
+                    WindowEvent::CloseRequested { api, .. } => {
+                        if window_app.state::<kiosk::KioskState>().is_active() {
+                            info!("🔐 Close blocked: kiosk mode is active");
+                            api.prevent_close();
+                            return;
+                        }
+                        if window_app.state::<settings::SettingsState>().get().window.minimize_to_tray {
+                            info!("🖥️ Window hidden to the tray; backend keeps running");
+                            api.prevent_close();
+                            if let Some(window) = window_app.get_webview_window("main") {
+                                let _ = window.hide();
                             }
+                            return;
+                        }
+
+                        // Keep the window up while the backend flushes, and
+                        // drop it once the shutdown is done. `destroy` doesn't
+                        // ask again.
+                        api.prevent_close();
+                        if closing.swap(true, Ordering::SeqCst) {
+                            info!("🚪 Close requested again; shutdown already in progress");
+                            return;
                         }
-                        WindowEvent::Focused(focused) => {
-                            if *focused {
-                                debug!("🔍 Window focused");
-                            } else {
-                                debug!("🔍 Window unfocused");
+
+                        info!("=================================================");
+                        info!("🚪 Window Close Requested - Initiating Graceful Shutdown");
+                        info!("=================================================");
+
+                        if let Some(window) = window_app.get_webview_window("main") {
+                            window_state::save(&window);
+                        }
+
+                        let app = window_app.clone();
+                        thread::spawn(move || {
+                            let state = app.state::<backend::BackendState>();
+                            if let Some(result) = backend::shutdown_backend(&state) {
+                                backend::log_kill_outcome(&result);
                             }
+                            if let Some(window) = app.get_webview_window("main") {
+                                let _ = window.destroy();
+                            }
+                        });
+                    }
+                    WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+                        if let Some(window) = window_app.get_webview_window("main") {
+                            window_state::schedule_save(&window);
+                        }
+                    }
+                    WindowEvent::ThemeChanged(theme) => {
+                        theme::on_os_theme_changed(&window_app, *theme);
+                    }
+                    WindowEvent::Focused(focused) => {
+                        if *focused {
+                            debug!("🔍 Window focused");
+                            window_app.state::<idle::IdleState>().touch();
+                        } else {
+                            debug!("🔍 Window unfocused");
                         }
-                        _ => {}
                     }
-                });
+                    _ => {}
+                }
+            });
 
-                info!("=================================================");
-                info!("✅ Tauri Setup Complete");
-                info!("=================================================");
+            info!("=================================================");
+            info!("✅ Tauri Setup Complete");
+            info!("=================================================");
 
-                Ok(())
-            }
+            Ok(())
         })
         .build(tauri::generate_context!())
         .expect("error building app")
         .run({
             let child_handle = Arc::clone(&child_handle);
-            move |_app_handle, event| {
+            move |app_handle, event| {
                 match event {
                     RunEvent::Exit => {
                         info!("=================================================");
                         info!("🚪 App Exit Event - Final Cleanup");
                         info!("=================================================");
 
+                        app_handle.state::<backend::BackendState>().stopping.store(true, Ordering::SeqCst);
+                        backend_health::stop();
                         if let Some(child) = child_handle.lock().unwrap().take() {
                             let pid = child.pid();
                             info!("🔄 Final cleanup of backend process (PID: {})", pid);
-                            
-                            #[cfg(target_os = "windows")]
-                            {
-                                let _ = Command::new("taskkill")
-                                    .args(&["/PID", &pid.to_string(), "/T", "/F"])
-                                    .status();
-                            }
-                            
-                            #[cfg(not(target_os = "windows"))]
-                            {
-                                let _ = Command::new("kill")
-                                    .args(&["-9", &pid.to_string()])
-                                    .status();
-                            }
-                            
+                            backend::log_kill_outcome(&backend::kill_process_tree(pid));
                             info!("✅ Final cleanup complete");
                         }
 
+                        // Drop the single-instance lock now rather than
+                        // leaving it to process teardown, so a relaunch right
+                        // after exit isn't turned away as a second instance.
+                        tauri_plugin_single_instance::destroy(app_handle);
+
                         info!("=================================================");
                     }
                     RunEvent::ExitRequested { .. } => {
@@ -702,31 +1219,138 @@ fn main() {
         });
 }
 
-/// Clean old log files on startup
-fn cleanup_old_logs(app_handle: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let app_data_dir = app_handle.path().app_data_dir()?;
-    let logs_dir = app_data_dir.join("logs");
-    
-    if logs_dir.exists() {
-        println!("🧹 Cleaning old logs from: {:?}", logs_dir);
-        if let Ok(entries) = fs::read_dir(&logs_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_file() {
-                    if let Some(extension) = path.extension() {
-                        if extension == "log" {
-                            match fs::remove_file(&path) {
-                                Ok(_) => println!("✅ Deleted old log: {:?}", path.file_name()),
-                                Err(e) => eprintln!("❌ Failed to delete {:?}: {}", path, e),
-                            }
-                        }
-                    }
-                }
+/// Subdirectories every install is expected to have under the app data dir.
+const APP_SUBDIRS: [&str; 4] = ["logs", "crashes", "backups", CACHE_TMP_DIR];
+
+/// The main window, recreated from its `tauri.conf.json` entry if it wasn't
+/// created (e.g. the label was changed). `None` if that fails too.
+fn main_window(app: &tauri::AppHandle) -> Option<tauri::WebviewWindow> {
+    if let Some(window) = app.get_webview_window("main") {
+        return Some(window);
+    }
+    error!("❌ Main window 'main' not found — check the window label in tauri.conf.json");
+
+    let windows = &app.config().app.windows;
+    let Some(config) = windows.iter().find(|w| w.label == "main").or(windows.first()) else {
+        error!("❌ No window is configured in tauri.conf.json");
+        return None;
+    };
+    let mut config = config.clone();
+    config.label = "main".to_string();
+    match WebviewWindowBuilder::from_config(app, &config).and_then(|builder| builder.build()) {
+        Ok(window) => {
+            warn!("⚠️ Recreated the main window from its configuration");
+            let _ = window.show();
+            Some(window)
+        }
+        Err(e) => {
+            error!("❌ Failed to recreate the main window: {}", e);
+            None
+        }
+    }
+}
+
+/// Tell the user about an unrecoverable startup problem, then exit.
+fn show_fatal_error(app: &tauri::AppHandle, message: &str) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+    error!("❌ Fatal: {}", message);
+    let message = match app.try_state::<paths::AppPaths>() {
+        Some(paths) => format!("{}\n\nLog files: {}", message, logs_folder(&paths).display()),
+        None => message.to_string(),
+    };
+    // The app exits after the dialog; the next start uploads the bundle.
+    if let Err(e) = diagnostics_upload::enqueue(app, "fatal") {
+        warn!("⚠️ Failed to queue diagnostics bundle: {}", e);
+    }
+    let exit_app = app.clone();
+    app.dialog()
+        .message(message)
+        .title("Siri Billing")
+        .kind(MessageDialogKind::Error)
+        .show(move |_| exit_app.exit(1));
+}
+
+/// Create the app data directory layout if it is missing, so the rest of the
+/// app can assume it exists. Safe to call on every launch; logs only what it
+/// actually had to create.
+fn ensure_app_dirs(app_data_dir: &Path) -> std::io::Result<()> {
+    if !app_data_dir.exists() {
+        fs::create_dir_all(app_data_dir)?;
+        info!("📁 Created app data directory: {:?}", app_data_dir);
+    }
+
+    for sub in APP_SUBDIRS {
+        let dir = app_data_dir.join(sub);
+        if !dir.exists() {
+            fs::create_dir_all(&dir)?;
+            info!("📁 Created {:?}", dir);
+        }
+    }
+
+    let settings = app_data_dir.join(settings::SETTINGS_FILE);
+    if !settings.exists() {
+        paths::write_atomic(&settings, b"{}")?;
+        info!("📝 Created default settings file: {:?}", settings);
+    }
+
+    Ok(())
+}
+
+/// Apply log retention on startup: delete `.log` files last modified more
+/// than `max_age_days` ago, then the oldest of the rest while the directory
+/// holds more than `max_total_bytes`. The newest file (the one being
+/// written) is always kept.
+fn cleanup_old_logs(
+    logs_dir: &Path,
+    max_age_days: u64,
+    max_total_bytes: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !logs_dir.exists() {
+        return Ok(());
+    }
+
+    let mut logs: Vec<(SystemTime, u64, PathBuf)> = fs::read_dir(logs_dir)?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|p| {
+            let meta = fs::metadata(&p).ok()?;
+            Some((meta.modified().ok()?, meta.len(), p))
+        })
+        .collect();
+    // Newest first.
+    logs.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let max_age = Duration::from_secs(max_age_days * 24 * 60 * 60);
+    let now = SystemTime::now();
+    let (mut deleted, mut freed, mut kept, mut kept_bytes) = (0, 0u64, 0, 0u64);
+    for (i, (modified, size, path)) in logs.iter().enumerate() {
+        let too_old = now.duration_since(*modified).unwrap_or_default() > max_age;
+        let over_cap = kept_bytes + size > max_total_bytes;
+        if i == 0 || !(too_old || over_cap) {
+            kept += 1;
+            kept_bytes += size;
+            continue;
+        }
+        match fs::remove_file(path) {
+            Ok(_) => {
+                deleted += 1;
+                freed += size;
+            }
+            Err(e) => {
+                warn!("⚠️ Failed to delete old log {:?}: {}", path, e);
+                kept += 1;
+                kept_bytes += size;
             }
         }
-    } else {
-        println!("📁 Logs directory doesn't exist yet, will be created");
     }
-    
+
+    info!(
+        "🧹 Log retention: deleted {} files, freed {} MB, kept {} files",
+        deleted,
+        freed / (1024 * 1024),
+        kept
+    );
     Ok(())
 }
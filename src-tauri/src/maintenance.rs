@@ -0,0 +1,122 @@
+//! Maintenance mode: while `maintenance.lock` exists in the app data dir the
+//! backend is not started, so no sales can be taken during upgrades or DB
+//! migrations. The frontend is told via `maintenance-mode` (`true`/`false`)
+//! and shows a maintenance screen. A poller watches for the lock to be
+//! removed — by `exit_maintenance` or by hand — and then starts the backend.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use log::{error, info};
+use tauri::{Emitter, Manager};
+
+use crate::backend::{self, BackendState};
+use crate::error::AppError;
+use crate::paths::{self, AppPaths};
+
+const MAINTENANCE_LOCK: &str = "maintenance.lock";
+const LOCK_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+#[derive(Default)]
+pub struct MaintenanceState {
+    /// Set while a poller thread is waiting for the lock to disappear.
+    polling: AtomicBool,
+}
+
+fn lock_path<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> PathBuf {
+    app.state::<AppPaths>().root.join(MAINTENANCE_LOCK)
+}
+
+pub fn is_active<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> bool {
+    lock_path(app).exists()
+}
+
+/// Create the lock without stopping the backend, for work (like migrations)
+/// that still needs it running. A crash before `remove_lock` comes back up
+/// in maintenance mode.
+pub fn create_lock<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<(), String> {
+    let path = lock_path(app);
+    paths::write_atomic(&path, b"").map_err(|e| format!("Failed to create {:?}: {}", path, e))
+}
+
+pub fn remove_lock<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    let _ = fs::remove_file(lock_path(app));
+}
+
+/// Wait (in the background) for the lock to be removed, then start the
+/// backend. Only one poller runs at a time.
+pub fn wait_for_exit(app: &tauri::AppHandle) {
+    let state = app.state::<MaintenanceState>();
+    if state.polling.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    info!("🛠️ Maintenance mode active — backend will start once {:?} is removed", lock_path(app));
+    let _ = app.emit("maintenance-mode", true);
+    crate::window_title::refresh(app);
+
+    let app = app.clone();
+    thread::spawn(move || {
+        while is_active(&app) {
+            thread::sleep(LOCK_POLL_INTERVAL);
+        }
+
+        app.state::<MaintenanceState>().polling.store(false, Ordering::SeqCst);
+        info!("🛠️ Maintenance lock removed — leaving maintenance mode");
+        let _ = app.emit("maintenance-mode", false);
+        crate::window_title::refresh(&app);
+
+        if let Err(e) = backend::start_backend(&app) {
+            error!("❌ Failed to start backend after maintenance: {}", e);
+        }
+    });
+}
+
+async fn enter(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    create_lock(&app_handle).map_err(AppError::Io)?;
+    info!("🛠️ Entering maintenance mode");
+
+    let app = app_handle.clone();
+    let stopped = tauri::async_runtime::spawn_blocking(move || backend::stop_backend(&app.state::<BackendState>()))
+        .await
+        .map_err(|e| format!("Backend shutdown failed: {}", e))?;
+    if let Err(e) = stopped {
+        // Still serving, so not in maintenance after all.
+        remove_lock(&app_handle);
+        return Err(AppError::Backend(e));
+    }
+
+    wait_for_exit(&app_handle);
+    Ok(())
+}
+
+/// Create the lock, stop the backend and wait for maintenance to end.
+#[tauri::command]
+pub async fn enter_maintenance(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    let result = enter(app_handle.clone()).await;
+    crate::audit_log::privileged(&app_handle, "enter_maintenance", result)
+}
+
+fn exit(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    let path = lock_path(&app_handle);
+    match fs::remove_file(&path) {
+        Ok(()) => {
+            info!("🛠️ Exit from maintenance mode requested");
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(AppError::Conflict("Not in maintenance mode.".to_string()))
+        }
+        Err(e) => Err(AppError::Io(format!("Failed to remove {:?}: {}", path, e))),
+    }
+}
+
+/// Remove the lock; the poller then starts the backend.
+#[tauri::command]
+pub fn exit_maintenance(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    let result = exit(app_handle.clone());
+    crate::audit_log::privileged(&app_handle, "exit_maintenance", result)
+}
@@ -0,0 +1,111 @@
+//! Native application menu with keyboard accelerators.
+//!
+//! Items that belong to the sale flow (`New Sale`, `Print Last Receipt`)
+//! are forwarded to the frontend as `menu-new-sale` / `menu-print-last-receipt`
+//! events; `Open Logs` and `Check for Updates` are handled in the shell.
+//! The menu is hidden when `window.show_menu` is off (kiosk terminals).
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::menu::{Menu, MenuBuilder, MenuEvent, MenuItemBuilder, PredefinedMenuItem, SubmenuBuilder};
+use tauri::{Emitter, Manager, WebviewWindow, Wry};
+
+use crate::error::AppError;
+use crate::paths::AppPaths;
+use crate::settings::SettingsState;
+use crate::updater;
+
+const NEW_SALE: &str = "new_sale";
+const PRINT_LAST_RECEIPT: &str = "print_last_receipt";
+const OPEN_LOGS: &str = "open_logs";
+const CHECK_FOR_UPDATES: &str = "check_for_updates";
+
+/// Payload of `update-check-result`, sent after a menu-triggered check.
+#[derive(Clone, Serialize)]
+struct UpdateCheckResult {
+    ok: bool,
+    message: String,
+}
+
+fn build(window: &WebviewWindow) -> tauri::Result<Menu<Wry>> {
+    let new_sale = MenuItemBuilder::with_id(NEW_SALE, "New Sale")
+        .accelerator("CmdOrCtrl+N")
+        .build(window)?;
+    let print_last = MenuItemBuilder::with_id(PRINT_LAST_RECEIPT, "Print Last Receipt")
+        .accelerator("CmdOrCtrl+P")
+        .build(window)?;
+    let open_logs = MenuItemBuilder::with_id(OPEN_LOGS, "Open Logs").build(window)?;
+    let check_updates = MenuItemBuilder::with_id(CHECK_FOR_UPDATES, "Check for Updates").build(window)?;
+
+    let file = SubmenuBuilder::new(window, "File")
+        .item(&new_sale)
+        .item(&print_last)
+        .separator()
+        .item(&PredefinedMenuItem::quit(window, None)?)
+        .build()?;
+    let help = SubmenuBuilder::new(window, "Help")
+        .item(&open_logs)
+        .item(&check_updates)
+        .build()?;
+
+    MenuBuilder::new(window).item(&file).item(&help).build()
+}
+
+fn on_menu_event(window: &WebviewWindow, event: MenuEvent) {
+    let app = window.app_handle().clone();
+    match event.id().as_ref() {
+        NEW_SALE => {
+            let _ = app.emit("menu-new-sale", ());
+        }
+        PRINT_LAST_RECEIPT => {
+            let _ = app.emit("menu-print-last-receipt", ());
+        }
+        OPEN_LOGS => {
+            if let Err(e) = crate::open_logs_folder(app.state::<AppPaths>()) {
+                warn!("⚠️ {}", e);
+            }
+        }
+        CHECK_FOR_UPDATES => {
+            tauri::async_runtime::spawn(async move {
+                let result = updater::check_for_updates(app.clone()).await;
+                let payload = match result {
+                    Ok(Some(version)) => {
+                        UpdateCheckResult { ok: true, message: format!("Update available: {}", version) }
+                    }
+                    Ok(None) => UpdateCheckResult { ok: true, message: "No update available.".to_string() },
+                    Err(e) => UpdateCheckResult { ok: false, message: e.message().to_string() },
+                };
+                let _ = app.emit("update-check-result", payload);
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Attach the menu to `window`, hidden if the setting says so.
+pub fn install(window: &WebviewWindow) -> tauri::Result<()> {
+    window.set_menu(build(window)?)?;
+    let handler_window = window.clone();
+    window.on_menu_event(move |_, event| on_menu_event(&handler_window, event));
+
+    if !window.app_handle().state::<SettingsState>().get().window.show_menu {
+        window.hide_menu()?;
+    }
+    info!("📋 Application menu installed");
+    Ok(())
+}
+
+/// Show or hide the menu and remember the choice.
+#[tauri::command]
+pub fn set_menu_visible(app_handle: tauri::AppHandle, visible: bool) -> Result<(), AppError> {
+    app_handle
+        .state::<SettingsState>()
+        .update(serde_json::json!({ "window": { "show_menu": visible } }))?;
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or_else(|| AppError::NotFound("Main window not found.".to_string()))?;
+    let result = if visible { window.show_menu() } else { window.hide_menu() };
+    result.map_err(|e| format!("Failed to update menu: {}", e))?;
+    info!("📋 Application menu {}", if visible { "shown" } else { "hidden" });
+    Ok(())
+}
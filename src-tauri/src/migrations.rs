@@ -0,0 +1,251 @@
+//! Running the backend's data migrations from the shell.
+//!
+//! The backend owns its schema and the migrations that move it forward;
+//! the shell only sequences them safely. `GET /api/migrations` reports
+//! `{ current, available: [{ version, name }] }` and
+//! `POST /api/migrations/apply { version }` applies exactly one migration
+//! (a no-op if it is already applied) and answers `{ current }`.
+//!
+//! `run_migrations` refuses to run mid-sale, in demo mode, or when the data
+//! is at a newer schema than this backend knows (a downgrade). Otherwise it
+//! zips the backend's `data/` to `backups/pre-migration-<ts>.zip`, takes the
+//! maintenance lock so the frontend shows its maintenance screen, and
+//! applies the pending migrations one at a time in version order, emitting
+//! `migration-progress { stage, version, name, index, total }`. With
+//! nothing pending it returns straight away, so it is safe to call on
+//! every start.
+//!
+//! A failed migration leaves the app in maintenance mode with the backend
+//! stopped: the data may be half-migrated and should be checked (or the
+//! backup restored) before `exit_maintenance`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{Emitter, Manager};
+
+use crate::backend::{self, BackendState};
+use crate::backend_transport::BackendRequest;
+use crate::error::AppError;
+use crate::paths::AppPaths;
+use crate::restart_schedule::RestartScheduler;
+use crate::{audit_log, backups, demo, maintenance};
+
+const STATUS_PATH: &str = "/api/migrations";
+const APPLY_PATH: &str = "/api/migrations/apply";
+const STATUS_TIMEOUT: Duration = Duration::from_secs(10);
+/// A single migration may rewrite every record.
+const APPLY_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Default)]
+pub struct MigrationState {
+    running: AtomicBool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Migration {
+    pub version: u32,
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+struct MigrationStatus {
+    current: u32,
+    #[serde(default)]
+    available: Vec<Migration>,
+}
+
+#[derive(Deserialize)]
+struct Applied {
+    current: u32,
+}
+
+#[derive(Clone, Serialize)]
+pub struct MigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub applied: Vec<Migration>,
+    /// `None` when nothing was pending, so no backup was taken.
+    pub backup: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct MigrationProgress<'a> {
+    /// `checking`, `backing_up`, `applying` or `done`.
+    stage: &'static str,
+    version: Option<u32>,
+    name: Option<&'a str>,
+    /// 1-based position of `version` among the pending migrations.
+    index: usize,
+    total: usize,
+}
+
+fn request(state: &BackendState, req: &BackendRequest) -> Result<Value, String> {
+    let response = state.transport().request(req)?;
+    if !response.is_success() {
+        return Err(format!("HTTP {}: {}", response.status, response.body.trim()));
+    }
+    serde_json::from_str(&response.body).map_err(|e| format!("Invalid response: {}", e))
+}
+
+fn status(state: &BackendState) -> Result<MigrationStatus, String> {
+    let body = request(state, &BackendRequest::get(STATUS_PATH, STATUS_TIMEOUT))?;
+    serde_json::from_value(body).map_err(|e| format!("Invalid migration status: {}", e))
+}
+
+fn apply(state: &BackendState, migration: &Migration) -> Result<u32, String> {
+    let body = request(
+        state,
+        &BackendRequest::post(APPLY_PATH, Some(json!({ "version": migration.version })), APPLY_TIMEOUT),
+    )?;
+    let applied: Applied = serde_json::from_value(body).map_err(|e| format!("Invalid response: {}", e))?;
+    if applied.current < migration.version {
+        return Err(format!("backend still reports schema {}", applied.current));
+    }
+    Ok(applied.current)
+}
+
+fn run(app: &tauri::AppHandle) -> Result<MigrationReport, AppError> {
+    let emit = |stage, migration: Option<&Migration>, index, total| {
+        crate::event_throttle::emit_coalesced(
+            app,
+            "migration-progress",
+            MigrationProgress {
+                stage,
+                version: migration.map(|m| m.version),
+                name: migration.map(|m| m.name.as_str()),
+                index,
+                total,
+            },
+        );
+    };
+
+    emit("checking", None, 0, 0);
+    let state = app.state::<BackendState>();
+    let status = status(&state).map_err(|e| AppError::Backend(format!("Failed to read schema version: {}", e)))?;
+    let latest = status.available.iter().map(|m| m.version).max().unwrap_or(0);
+    if status.current > latest {
+        return Err(AppError::Conflict(format!(
+            "The data is at schema {} but this backend only knows up to {}; refusing to downgrade.",
+            status.current, latest
+        ))
+        .with_details(json!({ "current": status.current, "latest": latest })));
+    }
+
+    let mut pending: Vec<Migration> =
+        status.available.into_iter().filter(|m| m.version > status.current).collect();
+    pending.sort_by_key(|m| m.version);
+    let total = pending.len();
+    if pending.is_empty() {
+        info!("🗄️ Schema is up to date (version {})", status.current);
+        emit("done", None, 0, 0);
+        return Ok(MigrationReport {
+            from_version: status.current,
+            to_version: status.current,
+            applied: Vec::new(),
+            backup: None,
+        });
+    }
+
+    info!("🗄️ {} migration(s) pending: schema {} → {}", total, status.current, latest);
+    emit("backing_up", None, 0, total);
+    crate::db_checkpoint::checkpoint_before_backup(app);
+    let paths = app.state::<AppPaths>();
+    let backup = backups::write_dir_backup(&paths, &paths.backend_data_dir(), "pre-migration")
+        .map_err(|e| AppError::Io(format!("Pre-migration backup failed, nothing was migrated: {}", e)))?;
+    info!("💾 Pre-migration backup written to {:?}", backup);
+
+    maintenance::create_lock(app).map_err(AppError::Io)?;
+    let _ = app.emit("maintenance-mode", true);
+    crate::window_title::refresh(app);
+
+    let mut applied = Vec::new();
+    let mut current = status.current;
+    for (i, migration) in pending.into_iter().enumerate() {
+        emit("applying", Some(&migration), i + 1, total);
+        match apply(&state, &migration) {
+            Ok(version) => {
+                info!("🗄️ Applied migration {} ({})", migration.version, migration.name);
+                audit_log::record(
+                    app,
+                    "migration_applied",
+                    json!({ "version": migration.version, "name": migration.name }),
+                );
+                current = version;
+                applied.push(migration);
+            }
+            Err(e) => {
+                error!("❌ Migration {} ({}) failed: {}", migration.version, migration.name, e);
+                audit_log::record(
+                    app,
+                    "migration_failed",
+                    json!({ "version": migration.version, "name": migration.name, "error": e }),
+                );
+                // Keep sales away from half-migrated data until someone
+                // has looked at it.
+                if let Err(e) = backend::stop_backend(&state) {
+                    error!("❌ {} after the failed migration", e);
+                }
+                maintenance::wait_for_exit(app);
+                return Err(AppError::Backend(format!(
+                    "Migration {} ({}) failed: {}. The app stays in maintenance mode; a backup is at {}.",
+                    migration.version,
+                    migration.name,
+                    e,
+                    backup.display()
+                ))
+                .with_details(json!({
+                    "version": migration.version,
+                    "applied": applied,
+                    "backup": backup.display().to_string(),
+                })));
+            }
+        }
+    }
+
+    maintenance::remove_lock(app);
+    let _ = app.emit("maintenance-mode", false);
+    crate::window_title::refresh(app);
+    emit("done", None, total, total);
+    info!("✅ Migrations complete: schema {} → {}", status.current, current);
+    Ok(MigrationReport {
+        from_version: status.current,
+        to_version: current,
+        applied,
+        backup: Some(backup.display().to_string()),
+    })
+}
+
+async fn migrate(app_handle: tauri::AppHandle) -> Result<MigrationReport, AppError> {
+    if app_handle.state::<RestartScheduler>().is_sale_in_progress() {
+        return Err(AppError::Conflict("Finish the current sale before migrating.".to_string()));
+    }
+    if demo::is_active(&app_handle) {
+        return Err(AppError::Conflict("Leave demo mode before migrating.".to_string()));
+    }
+    backend::ensure_ready(&app_handle, "run_migrations")?;
+    if app_handle.state::<BackendState>().is_paused() {
+        return Err(AppError::Conflict("Resume the backend before migrating.".to_string()));
+    }
+    if app_handle.state::<MigrationState>().running.swap(true, Ordering::SeqCst) {
+        return Err(AppError::Conflict("Migrations are already running.".to_string()));
+    }
+
+    let app = app_handle.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || run(&app))
+        .await
+        .map_err(|e| AppError::Internal(format!("Migration task failed: {}", e)))
+        .and_then(|r| r);
+    app_handle.state::<MigrationState>().running.store(false, Ordering::SeqCst);
+    result
+}
+
+/// Back up the backend's data and apply its pending migrations in order.
+#[tauri::command]
+pub async fn run_migrations(app_handle: tauri::AppHandle) -> Result<MigrationReport, AppError> {
+    let result = migrate(app_handle.clone()).await;
+    crate::audit_log::privileged(&app_handle, "run_migrations", result)
+}
@@ -0,0 +1,115 @@
+//! Internet connectivity status for sync and update UI.
+//!
+//! A check resolves the probe host and then makes a lightweight `HEAD`
+//! request to `network.probe_url`. Results are cached for a few seconds so
+//! the UI can call `network_status` freely. A background thread re-checks
+//! periodically and emits `connectivity-changed` on every transition.
+
+use std::net::ToSocketAddrs;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use reqwest::blocking::Client;
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::error::AppError;
+use crate::{ca_certs, proxy};
+use crate::settings::SettingsState;
+
+/// How long a check result is reused.
+const CACHE_TTL: Duration = Duration::from_secs(10);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(4);
+/// Background re-check interval for transition events.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Copy, Serialize, PartialEq)]
+pub struct NetworkStatus {
+    pub online: bool,
+    pub latency_ms: Option<u64>,
+}
+
+#[derive(Default)]
+pub struct NetworkState {
+    last: Mutex<Option<(Instant, NetworkStatus)>>,
+}
+
+fn probe(url: &str, proxy: Option<reqwest::Proxy>, roots: Vec<reqwest::Certificate>) -> NetworkStatus {
+    let offline = NetworkStatus { online: false, latency_ms: None };
+
+    let Ok(parsed) = reqwest::Url::parse(url) else {
+        warn!("⚠️ Invalid network probe URL '{}'", url);
+        return offline;
+    };
+    // Behind a proxy the terminal may not resolve outside names itself.
+    if proxy.is_none() {
+        let host = parsed.host_str().unwrap_or_default();
+        let port = parsed.port_or_known_default().unwrap_or(443);
+        if (host, port).to_socket_addrs().map(|mut a| a.next().is_none()).unwrap_or(true) {
+            return offline;
+        }
+    }
+
+    let builder = Client::builder().timeout(PROBE_TIMEOUT);
+    let builder = match proxy {
+        Some(proxy) => builder.proxy(proxy),
+        None => builder.no_proxy(),
+    };
+    let builder = roots.into_iter().fold(builder, |b, cert| b.add_root_certificate(cert));
+    let Ok(client) = builder.build() else { return offline };
+    let started = Instant::now();
+    match client.head(parsed).send() {
+        // Any HTTP answer means we reached the internet.
+        Ok(_) => NetworkStatus {
+            online: true,
+            latency_ms: Some(started.elapsed().as_millis() as u64),
+        },
+        Err(_) => offline,
+    }
+}
+
+/// Run a check (or reuse a fresh cached one) and emit
+/// `connectivity-changed` if the online state flipped.
+pub fn check(app: &tauri::AppHandle, force: bool) -> NetworkStatus {
+    let state = app.state::<NetworkState>();
+    let previous = *state.last.lock().unwrap();
+    if let Some((at, status)) = previous {
+        if !force && at.elapsed() < CACHE_TTL {
+            return status;
+        }
+    }
+
+    let url = app.state::<SettingsState>().get().network.probe_url;
+    let roots = ca_certs::reqwest_certs(app).unwrap_or_else(|e| {
+        warn!("⚠️ Probing without extra root certificates: {}", e);
+        Vec::new()
+    });
+    let status = probe(&url, proxy::reqwest_proxy(app), roots);
+    *state.last.lock().unwrap() = Some((Instant::now(), status));
+
+    if previous.map(|(_, s)| s.online) != Some(status.online) {
+        if status.online {
+            info!("📶 Network online ({:?} ms)", status.latency_ms);
+        } else {
+            warn!("📴 Network offline");
+        }
+        let _ = app.emit("connectivity-changed", status);
+    }
+    status
+}
+
+pub fn start_network_monitor(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        check(&app, true);
+        thread::sleep(POLL_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub async fn network_status(app_handle: tauri::AppHandle) -> Result<NetworkStatus, AppError> {
+    tauri::async_runtime::spawn_blocking(move || check(&app_handle, false))
+        .await
+        .map_err(|e| AppError::Internal(format!("Network check failed: {}", e)))
+}
@@ -0,0 +1,33 @@
+//! First-run onboarding flag.
+//!
+//! `onboarded` lives in `settings.json`, so it survives updates and is
+//! cleared by a factory reset (which removes the file). A settings file
+//! without the flag, e.g. from a version before onboarding existed, counts
+//! as not onboarded.
+
+use log::info;
+use tauri::{Emitter, Manager};
+
+use crate::error::AppError;
+use crate::settings::SettingsState;
+
+/// Emit `first-run` if the setup wizard hasn't been completed yet.
+pub fn announce_first_run(app: &tauri::AppHandle) {
+    if !app.state::<SettingsState>().get().onboarded {
+        info!("👋 First run: onboarding not completed yet");
+        let _ = app.emit("first-run", ());
+    }
+}
+
+#[tauri::command]
+pub fn is_first_run(state: tauri::State<'_, SettingsState>) -> bool {
+    !state.get().onboarded
+}
+
+/// Mark the setup wizard as finished.
+#[tauri::command]
+pub fn complete_onboarding(state: tauri::State<'_, SettingsState>) -> Result<(), AppError> {
+    state.update(serde_json::json!({ "onboarded": true }))?;
+    info!("✅ Onboarding completed");
+    Ok(())
+}
@@ -0,0 +1,191 @@
+//! Resolution of the app's data root.
+//!
+//! Everything the shell writes (logs, crashes, backups, settings) lives under
+//! a single root that is resolved once at startup and stored as managed
+//! state, so every code path agrees on where files go. With store profiles
+//! (see `profiles.rs`) the root is the active profile's directory under the
+//! base data dir.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use log::{error, warn};
+use serde::de::DeserializeOwned;
+use tauri::Manager;
+
+pub const BACKEND_DATA_DIR: &str = "backend-data";
+
+#[derive(Debug, Clone)]
+pub struct AppPaths {
+    /// Data dir of the active profile.
+    pub root: PathBuf,
+    /// Platform data dir holding `profiles.json` and all profiles.
+    pub base: PathBuf,
+    /// Active store profile name.
+    pub profile: String,
+}
+
+impl AppPaths {
+    /// Resolve the data root, trying in order: the platform app data dir,
+    /// the local app data dir, then a folder in the system temp dir. Never
+    /// falls back to a relative path, which would scatter files in the CWD.
+    pub fn resolve<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Self {
+        Self::for_base(Self::resolve_base(app))
+    }
+
+    fn resolve_base<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> PathBuf {
+        match app.path().app_data_dir() {
+            Ok(base) => return base,
+            Err(e) => error!("❌ Could not resolve app data directory: {}", e),
+        }
+
+        match app.path().app_local_data_dir() {
+            Ok(base) => {
+                warn!("⚠️ FALLBACK: using local app data directory {:?}", base);
+                return base;
+            }
+            Err(e) => error!("❌ Could not resolve local app data directory: {}", e),
+        }
+
+        let base = std::env::temp_dir().join(&app.config().identifier);
+        warn!("⚠️ FALLBACK: using temporary directory {:?} — data will not persist reliably", base);
+        base
+    }
+
+    /// Paths for the active profile under `base`.
+    fn for_base(base: PathBuf) -> Self {
+        crate::profiles::apply_pending_rename(&base);
+        let profile = crate::profiles::active_profile(&base);
+        let root = crate::profiles::profile_dir(&base, &profile);
+        Self { root, base, profile }
+    }
+
+    pub fn logs_dir(&self) -> PathBuf {
+        self.root.join("logs")
+    }
+
+    /// The backend's data dir (JSON store and offline queues) for this
+    /// profile, passed to it as `--data-dir`.
+    pub fn backend_data_dir(&self) -> PathBuf {
+        self.root.join(BACKEND_DATA_DIR)
+    }
+}
+
+/// Copy `src` recursively into `dest`, creating it.
+pub fn copy_dir(src: &Path, dest: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(src)?.flatten() {
+        let path = entry.path();
+        let target = dest.join(entry.file_name());
+        if path.is_dir() {
+            copy_dir(&path, &target)?;
+        } else {
+            fs::copy(&path, &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write `bytes` to `path` via a temp file in the same directory and a
+/// rename, so readers never see a half-written file.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let file_name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.tmp", file_name));
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+/// Read a JSON config file, returning defaults when it is missing. A file
+/// that exists but doesn't parse (e.g. truncated by a power cut) is moved
+/// aside to `<name>.corrupt-<UTC stamp>` so it can be inspected, and
+/// defaults are returned instead of failing.
+pub fn read_config_or_default<T: DeserializeOwned + Default>(path: &Path) -> T {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return T::default(),
+        Err(e) => {
+            warn!("⚠️ Failed to read {:?}, using defaults: {}", path, e);
+            return T::default();
+        }
+    };
+    match serde_json::from_str(&text) {
+        Ok(value) => value,
+        Err(e) => {
+            let file_name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+            let backup = path.with_file_name(format!("{}.corrupt-{}", file_name, crate::timestamps::file_stamp()));
+            match fs::rename(path, &backup) {
+                Ok(()) => warn!("⚠️ {:?} is corrupt ({}); moved to {:?}, using defaults", path, e, backup),
+                Err(re) => warn!("⚠️ {:?} is corrupt ({}) and could not be moved aside: {}", path, e, re),
+            }
+            T::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh, empty directory under the system temp dir.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("siri-paths-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn write_atomic_replaces_contents_and_leaves_no_temp_file() {
+        let dir = temp_dir("write");
+        let path = dir.join("settings.json");
+        fs::write(&path, b"old").unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert_eq!(file_names(&dir), ["settings.json"]);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn read_config_or_default_moves_corrupt_file_aside() {
+        let dir = temp_dir("corrupt");
+        let path = dir.join("settings.json");
+        fs::write(&path, b"{ truncated").unwrap();
+
+        let value: std::collections::HashMap<String, u32> = read_config_or_default(&path);
+
+        assert!(value.is_empty());
+        assert!(!path.exists());
+        let names = file_names(&dir);
+        assert_eq!(names.len(), 1);
+        assert!(names[0].starts_with("settings.json.corrupt-"), "unexpected {:?}", names);
+        assert_eq!(fs::read(dir.join(&names[0])).unwrap(), b"{ truncated");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn read_config_or_default_returns_default_for_missing_file() {
+        let dir = temp_dir("missing");
+        let value: std::collections::HashMap<String, u32> = read_config_or_default(&dir.join("absent.json"));
+        assert!(value.is_empty());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,171 @@
+//! Local PIN gate for shared terminals.
+//!
+//! This is a quick unlock, not authentication: the PIN is stored as an
+//! argon2 hash in `pin.dat` and, when set, the frontend keeps its content
+//! hidden (`require-pin` at startup, `pin_status` on load) until
+//! `verify_pin` succeeds. Repeated failures lock verification out for a
+//! growing period.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::error::AppError;
+use crate::paths::{self, AppPaths};
+
+const PIN_FILE: &str = "pin.dat";
+/// Failed attempts allowed before a lockout.
+const MAX_FAILURES: u32 = 5;
+/// First lockout; doubles with each further lockout.
+const BASE_LOCKOUT: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+struct PinInner {
+    unlocked: bool,
+    failures: u32,
+    lockouts: u32,
+    locked_until: Option<Instant>,
+}
+
+#[derive(Default)]
+pub struct PinState(Mutex<PinInner>);
+
+#[derive(Clone, Serialize)]
+pub struct PinStatus {
+    pub pin_set: bool,
+    pub unlocked: bool,
+    /// Seconds until verification is allowed again, while locked out.
+    pub locked_for_secs: Option<u64>,
+}
+
+fn pin_path(app: &tauri::AppHandle) -> PathBuf {
+    app.state::<AppPaths>().root.join(PIN_FILE)
+}
+
+fn stored_hash(app: &tauri::AppHandle) -> Option<String> {
+    fs::read_to_string(pin_path(app))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn validate_pin(pin: &str) -> Result<(), String> {
+    if (4..=8).contains(&pin.len()) && pin.chars().all(|c| c.is_ascii_digit()) {
+        Ok(())
+    } else {
+        Err("PIN must be 4 to 8 digits.".to_string())
+    }
+}
+
+fn matches(hash: &str, pin: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| Argon2::default().verify_password(pin.as_bytes(), &parsed).is_ok())
+        .unwrap_or(false)
+}
+
+/// Check `pin` against the stored PIN without touching the unlock state or
+/// failure counters. `None` when no PIN is set.
+pub fn check_pin(app: &tauri::AppHandle, pin: &str) -> Option<bool> {
+    stored_hash(app).map(|hash| matches(&hash, pin))
+}
+
+fn status(app: &tauri::AppHandle) -> PinStatus {
+    let pin_set = stored_hash(app).is_some();
+    let state = app.state::<PinState>();
+    let inner = state.0.lock().unwrap();
+    PinStatus {
+        pin_set,
+        unlocked: !pin_set || inner.unlocked,
+        locked_for_secs: inner
+            .locked_until
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+            .map(|left| left.as_secs() + 1),
+    }
+}
+
+/// Tell the frontend to show the PIN screen if a PIN is configured.
+pub fn require_pin_on_startup(app: &tauri::AppHandle) {
+    if stored_hash(app).is_some() {
+        info!("🔒 PIN is set; UI gated until unlocked");
+        let _ = app.emit("require-pin", ());
+    }
+}
+
+#[tauri::command]
+pub fn pin_status(app_handle: tauri::AppHandle) -> PinStatus {
+    status(&app_handle)
+}
+
+fn change_pin(app_handle: tauri::AppHandle, pin: String, current_pin: Option<String>) -> Result<(), AppError> {
+    validate_pin(&pin).map_err(AppError::Validation)?;
+    if let Some(hash) = stored_hash(&app_handle) {
+        if !current_pin.is_some_and(|current| matches(&hash, &current)) {
+            warn!("⚠️ PIN change rejected: current PIN did not match");
+            return Err(AppError::PermissionDenied("Current PIN is incorrect.".to_string()));
+        }
+    }
+
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(pin.as_bytes(), &salt)
+        .map_err(|e| format!("Failed to hash PIN: {}", e))?
+        .to_string();
+    let path = pin_path(&app_handle);
+    paths::write_atomic(&path, hash.as_bytes())
+        .map_err(|e| AppError::Io(format!("Failed to write {:?}: {}", path, e)))?;
+
+    app_handle.state::<PinState>().0.lock().unwrap().unlocked = true;
+    info!("🔒 PIN updated");
+    crate::audit_log::record(&app_handle, "pin_changed", serde_json::Value::Null);
+    Ok(())
+}
+
+/// Set or change the PIN. Changing an existing PIN needs the current one.
+#[tauri::command]
+pub fn set_pin(app_handle: tauri::AppHandle, pin: String, current_pin: Option<String>) -> Result<(), AppError> {
+    let result = change_pin(app_handle.clone(), pin, current_pin);
+    crate::audit_log::privileged(&app_handle, "set_pin", result)
+}
+
+/// Check `pin`, unlocking the UI on success. Errors while locked out.
+#[tauri::command]
+pub fn verify_pin(app_handle: tauri::AppHandle, pin: String) -> Result<bool, AppError> {
+    let Some(hash) = stored_hash(&app_handle) else { return Ok(true) };
+
+    let state = app_handle.state::<PinState>();
+    let mut inner = state.0.lock().unwrap();
+    if let Some(until) = inner.locked_until {
+        if let Some(left) = until.checked_duration_since(Instant::now()) {
+            return Err(AppError::PermissionDenied(format!(
+                "Too many attempts. Try again in {} seconds.",
+                left.as_secs() + 1
+            )));
+        }
+        inner.locked_until = None;
+    }
+
+    if matches(&hash, &pin) {
+        *inner = PinInner { unlocked: true, ..Default::default() };
+        info!("🔓 PIN verified, UI unlocked");
+        return Ok(true);
+    }
+
+    inner.failures += 1;
+    warn!("⚠️ Failed PIN attempt ({}/{})", inner.failures, MAX_FAILURES);
+    if inner.failures >= MAX_FAILURES {
+        let lockout = BASE_LOCKOUT * 2u32.pow(inner.lockouts.min(6));
+        inner.lockouts += 1;
+        inner.failures = 0;
+        inner.locked_until = Some(Instant::now() + lockout);
+        warn!("🔒 PIN verification locked for {:?} after repeated failures", lockout);
+    }
+    Ok(false)
+}
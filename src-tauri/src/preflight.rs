@@ -0,0 +1,174 @@
+//! Startup self-check run before the backend is spawned.
+//!
+//! Each check reports pass/fail with remediation text for the cashier or IT.
+//! A failed critical check keeps the backend from starting; the frontend
+//! shows the report as a blocking screen and calls `run_preflight` again
+//! once the problem is fixed.
+
+use std::fs;
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::{error, info, warn};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::backend::{self, BackendState, DEFAULT_BACKEND_PORT};
+use crate::disk;
+use crate::error::AppError;
+use crate::paths::AppPaths;
+
+/// Free space below which the app can't safely write its database and logs.
+const MIN_FREE_SPACE: u64 = 200 * 1024 * 1024;
+/// Host probed for update reachability.
+const UPDATE_HOST: &str = "github.com:443";
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Serialize)]
+pub struct PreflightCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    /// Critical failures block startup; others are warnings.
+    pub critical: bool,
+    pub detail: String,
+    pub remediation: Option<&'static str>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct PreflightReport {
+    pub checks: Vec<PreflightCheck>,
+    /// False if any critical check failed.
+    pub passed: bool,
+}
+
+/// Last report, so a frontend that loads after setup can still read it.
+#[derive(Default)]
+pub struct PreflightState(Mutex<Option<PreflightReport>>);
+
+impl PreflightState {
+    pub fn last(&self) -> Option<PreflightReport> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+fn check(name: &'static str, critical: bool, result: Result<String, String>, remediation: &'static str) -> PreflightCheck {
+    match result {
+        Ok(detail) => PreflightCheck { name, ok: true, critical, detail, remediation: None },
+        Err(detail) => PreflightCheck { name, ok: false, critical, detail, remediation: Some(remediation) },
+    }
+}
+
+fn check_data_dir_writable(paths: &AppPaths) -> Result<String, String> {
+    let probe = paths.root.join(".preflight-write-test");
+    fs::write(&probe, b"ok")
+        .and_then(|_| fs::remove_file(&probe))
+        .map(|_| format!("{} is writable", paths.root.display()))
+        .map_err(|e| format!("Cannot write to {}: {}", paths.root.display(), e))
+}
+
+fn check_port_free(app: &tauri::AppHandle) -> Result<String, String> {
+    let state = app.state::<BackendState>();
+    if state.child.lock().unwrap().is_some() || backend::running_backend_port(app).is_some() {
+        return Ok("Backend is already running".to_string());
+    }
+    TcpListener::bind(("127.0.0.1", DEFAULT_BACKEND_PORT))
+        .map(|_| format!("Port {} is free", DEFAULT_BACKEND_PORT))
+        .map_err(|e| format!("Port {} is in use by another program: {}", DEFAULT_BACKEND_PORT, e))
+}
+
+fn check_sidecar() -> Result<String, String> {
+    let info = backend::inspect_backend_binary()?;
+    match info.problem {
+        None => Ok(format!("{} ({} bytes)", info.path, info.size.unwrap_or(0))),
+        Some(problem) => Err(problem),
+    }
+}
+
+pub fn check_disk_space(paths: &AppPaths) -> Result<String, String> {
+    let free = disk::available_space(&paths.root)
+        .map_err(|e| format!("Could not read free space: {}", e))?;
+    let free_mb = free / (1024 * 1024);
+    if free >= MIN_FREE_SPACE {
+        Ok(format!("{} MB free", free_mb))
+    } else {
+        Err(format!("Only {} MB free, {} MB required", free_mb, MIN_FREE_SPACE / (1024 * 1024)))
+    }
+}
+
+pub fn check_update_reachable() -> Result<String, String> {
+    let addr = UPDATE_HOST
+        .to_socket_addrs()
+        .map_err(|e| format!("Cannot resolve {}: {}", UPDATE_HOST, e))?
+        .next()
+        .ok_or_else(|| format!("No address for {}", UPDATE_HOST))?;
+    TcpStream::connect_timeout(&addr, NETWORK_TIMEOUT)
+        .map(|_| format!("{} reachable", UPDATE_HOST))
+        .map_err(|e| format!("{} unreachable: {}", UPDATE_HOST, e))
+}
+
+/// Run every check, log the results, remember and emit `preflight-report`.
+pub fn preflight(app: &tauri::AppHandle) -> PreflightReport {
+    let paths = app.state::<AppPaths>();
+    let checks = vec![
+        check("data_dir_writable", true, check_data_dir_writable(&paths),
+            "Make sure the app data folder exists and this user can write to it, or run the app as the user it was installed for."),
+        check("port_free", true, check_port_free(app),
+            "Close the other program using port 8080, or restart the computer."),
+        check("sidecar_binary", true, check_sidecar(),
+            "Reinstall Siri Billing; antivirus software may have quarantined the backend."),
+        check("disk_space", true, check_disk_space(&paths),
+            "Free up disk space on the drive holding the app data folder."),
+        check("update_network", false, check_update_reachable(),
+            "Check the internet connection. The app works offline but cannot update."),
+    ];
+    let passed = checks.iter().all(|c| c.ok || !c.critical);
+
+    for c in &checks {
+        if c.ok {
+            info!("✅ Preflight {}: {}", c.name, c.detail);
+        } else if c.critical {
+            error!("❌ Preflight {}: {}", c.name, c.detail);
+        } else {
+            warn!("⚠️ Preflight {}: {}", c.name, c.detail);
+        }
+    }
+
+    crate::startup_timing::mark(app, "preflight_done");
+    let report = PreflightReport { checks, passed };
+    *app.state::<PreflightState>().0.lock().unwrap() = Some(report.clone());
+    let _ = app.emit("preflight-report", report.clone());
+    report
+}
+
+/// Re-run the checks. If they now pass and the backend was held back by an
+/// earlier failure, start it.
+#[tauri::command]
+pub async fn run_preflight(app_handle: tauri::AppHandle) -> Result<PreflightReport, AppError> {
+    let previously_failed = app_handle
+        .state::<PreflightState>()
+        .last()
+        .map(|r| !r.passed)
+        .unwrap_or(false);
+
+    let app = app_handle.clone();
+    let report = tauri::async_runtime::spawn_blocking(move || preflight(&app))
+        .await
+        .map_err(|e| format!("Preflight failed to run: {}", e))?;
+
+    if report.passed && previously_failed {
+        info!("✅ Preflight now passes, starting backend");
+        let app = app_handle.clone();
+        tauri::async_runtime::spawn_blocking(move || backend::start_backend(&app))
+            .await
+            .map_err(|e| format!("Backend start failed: {}", e))?
+            .map_err(AppError::Backend)?;
+    }
+    Ok(report)
+}
+
+/// The report from the most recent run, if any.
+#[tauri::command]
+pub fn get_preflight_report(state: tauri::State<'_, PreflightState>) -> Option<PreflightReport> {
+    state.last()
+}
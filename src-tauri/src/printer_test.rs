@@ -0,0 +1,143 @@
+//! `test_printer`: a short test page so staff can check a printer before
+//! a busy day without wasting a real receipt.
+//!
+//! Printing goes through `print_html_native` like receipts do; the page
+//! exercises alignment, a Code 39 barcode and the end-of-job cut. On
+//! Windows the spooler status is checked first so an offline printer or
+//! one out of paper gives a clear error instead of a silent queued job.
+
+use log::{info, warn};
+
+use crate::error::AppError;
+
+/// Code 39 patterns: nine elements alternating bar/space, `1` = wide.
+const CODE39: &[(char, &str)] = &[
+    ('0', "000110100"), ('1', "100100001"), ('2', "001100001"), ('3', "101100000"),
+    ('4', "000110001"), ('5', "100110000"), ('6', "001110000"), ('7', "000100101"),
+    ('8', "100100100"), ('9', "001100100"), ('A', "100001001"), ('B', "001001001"),
+    ('C', "101001000"), ('D', "000011001"), ('E', "100011000"), ('F', "001011000"),
+    ('G', "000001101"), ('H', "100001100"), ('I', "001001100"), ('J', "000011100"),
+    ('K', "100000011"), ('L', "001000011"), ('M', "101000010"), ('N', "000010011"),
+    ('O', "100010010"), ('P', "001010010"), ('Q', "000000111"), ('R', "100000110"),
+    ('S', "001000110"), ('T', "000010110"), ('U', "110000001"), ('V', "011000001"),
+    ('W', "111000000"), ('X', "010010001"), ('Y', "110010000"), ('Z', "011010000"),
+    ('-', "010000101"), ('*', "010010100"),
+];
+
+/// Render `text` (uppercase letters, digits, `-`) as an inline SVG Code 39
+/// barcode, with the `*` start/stop characters added.
+fn code39_svg(text: &str) -> String {
+    const NARROW: u32 = 2;
+    const WIDE: u32 = 5;
+    const HEIGHT: u32 = 60;
+
+    let mut x = 0;
+    let mut bars = String::new();
+    for c in format!("*{}*", text).chars() {
+        let Some((_, pattern)) = CODE39.iter().find(|(k, _)| *k == c) else { continue };
+        for (i, element) in pattern.chars().enumerate() {
+            let width = if element == '1' { WIDE } else { NARROW };
+            if i % 2 == 0 {
+                bars.push_str(&format!(
+                    "<rect x=\"{}\" y=\"0\" width=\"{}\" height=\"{}\"/>",
+                    x, width, HEIGHT
+                ));
+            }
+            x += width;
+        }
+        x += NARROW; // inter-character gap
+    }
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">{bars}</svg>",
+        w = x,
+        h = HEIGHT,
+        bars = bars
+    )
+}
+
+fn render_test_page(printer: &str, code: &str) -> String {
+    let printer = printer.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    format!(
+        r#"<!DOCTYPE html><html><head><meta charset="utf-8"><style>
+@page {{ size: 80mm auto; margin: 0; }}
+body {{ width: 72mm; margin: 4mm; font-family: monospace; font-size: 12px; }}
+.l {{ text-align: left; }} .c {{ text-align: center; }} .r {{ text-align: right; }}
+.b {{ font-weight: bold; font-size: 15px; }} hr {{ border: 0; border-top: 1px dashed #000; }}
+</style></head><body>
+<div class="c b">PRINTER TEST</div>
+<div class="c">{printer}</div>
+<div class="c">{time}</div><hr>
+<div class="l">Left aligned</div>
+<div class="c">Centered</div>
+<div class="r">Right aligned</div><hr>
+<div class="c">{barcode}</div>
+<div class="c">{code}</div><hr>
+<div class="c">If the paper cuts below this line,<br>the printer is working.</div>
+</body></html>"#,
+        printer = printer,
+        time = chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        barcode = code39_svg(code),
+        code = code,
+    )
+}
+
+/// Spooler status for `name` (`Normal`, `Offline`, `PaperOut`, ...).
+#[cfg(target_os = "windows")]
+fn printer_status(name: &str) -> Option<String> {
+    use std::os::windows::process::CommandExt;
+
+    let script = format!(
+        "(Get-Printer -Name '{}').PrinterStatus.ToString()",
+        name.replace('\'', "''")
+    );
+    let output = std::process::Command::new("powershell")
+        .args(["-NonInteractive", "-WindowStyle", "Hidden", "-ExecutionPolicy", "Bypass", "-Command", &script])
+        .creation_flags(0x08000000)
+        .output()
+        .ok()?;
+    let status = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!status.is_empty()).then_some(status)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn printer_status(_name: &str) -> Option<String> {
+    None
+}
+
+/// Print a test page on `name`. Fails with a descriptive error if the
+/// printer is unknown, offline or reports a paper problem.
+#[tauri::command]
+pub async fn test_printer(app_handle: tauri::AppHandle, name: String) -> Result<String, AppError> {
+    let lookup = name.clone();
+    let (installed, status) = tauri::async_runtime::spawn_blocking(move || {
+        (crate::query_printers(), printer_status(&lookup))
+    })
+    .await
+    .map_err(|e| format!("Printer check failed: {}", e))?;
+
+    if !installed.map_err(AppError::Printer)?.iter().any(|p| p == &name) {
+        warn!("⚠️ Test print requested for unknown printer '{}'", name);
+        return Err(AppError::NotFound(format!("Printer '{}' is not installed on this computer.", name)));
+    }
+
+    let problem = match status.as_deref() {
+        Some("Offline") => Some("is offline. Check that it is switched on and connected."),
+        Some("PaperOut") => Some("is out of paper."),
+        Some("PaperJam") => Some("has a paper jam."),
+        Some("Error") => Some("is reporting an error."),
+        Some(other) => {
+            info!("🖨️ Printer '{}' status: {}", name, other);
+            None
+        }
+        None => None,
+    };
+    if let Some(problem) = problem {
+        return Err(AppError::Printer(format!("Printer '{}' {}", name, problem))
+            .with_details(serde_json::json!({ "status": status })));
+    }
+
+    let code = format!("TEST-{}", chrono::Local::now().format("%H%M%S"));
+    info!("🖨️ Sending test page {} to '{}'", code, name);
+    crate::print_html_native(app_handle, render_test_page(&name, &code), name.clone(), 1, None).await?;
+    Ok(format!("Test page sent to {}.", name))
+}
@@ -0,0 +1,256 @@
+//! Store profiles, for operators running several stores from one machine.
+//!
+//! Each profile is a full data root (settings, the backend's
+//! `backend-data/`, license, PIN) under `profiles/<name>/` in the base data
+//! dir. The
+//! `default` profile is the base dir itself, so installs from before
+//! profiles existed keep their data. The active profile is recorded in
+//! `profiles.json` in the base dir and read once at startup by
+//! `AppPaths::resolve`; switching persists the new name and restarts the
+//! app, so no state from the old profile carries over.
+//!
+//! `rename_profile` moves another profile's directory at once. The active
+//! profile's directory is in use until the app exits, so its rename is
+//! recorded as `rename_to` and carried out by `AppPaths::resolve` on the
+//! next start, before anything has opened files under it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::backend::{self, BackendState};
+use crate::error::AppError;
+use crate::paths::{self, AppPaths};
+
+pub const PROFILES_FILE: &str = "profiles.json";
+pub const PROFILES_DIR: &str = "profiles";
+pub const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Default, Serialize, Deserialize)]
+struct ProfilesFile {
+    active: Option<String>,
+    /// New name for the active profile, applied at the next start.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rename_to: Option<String>,
+}
+
+fn write_profiles_file(base: &Path, file: &ProfilesFile) -> Result<(), String> {
+    let bytes = serde_json::to_vec_pretty(file).map_err(|e| e.to_string())?;
+    let path = base.join(PROFILES_FILE);
+    paths::write_atomic(&path, &bytes).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+#[derive(Clone, Serialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub active: bool,
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    let ok = (1..=32).contains(&name.len())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if ok {
+        Ok(())
+    } else {
+        Err("Profile names must be 1-32 letters, digits, '-' or '_'.".to_string())
+    }
+}
+
+/// Data dir of profile `name` under `base`.
+pub fn profile_dir(base: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        base.to_path_buf()
+    } else {
+        base.join(PROFILES_DIR).join(name)
+    }
+}
+
+/// The persisted active profile, or `default` when none is set or the
+/// recorded one is no longer usable.
+pub fn active_profile(base: &Path) -> String {
+    let file: ProfilesFile = paths::read_config_or_default(&base.join(PROFILES_FILE));
+    match file.active {
+        Some(name) if name == DEFAULT_PROFILE => name,
+        Some(name) if validate_name(&name).is_ok() && profile_dir(base, &name).is_dir() => name,
+        Some(name) => {
+            warn!("⚠️ Active profile '{}' not found; using '{}'", name, DEFAULT_PROFILE);
+            DEFAULT_PROFILE.to_string()
+        }
+        None => DEFAULT_PROFILE.to_string(),
+    }
+}
+
+/// Carry out a rename of the active profile recorded by `rename_profile`.
+/// The directory is moved first and the pointer updated second; if the
+/// pointer can't be written the move is undone, so the profile is never
+/// left without a name that points at it.
+pub fn apply_pending_rename(base: &Path) {
+    let file: ProfilesFile = paths::read_config_or_default(&base.join(PROFILES_FILE));
+    let (Some(old), Some(new)) = (file.active.clone(), file.rename_to.clone()) else { return };
+    let (from, to) = (profile_dir(base, &old), profile_dir(base, &new));
+
+    let result = if validate_name(&new).is_err() || new == DEFAULT_PROFILE || to.exists() {
+        Err(format!("'{}' is not a free profile name", new))
+    } else {
+        fs::rename(&from, &to)
+            .map_err(|e| format!("Failed to move {:?} to {:?}: {}", from, to, e))
+            .and_then(|()| {
+                write_profiles_file(base, &ProfilesFile { active: Some(new.clone()), rename_to: None })
+                    .inspect_err(|_| {
+                        if let Err(e) = fs::rename(&to, &from) {
+                            error!("❌ Failed to roll back profile rename {:?} → {:?}: {}", to, from, e);
+                        }
+                    })
+            })
+    };
+    match result {
+        Ok(()) => info!("🏪 Renamed active store profile '{}' → '{}'", old, new),
+        Err(e) => {
+            error!("❌ Renaming store profile '{}' → '{}' failed; keeping '{}': {}", old, new, old, e);
+            if let Err(e) = write_profiles_file(base, &ProfilesFile { active: Some(old), rename_to: None }) {
+                error!("❌ Failed to clear the pending profile rename: {}", e);
+            }
+        }
+    }
+}
+
+fn profile_names(base: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(base.join(PROFILES_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| validate_name(name).is_ok() && name != DEFAULT_PROFILE)
+        .collect();
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+#[tauri::command]
+pub fn list_profiles(app_paths: tauri::State<'_, AppPaths>) -> Vec<ProfileInfo> {
+    profile_names(&app_paths.base)
+        .into_iter()
+        .map(|name| ProfileInfo { active: name == app_paths.profile, name })
+        .collect()
+}
+
+fn create(app_paths: &AppPaths, name: String) -> Result<ProfileInfo, AppError> {
+    let name = name.trim().to_string();
+    validate_name(&name).map_err(AppError::Validation)?;
+    let dir = profile_dir(&app_paths.base, &name);
+    if name == DEFAULT_PROFILE || dir.exists() {
+        return Err(AppError::Conflict(format!("Profile '{}' already exists.", name)));
+    }
+    crate::ensure_app_dirs(&dir)
+        .map_err(|e| AppError::Io(format!("Failed to create profile {:?}: {}", dir, e)))?;
+    info!("🏪 Created store profile '{}' at {:?}", name, dir);
+    Ok(ProfileInfo { name, active: false })
+}
+
+/// Create an empty profile. It's set up on first use after switching to it.
+#[tauri::command]
+pub fn create_profile(app_handle: tauri::AppHandle, name: String) -> Result<ProfileInfo, AppError> {
+    let result = create(&app_handle.state::<AppPaths>(), name);
+    crate::audit_log::privileged(&app_handle, "create_profile", result)
+}
+
+async fn switch(app_handle: tauri::AppHandle, name: String) -> Result<(), AppError> {
+    let paths = app_handle.state::<AppPaths>().inner().clone();
+    let name = name.trim().to_string();
+    if name != DEFAULT_PROFILE {
+        validate_name(&name).map_err(AppError::Validation)?;
+        if !profile_dir(&paths.base, &name).is_dir() {
+            return Err(AppError::NotFound(format!("Profile '{}' does not exist.", name)));
+        }
+    }
+    if name == paths.profile {
+        return Ok(());
+    }
+
+    write_profiles_file(&paths.base, &ProfilesFile { active: Some(name.clone()), rename_to: None })
+        .map_err(AppError::Io)?;
+
+    info!("=================================================");
+    info!("🏪 Switching store profile '{}' → '{}'", paths.profile, name);
+    info!("=================================================");
+    crate::audit_log::record(
+        &app_handle,
+        "profile_switched",
+        serde_json::json!({ "from": paths.profile, "to": name }),
+    );
+
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || backend::stop_backend(&app.state::<BackendState>()))
+        .await
+        .map_err(|e| format!("Backend shutdown failed: {}", e))?
+        .map_err(AppError::Backend)
+}
+
+/// Make `name` the active profile and restart into it. The switch is
+/// recorded with an atomic write before anything is stopped, so an
+/// interrupted switch comes back up in either the old or the new profile.
+#[tauri::command]
+pub async fn switch_profile(app_handle: tauri::AppHandle, name: String) -> Result<(), AppError> {
+    let result = switch(app_handle.clone(), name).await;
+    crate::audit_log::privileged(&app_handle, "switch_profile", result)?;
+    app_handle.restart();
+}
+
+/// Rename `old` now, or record the rename for the next start when it is
+/// the active profile. Returns whether the app must restart.
+fn rename(app_handle: &tauri::AppHandle, old: String, new: String) -> Result<bool, AppError> {
+    let paths = app_handle.state::<AppPaths>().inner().clone();
+    let (old, new) = (old.trim().to_string(), new.trim().to_string());
+    if old == DEFAULT_PROFILE {
+        return Err(AppError::Validation(format!(
+            "The '{}' profile is the base data folder and can't be renamed.",
+            DEFAULT_PROFILE
+        )));
+    }
+    validate_name(&new).map_err(AppError::Validation)?;
+    let from = profile_dir(&paths.base, &old);
+    let to = profile_dir(&paths.base, &new);
+    if validate_name(&old).is_err() || !from.is_dir() {
+        return Err(AppError::NotFound(format!("Profile '{}' does not exist.", old)));
+    }
+    if old == new {
+        return Ok(false);
+    }
+    if new == DEFAULT_PROFILE || to.exists() {
+        return Err(AppError::Conflict(format!("Profile '{}' already exists.", new)));
+    }
+
+    if old != paths.profile {
+        fs::rename(&from, &to)
+            .map_err(|e| AppError::Io(format!("Failed to rename {:?} to {:?}: {}", from, to, e)))?;
+        info!("🏪 Renamed store profile '{}' → '{}'", old, new);
+        return Ok(false);
+    }
+
+    if app_handle.state::<BackendState>().child.lock().unwrap().is_some() {
+        return Err(AppError::Conflict(
+            "Stop the backend (enter maintenance mode) before renaming the active profile.".to_string(),
+        ));
+    }
+    write_profiles_file(&paths.base, &ProfilesFile { active: Some(old.clone()), rename_to: Some(new.clone()) })
+        .map_err(AppError::Io)?;
+    info!("🏪 Active store profile '{}' will be renamed to '{}'; restarting", old, new);
+    Ok(true)
+}
+
+/// Rename profile `old` to `new`. Another profile is renamed at once. The
+/// active profile needs the backend stopped first (e.g. via maintenance
+/// mode); its rename is applied on the restart that follows.
+#[tauri::command]
+pub fn rename_profile(app_handle: tauri::AppHandle, old: String, new: String) -> Result<(), AppError> {
+    let result = rename(&app_handle, old, new);
+    if crate::audit_log::privileged(&app_handle, "rename_profile", result)? {
+        app_handle.restart();
+    }
+    Ok(())
+}
@@ -0,0 +1,193 @@
+//! Validation of plain-text receipt templates before a batch print.
+//!
+//! Templates are line-based text for the thermal printer:
+//!
+//! ```text
+//! {{store_name}}
+//! Bill {{bill_no}}   {{date}} {{time}}
+//! {{#items}}
+//! {{item_name:24}} {{qty:4}} {{amount:10}}
+//! {{/items}}
+//! TOTAL {{grand_total}}
+//! ```
+//!
+//! `{{token}}` is replaced by a value, `{{token:N}}` pads/cuts it to N
+//! columns and `{{#section}}…{{/section}}` repeats for each item, tax or
+//! payment. Row tokens are only valid inside their section. Each line is
+//! checked against `printing.paper_width_chars`, counting every token at its
+//! width (or its usual maximum when no width is given).
+
+use std::collections::HashMap;
+
+use crate::error::AppError;
+use crate::settings::SettingsState;
+
+/// Tokens valid anywhere, with their usual maximum width.
+const GLOBAL_TOKENS: &[(&str, usize)] = &[
+    ("store_name", 32),
+    ("store_address", 48),
+    ("store_phone", 15),
+    ("gstin", 15),
+    ("bill_no", 12),
+    ("date", 10),
+    ("time", 8),
+    ("cashier", 16),
+    ("customer_name", 24),
+    ("customer_phone", 15),
+    ("subtotal", 12),
+    ("discount", 12),
+    ("tax_total", 12),
+    ("grand_total", 12),
+    ("paid", 12),
+    ("change", 12),
+    ("footer", 48),
+];
+
+/// Repeating sections and the row tokens valid inside them.
+const SECTIONS: &[(&str, &[(&str, usize)])] = &[
+    ("items", &[("item_name", 24), ("hsn", 8), ("qty", 5), ("rate", 10), ("amount", 12)]),
+    ("taxes", &[("tax_name", 12), ("tax_rate", 6), ("tax_amount", 12)]),
+    ("payments", &[("payment_method", 12), ("payment_amount", 12)]),
+];
+
+/// One `{{…}}` tag found in a line.
+enum Tag<'a> {
+    Open(&'a str),
+    Close(&'a str),
+    Token { name: &'a str, width: Option<&'a str> },
+}
+
+fn parse_tag(inner: &str) -> Tag<'_> {
+    let inner = inner.trim();
+    if let Some(name) = inner.strip_prefix('#') {
+        Tag::Open(name.trim())
+    } else if let Some(name) = inner.strip_prefix('/') {
+        Tag::Close(name.trim())
+    } else {
+        match inner.split_once(':') {
+            Some((name, width)) => Tag::Token { name: name.trim(), width: Some(width.trim()) },
+            None => Tag::Token { name: inner, width: None },
+        }
+    }
+}
+
+fn section_tokens(name: &str) -> Option<&'static [(&'static str, usize)]> {
+    SECTIONS.iter().find(|(s, _)| *s == name).map(|(_, tokens)| *tokens)
+}
+
+/// Check `template` for a paper `width` columns wide; returns every issue.
+pub fn validate(template: &str, width: usize) -> Vec<String> {
+    let globals: HashMap<&str, usize> = GLOBAL_TOKENS.iter().copied().collect();
+    let mut issues = Vec::new();
+    let mut open: Vec<(&str, usize)> = Vec::new();
+
+    for (i, line) in template.lines().enumerate() {
+        let n = i + 1;
+        let mut columns = 0;
+        let mut rest = line;
+        let mut only_section_tags = true;
+        let mut stray_close = false;
+
+        while let Some(start) = rest.find("{{") {
+            let literal = &rest[..start];
+            columns += literal.chars().count();
+            only_section_tags &= literal.trim().is_empty();
+            stray_close |= literal.contains("}}");
+            let after = &rest[start + 2..];
+            let Some(end) = after.find("}}") else {
+                issues.push(format!("Line {}: '{{{{' is never closed with '}}}}'.", n));
+                rest = "";
+                break;
+            };
+
+            match parse_tag(&after[..end]) {
+                Tag::Open(name) => {
+                    if section_tokens(name).is_none() {
+                        issues.push(format!("Line {}: unknown section '{}'.", n, name));
+                    } else if let Some((outer, _)) = open.last() {
+                        issues.push(format!("Line {}: section '{}' can't be nested inside '{}'.", n, name, outer));
+                    }
+                    open.push((name, n));
+                }
+                Tag::Close(name) => match open.pop() {
+                    Some((expected, _)) if expected == name => {}
+                    Some((expected, opened)) => {
+                        issues.push(format!(
+                            "Line {}: '{{{{/{}}}}}' closes '{}' opened on line {}.",
+                            n, name, expected, opened
+                        ));
+                    }
+                    None => issues.push(format!("Line {}: '{{{{/{}}}}}' has no matching opening tag.", n, name)),
+                },
+                Tag::Token { name, width: spec } => {
+                    only_section_tags = false;
+                    let row_width = open
+                        .last()
+                        .and_then(|(section, _)| section_tokens(section))
+                        .and_then(|tokens| tokens.iter().find(|(t, _)| *t == name))
+                        .map(|(_, w)| *w);
+                    let default_width = match row_width.or_else(|| globals.get(name).copied()) {
+                        Some(w) => w,
+                        None => {
+                            let section = SECTIONS
+                                .iter()
+                                .find(|(_, tokens)| tokens.iter().any(|(t, _)| *t == name))
+                                .map(|(s, _)| *s);
+                            match section {
+                                Some(s) => issues.push(format!("Line {}: '{}' is only valid inside {{{{#{}}}}}.", n, name, s)),
+                                None => issues.push(format!("Line {}: unknown token '{}'.", n, name)),
+                            }
+                            0
+                        }
+                    };
+                    columns += match spec {
+                        None => default_width,
+                        Some(spec) => match spec.parse::<usize>() {
+                            Ok(w) if w > 0 => w,
+                            _ => {
+                                issues.push(format!("Line {}: invalid width '{}' for '{}'.", n, spec, name));
+                                default_width
+                            }
+                        },
+                    };
+                }
+            }
+            rest = &after[end + 2..];
+        }
+        columns += rest.chars().count();
+        only_section_tags &= rest.trim().is_empty();
+        if stray_close || rest.contains("}}") {
+            issues.push(format!("Line {}: stray '}}}}' without an opening '{{{{'.", n));
+        }
+
+        if !only_section_tags && columns > width {
+            issues.push(format!(
+                "Line {}: up to {} columns wide, paper fits {}.",
+                n, columns, width
+            ));
+        }
+    }
+
+    for (name, line) in open {
+        issues.push(format!("Line {}: section '{}' is never closed.", line, name));
+    }
+    issues
+}
+
+/// Check a receipt template for unknown tokens, unbalanced sections and
+/// lines wider than the configured paper. The issues are listed in the
+/// error's `details.issues`.
+#[tauri::command]
+pub fn validate_receipt_template(
+    settings: tauri::State<'_, SettingsState>,
+    template: String,
+) -> Result<(), AppError> {
+    let width = settings.get().printing.paper_width_chars;
+    let issues = validate(&template, width);
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!("The template has {} issue(s).", issues.len()))
+            .with_details(serde_json::json!({ "issues": issues })))
+    }
+}
@@ -0,0 +1,127 @@
+//! The last few files the app exported, for a "recent exports" menu.
+//!
+//! Exports written by the shell (support bundles, log exports) are added
+//! here automatically; the frontend adds the receipts and invoice PDFs it
+//! saves with `record_export`. The list lives in `recent-exports.json`,
+//! newest first and capped at `MAX_RECENT_EXPORTS`. Entries whose file has
+//! since been deleted are dropped whenever the list is read.
+//!
+//! `open_export` only opens paths that are on the list, so the frontend
+//! can't use it to launch arbitrary files.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::error::AppError;
+use crate::paths::{self, AppPaths};
+
+const RECENT_EXPORTS_FILE: &str = "recent-exports.json";
+const MAX_RECENT_EXPORTS: usize = 20;
+
+#[derive(Default)]
+pub struct RecentExports {
+    /// Serializes read-modify-write of the list file.
+    lock: Mutex<()>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RecentExport {
+    pub path: String,
+    /// `receipt`, `invoice`, `support_bundle`, `logs`, …
+    pub kind: String,
+    /// Epoch millis.
+    pub created_at: u64,
+}
+
+fn list_path(app: &tauri::AppHandle) -> PathBuf {
+    app.state::<AppPaths>().root.join(RECENT_EXPORTS_FILE)
+}
+
+fn save(app: &tauri::AppHandle, entries: &[RecentExport]) {
+    let path = list_path(app);
+    let result = serde_json::to_vec_pretty(entries)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| paths::write_atomic(&path, &bytes).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("⚠️ Failed to save recent exports to {:?}: {}", path, e);
+    }
+}
+
+/// Read the list, dropping (and persisting the removal of) entries whose
+/// file no longer exists.
+fn load_pruned(app: &tauri::AppHandle) -> Vec<RecentExport> {
+    let mut entries: Vec<RecentExport> = paths::read_config_or_default(&list_path(app));
+    let before = entries.len();
+    entries.retain(|entry| Path::new(&entry.path).is_file());
+    if entries.len() != before {
+        save(app, &entries);
+    }
+    entries
+}
+
+/// Put `path` at the top of the list. Failures are logged, never returned:
+/// the export itself already succeeded.
+pub fn record(app: &tauri::AppHandle, path: &Path, kind: &str) {
+    let _guard = app.state::<RecentExports>().lock.lock().unwrap();
+    let path = path.display().to_string();
+    let mut entries = load_pruned(app);
+    entries.retain(|entry| entry.path != path);
+    entries.insert(
+        0,
+        RecentExport {
+            path,
+            kind: kind.to_string(),
+            created_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+        },
+    );
+    entries.truncate(MAX_RECENT_EXPORTS);
+    save(app, &entries);
+}
+
+/// Recent exports, newest first.
+#[tauri::command]
+pub fn recent_exports(app_handle: tauri::AppHandle) -> Vec<RecentExport> {
+    let _guard = app_handle.state::<RecentExports>().lock.lock().unwrap();
+    load_pruned(&app_handle)
+}
+
+/// Add a file the frontend exported (a receipt, an invoice PDF).
+#[tauri::command]
+pub fn record_export(app_handle: tauri::AppHandle, path: String, kind: String) -> Result<(), AppError> {
+    if !Path::new(&path).is_file() {
+        return Err(AppError::NotFound(format!("File not found: {}", path)));
+    }
+    record(&app_handle, Path::new(&path), &kind);
+    Ok(())
+}
+
+/// Open a recent export with its default application.
+#[tauri::command]
+pub fn open_export(app_handle: tauri::AppHandle, path: String) -> Result<(), AppError> {
+    let listed = recent_exports(app_handle).iter().any(|entry| entry.path == path);
+    if !listed {
+        warn!("⚠️ Refusing to open {:?}: not a recent export", path);
+        return Err(AppError::PermissionDenied("File is not in the recent exports list.".to_string()));
+    }
+
+    info!("📂 Opening export {:?}", path);
+
+    #[cfg(target_os = "windows")]
+    let result = Command::new("explorer").arg(&path).spawn();
+    #[cfg(target_os = "macos")]
+    let result = Command::new("open").arg(&path).spawn();
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let result = Command::new("xdg-open").arg(&path).spawn();
+
+    result
+        .map(|_| ())
+        .map_err(|e| AppError::Io(format!("Failed to open {}: {}", path, e)))
+}
@@ -0,0 +1,111 @@
+//! Recovery console for when the main UI fails to load.
+//!
+//! A corrupted bundle or an antivirus quarantine leaves the webview blank
+//! with no way to get help. Every page load of the main window is logged,
+//! and a short while after it finishes a probe script reports back whether
+//! anything rendered. A blank page, or a load that ended up outside the
+//! app's own origin (a webview error page), gets replaced with the embedded
+//! `recovery.html`, which only uses commands the shell already exposes.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use log::{error, info};
+use tauri::webview::{PageLoadEvent, PageLoadPayload};
+use tauri::{Manager, Webview};
+
+use crate::backend::{self, BackendState};
+use crate::error::AppError;
+
+const RECOVERY_HTML: &str = include_str!("recovery.html");
+/// Time after a load finishes before checking that something rendered.
+const PROBE_DELAY: Duration = Duration::from_secs(15);
+
+/// Asks the page whether it rendered anything; `__TAURI_INTERNALS__` is
+/// injected by the shell, so this works even when the app's own JS is broken.
+const PROBE_SCRIPT: &str = r#"(function () {
+  var body = document.body;
+  var blank = !body || ((body.innerText || "").trim() === "" &&
+    !body.querySelector("img, svg, canvas, input, button"));
+  window.__TAURI_INTERNALS__.invoke("report_frontend_load", {
+    blank: blank,
+    url: location.href,
+    readyState: document.readyState
+  });
+})();"#;
+
+/// Bumped on every main-window load so a probe for an older load is skipped.
+static LOAD_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Whether `url` is served by the app itself rather than being an error page.
+fn is_app_origin(url: &tauri::Url) -> bool {
+    match url.scheme() {
+        "tauri" => true,
+        "http" | "https" => matches!(url.host_str(), Some("tauri.localhost" | "localhost" | "127.0.0.1")),
+        _ => false,
+    }
+}
+
+/// Page-load hook for all webviews; only the main window is watched.
+pub fn on_page_load<R: tauri::Runtime>(webview: &Webview<R>, payload: &PageLoadPayload<'_>) {
+    if webview.label() != "main" {
+        return;
+    }
+    let url = payload.url().clone();
+    match payload.event() {
+        PageLoadEvent::Started => {
+            LOAD_GENERATION.fetch_add(1, Ordering::SeqCst);
+            info!("🌐 Main window loading {}", url);
+        }
+        PageLoadEvent::Finished => {
+            info!("🌐 Main window finished loading {}", url);
+            if !is_app_origin(&url) {
+                error!("🧯 Main window navigation failed, ended up at {} — showing recovery console", url);
+                show_recovery(webview);
+                return;
+            }
+
+            let generation = LOAD_GENERATION.load(Ordering::SeqCst);
+            let webview = webview.clone();
+            thread::spawn(move || {
+                thread::sleep(PROBE_DELAY);
+                if LOAD_GENERATION.load(Ordering::SeqCst) == generation {
+                    let _ = webview.eval(PROBE_SCRIPT);
+                }
+            });
+        }
+    }
+}
+
+/// Replace the current document with the recovery page. Writing into the
+/// page keeps the app origin, so IPC from the recovery buttons still works.
+pub fn show_recovery<R: tauri::Runtime>(webview: &Webview<R>) {
+    let html = serde_json::to_string(RECOVERY_HTML).unwrap_or_default();
+    let script = format!("document.open();document.write({});document.close();", html);
+    if let Err(e) = webview.eval(&script) {
+        error!("❌ Failed to show recovery console: {}", e);
+    }
+}
+
+/// Result of the post-load probe.
+#[tauri::command]
+pub fn report_frontend_load(webview: Webview, blank: bool, url: String, ready_state: String) {
+    if !blank {
+        return;
+    }
+    error!(
+        "🧯 Main UI rendered nothing {:?} after loading {} (readyState {}) — showing recovery console",
+        PROBE_DELAY, url, ready_state
+    );
+    show_recovery(&webview);
+}
+
+/// Restart the whole app, for the recovery console's "Restart" button.
+#[tauri::command]
+pub fn restart_app(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    info!("🔄 Restart requested from the recovery console");
+    let result = backend::stop_backend(&app_handle.state::<BackendState>()).map_err(AppError::Backend);
+    crate::audit_log::privileged(&app_handle, "restart_app", result)?;
+    app_handle.restart();
+}
@@ -0,0 +1,166 @@
+//! CPU and memory sampling of the backend sidecar, for capacity planning
+//! and leak detection.
+//!
+//! The backend is a PyInstaller build whose bootloader re-launches itself,
+//! so usage is summed over the sidecar PID and its descendants. Sampling
+//! only happens while a backend is running; when the PID changes (restart)
+//! the rolling window is reset.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::Serialize;
+use sysinfo::{Pid, System};
+use tauri::{Emitter, Manager};
+
+use crate::backend::BackendState;
+use crate::settings::SettingsState;
+
+/// Samples kept for the rolling window.
+const WINDOW_LEN: usize = 60;
+
+#[derive(Clone, Copy, Serialize)]
+pub struct ResourceSample {
+    pub ts: u64,
+    /// Percent of one core; can exceed 100 on multi-core use.
+    pub cpu_percent: f32,
+    pub rss_bytes: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ResourceUsage {
+    pub pid: Option<u32>,
+    pub current: Option<ResourceSample>,
+    pub peak_cpu_percent: f32,
+    pub peak_rss_bytes: u64,
+    /// Oldest first.
+    pub window: Vec<ResourceSample>,
+}
+
+#[derive(Default)]
+struct MonitorInner {
+    pid: Option<u32>,
+    samples: VecDeque<ResourceSample>,
+    peak_cpu: f32,
+    peak_rss: u64,
+    /// Set while RSS is over the threshold so the event fires once per episode.
+    over_threshold: bool,
+}
+
+/// Managed state holding the rolling window for the current backend PID.
+#[derive(Default)]
+pub struct ResourceMonitor(Mutex<MonitorInner>);
+
+#[derive(Clone, Serialize)]
+struct HighMemory {
+    pid: u32,
+    rss_bytes: u64,
+    threshold_bytes: u64,
+}
+
+fn unix_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// `root` followed by every process descended from it, parents first.
+pub fn process_tree(sys: &System, root: Pid) -> Vec<Pid> {
+    let mut tree = vec![root];
+    let mut i = 0;
+    while i < tree.len() {
+        let parent = tree[i];
+        tree.extend(
+            sys.processes()
+                .iter()
+                .filter(|(_, p)| p.parent() == Some(parent))
+                .map(|(pid, _)| *pid),
+        );
+        i += 1;
+    }
+    tree
+}
+
+/// Sum CPU and RSS over `root` and every process descended from it.
+fn sample_tree(sys: &System, root: Pid) -> Option<ResourceSample> {
+    sys.process(root)?;
+    let (mut cpu_percent, mut rss_bytes) = (0.0, 0);
+    for pid in process_tree(sys, root) {
+        if let Some(p) = sys.process(pid) {
+            cpu_percent += p.cpu_usage();
+            rss_bytes += p.memory();
+        }
+    }
+    Some(ResourceSample { ts: unix_millis(), cpu_percent, rss_bytes })
+}
+
+/// Poll the backend every `backend_monitor.interval_secs` on a background thread.
+pub fn start_resource_monitor(app: tauri::AppHandle) {
+    thread::spawn(move || {
+        let mut sys = System::new();
+        loop {
+            let cfg = app.state::<SettingsState>().get().backend_monitor;
+            thread::sleep(Duration::from_secs(cfg.interval_secs.max(1)));
+
+            let pid = app
+                .state::<BackendState>()
+                .child
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map(|c| c.pid());
+
+            let monitor = app.state::<ResourceMonitor>();
+            let mut inner = monitor.0.lock().unwrap();
+            if inner.pid != pid {
+                *inner = MonitorInner { pid, ..Default::default() };
+            }
+            let Some(pid) = pid else { continue };
+
+            sys.refresh_processes();
+            let Some(sample) = sample_tree(&sys, Pid::from_u32(pid)) else { continue };
+
+            if inner.samples.len() == WINDOW_LEN {
+                inner.samples.pop_front();
+            }
+            inner.samples.push_back(sample);
+            inner.peak_cpu = inner.peak_cpu.max(sample.cpu_percent);
+            inner.peak_rss = inner.peak_rss.max(sample.rss_bytes);
+
+            let threshold = cfg.high_memory_mb * 1024 * 1024;
+            let over = threshold > 0 && sample.rss_bytes > threshold;
+            if over && !inner.over_threshold {
+                warn!(
+                    "🐘 Backend PID {} using {} MB (threshold {} MB) — possible leak",
+                    pid,
+                    sample.rss_bytes / (1024 * 1024),
+                    cfg.high_memory_mb
+                );
+                let _ = app.emit(
+                    "backend-high-memory",
+                    HighMemory { pid, rss_bytes: sample.rss_bytes, threshold_bytes: threshold },
+                );
+            } else if !over && inner.over_threshold {
+                info!("✅ Backend memory back under {} MB", cfg.high_memory_mb);
+            }
+            inner.over_threshold = over;
+        }
+    });
+}
+
+/// Current and peak CPU/RSS for the running backend, plus the recent window.
+#[tauri::command]
+pub fn backend_resource_usage(monitor: tauri::State<'_, ResourceMonitor>) -> ResourceUsage {
+    let inner = monitor.0.lock().unwrap();
+    ResourceUsage {
+        pid: inner.pid,
+        current: inner.samples.back().copied(),
+        peak_cpu_percent: inner.peak_cpu,
+        peak_rss_bytes: inner.peak_rss,
+        window: inner.samples.iter().copied().collect(),
+    }
+}
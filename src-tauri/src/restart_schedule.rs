@@ -0,0 +1,175 @@
+//! Scheduled app restarts for kiosks.
+//!
+//! `schedule_restart` accepts either a daily local time (`"03:30"`) or a
+//! one-shot RFC 3339 timestamp (`"2026-01-31T03:30:00+05:30"`). The schedule
+//! is stored in settings as `restart_at`, so it survives restarts. When it
+//! fires, the backend is shut down gracefully and the app relaunches. While
+//! the frontend reports a sale in progress, the restart waits.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveTime, TimeZone};
+use log::{error, info, warn};
+use tauri::Manager;
+
+use crate::backend::{self, BackendState};
+use crate::error::AppError;
+use crate::settings::SettingsState;
+
+const SCHEDULER_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+pub struct RestartScheduler {
+    /// Set by the frontend while a sale is open; vetoes the restart.
+    sale_in_progress: AtomicBool,
+}
+
+impl RestartScheduler {
+    pub fn is_sale_in_progress(&self) -> bool {
+        self.sale_in_progress.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Schedule {
+    Daily(NaiveTime),
+    Once(DateTime<Local>),
+}
+
+fn parse_schedule(spec: &str) -> Result<Schedule, String> {
+    let spec = spec.trim();
+    if let Ok(time) = NaiveTime::parse_from_str(spec, "%H:%M") {
+        return Ok(Schedule::Daily(time));
+    }
+    if let Ok(time) = NaiveTime::parse_from_str(spec, "%H:%M:%S") {
+        return Ok(Schedule::Daily(time));
+    }
+    DateTime::parse_from_rfc3339(spec)
+        .map(|dt| Schedule::Once(dt.with_timezone(&Local)))
+        .map_err(|_| format!("Invalid restart time '{}': use HH:MM or an RFC 3339 timestamp", spec))
+}
+
+/// Next moment the schedule fires after `now`, or `None` for a one-shot
+/// that is already in the past.
+fn next_fire(schedule: Schedule, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    match schedule {
+        Schedule::Once(at) => (at > now).then_some(at),
+        Schedule::Daily(time) => {
+            let today = Local
+                .from_local_datetime(&now.date_naive().and_time(time))
+                .earliest()?;
+            if today > now {
+                Some(today)
+            } else {
+                Local
+                    .from_local_datetime(&(now.date_naive() + chrono::Days::new(1)).and_time(time))
+                    .earliest()
+            }
+        }
+    }
+}
+
+pub fn start_restart_scheduler(app: tauri::AppHandle) {
+    thread::spawn(move || {
+        let mut armed: Option<(String, Option<DateTime<Local>>)> = None;
+        let mut veto_logged = false;
+
+        loop {
+            let spec = app.state::<SettingsState>().get().restart_at;
+
+            let Some(spec) = spec else {
+                armed = None;
+                thread::sleep(SCHEDULER_POLL_INTERVAL);
+                continue;
+            };
+
+            // Recompute the fire time whenever the stored schedule changes.
+            if armed.as_ref().map(|(s, _)| s != &spec).unwrap_or(true) {
+                let fire_at = parse_schedule(&spec).ok().and_then(|s| next_fire(s, Local::now()));
+                match fire_at {
+                    Some(at) => info!("⏰ Restart scheduled for {}", at.to_rfc3339()),
+                    None => warn!("⚠️ Restart schedule '{}' has no future run; ignoring", spec),
+                }
+                armed = Some((spec.clone(), fire_at));
+            }
+
+            let due = matches!(&armed, Some((_, Some(at))) if Local::now() >= *at);
+            if due {
+                if app.state::<RestartScheduler>().sale_in_progress.load(Ordering::SeqCst) {
+                    if !veto_logged {
+                        info!("⏸️ Scheduled restart postponed: sale in progress");
+                        veto_logged = true;
+                    }
+                } else {
+                    run_scheduled_restart(&app, &spec);
+                }
+            }
+
+            thread::sleep(SCHEDULER_POLL_INTERVAL);
+        }
+    });
+}
+
+fn run_scheduled_restart(app: &tauri::AppHandle, spec: &str) {
+    info!("=================================================");
+    info!("⏰ Executing scheduled restart ({})", spec);
+    info!("=================================================");
+
+    // A one-shot schedule is consumed; a daily one re-arms after relaunch.
+    if matches!(parse_schedule(spec), Ok(Schedule::Once(_))) {
+        if let Err(e) = app.state::<SettingsState>().update(serde_json::json!({ "restart_at": null })) {
+            error!("❌ Failed to clear one-shot restart schedule: {}", e);
+        }
+    }
+
+    if let Err(e) = backend::stop_backend(&app.state::<BackendState>()) {
+        error!("❌ Scheduled restart skipped: {}", e);
+        return;
+    }
+    app.restart();
+}
+
+fn schedule(settings: &SettingsState, at: String) -> Result<String, AppError> {
+    let schedule = parse_schedule(&at).map_err(AppError::Validation)?;
+    let fire_at = next_fire(schedule, Local::now())
+        .ok_or_else(|| AppError::Validation("Restart time is in the past".to_string()))?;
+
+    settings.update(serde_json::json!({ "restart_at": at.trim() }))?;
+    info!("⏰ Restart scheduled via command: '{}' (next: {})", at.trim(), fire_at.to_rfc3339());
+    Ok(fire_at.to_rfc3339())
+}
+
+/// Schedule a restart at `at` (daily `HH:MM` or RFC 3339). Returns the next
+/// time it will fire.
+#[tauri::command]
+pub fn schedule_restart(
+    app_handle: tauri::AppHandle,
+    settings: tauri::State<'_, SettingsState>,
+    at: String,
+) -> Result<String, AppError> {
+    let result = schedule(&settings, at);
+    crate::audit_log::privileged(&app_handle, "schedule_restart", result)
+}
+
+fn cancel(settings: &SettingsState) -> Result<(), AppError> {
+    settings.update(serde_json::json!({ "restart_at": null }))?;
+    info!("⏰ Scheduled restart cancelled");
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cancel_scheduled_restart(
+    app_handle: tauri::AppHandle,
+    settings: tauri::State<'_, SettingsState>,
+) -> Result<(), AppError> {
+    let result = cancel(&settings);
+    crate::audit_log::privileged(&app_handle, "cancel_scheduled_restart", result)
+}
+
+/// The frontend flags open sales so a scheduled restart never interrupts one.
+#[tauri::command]
+pub fn set_sale_in_progress(state: tauri::State<'_, RestartScheduler>, in_progress: bool) {
+    state.sale_in_progress.store(in_progress, Ordering::SeqCst);
+}
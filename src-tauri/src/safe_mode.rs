@@ -0,0 +1,155 @@
+//! Read-only safe mode for a crash-looping backend.
+//!
+//! A backend that keeps crashing may be tripping over corrupt data, and
+//! restarting it normally risks making that worse. Once it has crashed
+//! `CRASH_THRESHOLD` times within `CRASH_WINDOW`, `backend-crash-loop` is
+//! emitted so the UI can offer safe mode. Entering it is always the user's
+//! call: `start_safe_mode` is refused unless the backend is crash-looping.
+//!
+//! In safe mode the backend is started with `--readonly`, so data can be
+//! viewed, exported and checked but not changed. The session is not
+//! persisted — the next app start is normal — and is logged and audited
+//! on its own (`safe_mode_started` / `safe_mode_ended`). `safe-mode
+//! { active }` is emitted on every change for the UI's banner.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use serde::Serialize;
+use serde_json::json;
+use tauri::{Emitter, Manager};
+
+use crate::backend::{self, BackendState};
+use crate::error::AppError;
+use crate::event_bus::LifecycleEvent;
+
+/// Crashes within `CRASH_WINDOW` that count as a crash loop.
+const CRASH_THRESHOLD: usize = 3;
+const CRASH_WINDOW: Duration = Duration::from_secs(5 * 60);
+const READONLY_FLAG: &str = "--readonly";
+
+#[derive(Default)]
+pub struct SafeModeState {
+    /// When the current session started, while in safe mode.
+    started: Mutex<Option<Instant>>,
+}
+
+#[derive(Clone, Serialize)]
+struct SafeModeChanged {
+    active: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct CrashLoop {
+    crashes: usize,
+    window_secs: u64,
+}
+
+pub fn is_active(app: &tauri::AppHandle) -> bool {
+    app.state::<SafeModeState>().started.lock().unwrap().is_some()
+}
+
+/// Extra arguments for the backend sidecar while in safe mode.
+pub fn backend_args(app: &tauri::AppHandle) -> Vec<&'static str> {
+    if is_active(app) {
+        vec![READONLY_FLAG]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Crashes (non-zero exits or signals) within the last `CRASH_WINDOW`.
+fn recent_crashes(state: &BackendState) -> usize {
+    let since = (std::time::SystemTime::now() - CRASH_WINDOW)
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    state
+        .exit_history()
+        .iter()
+        .filter(|exit| exit.ts >= since && (exit.code != Some(0) || exit.signal.is_some()))
+        .count()
+}
+
+/// Event-bus subscriber: offer safe mode once the backend is crash-looping.
+pub fn on_lifecycle(app: &tauri::AppHandle, event: &LifecycleEvent) {
+    let LifecycleEvent::BackendExited { expected: false, .. } = event else { return };
+    if is_active(app) {
+        return;
+    }
+    let crashes = recent_crashes(&app.state::<BackendState>());
+    if crashes >= CRASH_THRESHOLD {
+        warn!(
+            "⚠️ Backend crashed {} times in {} min — offering read-only safe mode",
+            crashes,
+            CRASH_WINDOW.as_secs() / 60
+        );
+        let _ = app.emit("backend-crash-loop", CrashLoop { crashes, window_secs: CRASH_WINDOW.as_secs() });
+    }
+}
+
+fn set_active(app: &tauri::AppHandle, active: bool) {
+    let _ = app.emit("safe-mode", SafeModeChanged { active });
+    crate::window_title::refresh(app);
+}
+
+async fn start(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    if is_active(&app_handle) {
+        return Err(AppError::Conflict("Safe mode is already active.".to_string()));
+    }
+    let crashes = recent_crashes(&app_handle.state::<BackendState>());
+    if crashes < CRASH_THRESHOLD {
+        return Err(AppError::Conflict(
+            "Safe mode is only available after the backend has crashed repeatedly.".to_string(),
+        ));
+    }
+
+    warn!("=================================================");
+    warn!("🛟 [SAFE MODE] Starting read-only session after {} backend crashes", crashes);
+    warn!("=================================================");
+    *app_handle.state::<SafeModeState>().started.lock().unwrap() = Some(Instant::now());
+    crate::audit_log::record(&app_handle, "safe_mode_started", json!({ "crashes": crashes }));
+    set_active(&app_handle, true);
+
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || backend::restart(&app))
+        .await
+        .map_err(|e| format!("Safe mode task failed: {}", e))?
+        .map_err(AppError::Backend)
+}
+
+/// Restart the backend read-only. Only offered after repeated crashes.
+#[tauri::command]
+pub async fn start_safe_mode(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    let result = start(app_handle.clone()).await;
+    crate::audit_log::privileged(&app_handle, "start_safe_mode", result)
+}
+
+async fn end(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    let Some(started) = app_handle.state::<SafeModeState>().started.lock().unwrap().take() else {
+        return Ok(());
+    };
+    let mins = started.elapsed().as_secs() / 60;
+    info!("🛟 [SAFE MODE] Session ended after {} min; restarting the backend normally", mins);
+    crate::audit_log::record(&app_handle, "safe_mode_ended", json!({ "duration_mins": mins }));
+    set_active(&app_handle, false);
+
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || backend::restart(&app))
+        .await
+        .map_err(|e| format!("Safe mode task failed: {}", e))?
+        .map_err(AppError::Backend)
+}
+
+/// Leave safe mode and restart the backend normally.
+#[tauri::command]
+pub async fn end_safe_mode(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    let result = end(app_handle.clone()).await;
+    crate::audit_log::privileged(&app_handle, "end_safe_mode", result)
+}
+
+#[tauri::command]
+pub fn safe_mode_status(app_handle: tauri::AppHandle) -> bool {
+    is_active(&app_handle)
+}
@@ -0,0 +1,215 @@
+//! Scheduled CSV exports of sales to a watched folder.
+//!
+//! Some integrations ingest sales from a shared folder. With
+//! `sales_export.dir` set and `sales_export.interval_mins` non-zero, every
+//! interval the shell asks the backend for the transactions it hasn't
+//! exported yet (`GET /api/export/sales?after=<cursor>`, answering
+//! `{ csv, rows, cursor }`) and writes them to `<dir>/sales-<ts>.csv`.
+//!
+//! The cursor is kept in `sales-export.json` and only advances once the
+//! file is written, so rows are never exported twice and never skipped. A
+//! folder on a network share can be briefly unreachable: the write is
+//! retried a few times, and if it still fails `export-failed` is emitted
+//! and the same rows are picked up by the next run.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+use crate::backend::{self, BackendState};
+use crate::backend_transport::BackendRequest;
+use crate::error::AppError;
+use crate::feature_flags;
+use crate::paths::{self, AppPaths};
+use crate::settings::SettingsState;
+
+const EXPORT_PATH: &str = "/api/export/sales";
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(60);
+const STATE_FILE: &str = "sales-export.json";
+const SCHEDULER_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Waits between attempts to write to the export folder.
+const WRITE_RETRY_DELAYS: [Duration; 2] = [Duration::from_secs(5), Duration::from_secs(30)];
+
+#[derive(Default)]
+pub struct SalesExportState {
+    /// Keeps a manual export from overlapping a scheduled one, so the
+    /// cursor file has one writer.
+    running: AtomicBool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct ExportCursor {
+    /// Opaque position from the backend; rows up to it are exported.
+    cursor: Option<String>,
+    /// Epoch millis of the last completed run.
+    last_run: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct SalesBatch {
+    csv: String,
+    rows: u64,
+    cursor: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ExportResult {
+    /// The file written, or `None` when there was nothing new.
+    pub path: Option<String>,
+    pub rows: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct ExportFailed {
+    dir: String,
+    error: String,
+    attempts: usize,
+}
+
+fn state_path(app: &tauri::AppHandle) -> PathBuf {
+    app.state::<AppPaths>().root.join(STATE_FILE)
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn fetch(state: &BackendState, cursor: Option<&str>) -> Result<SalesBatch, String> {
+    let path = match cursor {
+        Some(cursor) => format!("{}?after={}", EXPORT_PATH, urlencoding::encode(cursor)),
+        None => EXPORT_PATH.to_string(),
+    };
+    let response = state.transport().request(&BackendRequest::get(&path, EXPORT_TIMEOUT))?;
+    if !response.is_success() {
+        return Err(format!("HTTP {}: {}", response.status, response.body.trim()));
+    }
+    serde_json::from_str(&response.body).map_err(|e| format!("Invalid export response: {}", e))
+}
+
+/// Write `bytes` to `path`, retrying while the folder is unreachable.
+fn write_with_retry(path: &Path, bytes: &[u8]) -> Result<(), (String, usize)> {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let result = std::fs::create_dir_all(path.parent().unwrap_or(path))
+            .and_then(|_| paths::write_atomic(path, bytes));
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) => match WRITE_RETRY_DELAYS.get(attempt - 1) {
+                Some(delay) => {
+                    warn!(
+                        "⚠️ Sales export write to {:?} failed (attempt {}), retrying in {:?}: {}",
+                        path, attempt, delay, e
+                    );
+                    thread::sleep(*delay);
+                }
+                None => return Err((e.to_string(), attempt)),
+            },
+        }
+    }
+}
+
+/// Export everything since the last run to `dir`. Blocking.
+fn run(app: &tauri::AppHandle, dir: &str) -> Result<ExportResult, String> {
+    let export = app.state::<SalesExportState>();
+    if export.running.swap(true, Ordering::SeqCst) {
+        return Err("A sales export is already running.".to_string());
+    }
+    let result = export_batch(app, dir);
+    export.running.store(false, Ordering::SeqCst);
+    result
+}
+
+fn export_batch(app: &tauri::AppHandle, dir: &str) -> Result<ExportResult, String> {
+    let mut saved: ExportCursor = paths::read_config_or_default(&state_path(app));
+    let batch = fetch(&app.state::<BackendState>(), saved.cursor.as_deref())?;
+
+    let mut path = None;
+    if batch.rows > 0 {
+        let file = Path::new(dir).join(format!("sales-{}.csv", crate::timestamps::file_stamp()));
+        if let Err((e, attempts)) = write_with_retry(&file, batch.csv.as_bytes()) {
+            error!("❌ Sales export to {} failed after {} attempt(s): {}", dir, attempts, e);
+            let _ = app.emit("export-failed", ExportFailed { dir: dir.to_string(), error: e.clone(), attempts });
+            return Err(format!("Failed to write to {}: {}", dir, e));
+        }
+        info!("📤 Exported {} sale row(s) to {:?}", batch.rows, file);
+        crate::recent_exports::record(app, &file, "sales_csv");
+        path = Some(file.display().to_string());
+    }
+
+    // Only now is it safe to move past these rows.
+    if batch.cursor.is_some() {
+        saved.cursor = batch.cursor;
+    }
+    saved.last_run = Some(unix_millis());
+    let bytes = serde_json::to_vec_pretty(&saved).map_err(|e| e.to_string())?;
+    paths::write_atomic(&state_path(app), &bytes)
+        .map_err(|e| format!("Failed to save sales export cursor: {}", e))?;
+    Ok(ExportResult { path, rows: batch.rows })
+}
+
+pub fn start_export_scheduler(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(SCHEDULER_POLL_INTERVAL);
+
+        let settings = app.state::<SettingsState>().get().sales_export;
+        let Some(dir) = settings.dir.filter(|d| !d.trim().is_empty()) else { continue };
+        if settings.interval_mins == 0 {
+            continue;
+        }
+        let last_run = paths::read_config_or_default::<ExportCursor>(&state_path(&app)).last_run;
+        let due = match last_run {
+            Some(at) => unix_millis() >= at + settings.interval_mins * 60_000,
+            None => true,
+        };
+        // `scheduled_sales_export` is a rollout kill switch.
+        let enabled = feature_flags::enabled(&app, "scheduled_sales_export", true);
+        if !due || !enabled || backend::phase(&app) != "ready" {
+            continue;
+        }
+        if let Err(e) = run(&app, dir.trim()) {
+            warn!("⚠️ Scheduled sales export failed: {}", e);
+        }
+    });
+}
+
+/// Export to `dir` every `interval_mins`; `null` or `0` turns it off.
+#[tauri::command]
+pub fn set_export_schedule(
+    settings: tauri::State<'_, SettingsState>,
+    dir: Option<String>,
+    interval_mins: u64,
+) -> Result<(), AppError> {
+    let dir = dir.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+    settings.update(serde_json::json!({ "sales_export": { "dir": dir, "interval_mins": interval_mins } }))?;
+    match (&dir, interval_mins) {
+        (Some(dir), mins) if mins > 0 => info!("📤 Sales export scheduled every {} min to {}", mins, dir),
+        _ => info!("📤 Scheduled sales export turned off"),
+    }
+    Ok(())
+}
+
+/// Run an export to the configured folder right away.
+#[tauri::command]
+pub async fn trigger_export_now(app_handle: tauri::AppHandle) -> Result<ExportResult, AppError> {
+    let dir = app_handle
+        .state::<SettingsState>()
+        .get()
+        .sales_export
+        .dir
+        .filter(|d| !d.trim().is_empty())
+        .ok_or_else(|| AppError::Validation("No sales export folder is configured.".to_string()))?;
+    backend::ensure_ready(&app_handle, "trigger_export_now")?;
+    tauri::async_runtime::spawn_blocking(move || run(&app_handle, dir.trim()))
+        .await
+        .map_err(|e| format!("Sales export task failed: {}", e))?
+        .map_err(AppError::Backend)
+}
@@ -0,0 +1,112 @@
+//! Screenshots of the main window for bug reports.
+//!
+//! Captures are saved as PNGs under `screenshots/` in the app data dir and
+//! the newest few are added to support bundles. They show exactly what is
+//! on screen, which may include customer names, amounts and other bill
+//! details; nothing is redacted.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use log::{info, warn};
+use tauri::Manager;
+use xcap::image::ImageFormat;
+
+use crate::error::AppError;
+use crate::paths::AppPaths;
+
+pub const SCREENSHOTS_DIR: &str = "screenshots";
+/// How many of the newest screenshots go into a support bundle.
+pub const BUNDLE_SCREENSHOTS: usize = 3;
+/// Time for a restored window to repaint before it is captured.
+const REPAINT_DELAY: Duration = Duration::from_millis(400);
+
+pub fn screenshots_dir(paths: &AppPaths) -> PathBuf {
+    paths.root.join(SCREENSHOTS_DIR)
+}
+
+/// The newest `limit` screenshots, newest first.
+pub fn latest(paths: &AppPaths, limit: usize) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(screenshots_dir(paths)) else { return Vec::new() };
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "png"))
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .collect();
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    files.into_iter().take(limit).map(|(_, path)| path).collect()
+}
+
+/// Find our main window among the OS windows and save it as a PNG.
+fn capture_to(title: &str, path: &Path) -> Result<(), String> {
+    let pid = std::process::id();
+    let window = xcap::Window::all()
+        .map_err(|e| format!("Failed to list windows: {}", e))?
+        .into_iter()
+        .find(|w| w.pid() == pid && w.title() == title)
+        .ok_or_else(|| "Main window not found on screen.".to_string())?;
+
+    let image = window
+        .capture_image()
+        .map_err(|e| format!("Failed to capture window: {}", e))?;
+    image
+        .save_with_format(path, ImageFormat::Png)
+        .map_err(|e| format!("Failed to save {:?}: {}", path, e))
+}
+
+/// Capture the main window and return the saved PNG path. A minimized or
+/// hidden window is brought back briefly for the capture and then returned
+/// to its previous state.
+#[tauri::command]
+pub async fn capture_screenshot(app_handle: tauri::AppHandle) -> Result<String, AppError> {
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or_else(|| AppError::NotFound("Main window not found.".to_string()))?;
+    let title = window.title()?;
+
+    let was_minimized = window.is_minimized().unwrap_or(false);
+    let was_hidden = !window.is_visible().unwrap_or(true);
+    if was_minimized {
+        let _ = window.unminimize();
+    }
+    if was_hidden {
+        let _ = window.show();
+    }
+
+    let dir = screenshots_dir(&app_handle.state::<AppPaths>());
+    fs::create_dir_all(&dir).map_err(|e| AppError::Io(format!("Failed to create {:?}: {}", dir, e)))?;
+    let path = dir.join(format!(
+        "screenshot-{}.png",
+        crate::timestamps::file_stamp()
+    ));
+
+    let capture_path = path.clone();
+    let restored = was_minimized || was_hidden;
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        if restored {
+            thread::sleep(REPAINT_DELAY);
+        }
+        capture_to(&title, &capture_path)
+    })
+    .await
+    .map_err(|e| format!("Screenshot failed: {}", e))?;
+
+    if was_hidden {
+        let _ = window.hide();
+    } else if was_minimized {
+        let _ = window.minimize();
+    }
+
+    match result {
+        Ok(()) => {
+            info!("📸 Screenshot saved to {:?}", path);
+            Ok(path.display().to_string())
+        }
+        Err(e) => {
+            warn!("⚠️ {}", e);
+            Err(AppError::from(e))
+        }
+    }
+}
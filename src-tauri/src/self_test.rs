@@ -0,0 +1,151 @@
+//! One-button opening checklist: `run_self_test`.
+//!
+//! Runs the backend health check, network status, a printer test page on
+//! the default printer, free disk space and update-server reachability.
+//! The checks are independent, so they all run at once, each under its own
+//! timeout so a hung printer or network can't hold up the others.
+//! `self-test-progress` is emitted as each result comes in, and the
+//! aggregate is logged.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::backend::BackendState;
+use crate::backend_transport::BackendRequest;
+use crate::paths::AppPaths;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Printing waits on the spooler and the page itself.
+const PRINTER_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Serialize)]
+pub struct SelfTestCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SelfTestReport {
+    pub checks: Vec<SelfTestCheck>,
+    pub passed: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct SelfTestProgress {
+    check: SelfTestCheck,
+    done: usize,
+    total: usize,
+}
+
+type CheckTask = tauri::async_runtime::JoinHandle<SelfTestCheck>;
+
+/// Start `check` with its own timeout.
+fn spawn_check<F>(name: &'static str, timeout: Duration, check: F) -> CheckTask
+where
+    F: Future<Output = Result<String, String>> + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        let started = Instant::now();
+        let (ok, detail) = match tokio::time::timeout(timeout, check).await {
+            Ok(Ok(detail)) => (true, detail),
+            Ok(Err(detail)) => (false, detail),
+            Err(_) => (false, format!("No result within {}s", timeout.as_secs())),
+        };
+        SelfTestCheck { name, ok, detail, duration_ms: started.elapsed().as_millis() as u64 }
+    })
+}
+
+/// Run blocking `work` on the blocking pool as a check.
+fn spawn_blocking_check<F>(name: &'static str, work: F) -> CheckTask
+where
+    F: FnOnce() -> Result<String, String> + Send + 'static,
+{
+    spawn_check(name, CHECK_TIMEOUT, async move {
+        tauri::async_runtime::spawn_blocking(work)
+            .await
+            .map_err(|e| format!("Check failed to run: {}", e))?
+    })
+}
+
+fn backend_health(app: &tauri::AppHandle) -> Result<String, String> {
+    let started = Instant::now();
+    let response = app
+        .state::<BackendState>()
+        .transport()
+        .request(&BackendRequest::get("/api/health", Duration::from_secs(5)))?;
+    if response.is_success() {
+        Ok(format!("Healthy ({} ms)", started.elapsed().as_millis()))
+    } else {
+        Err(format!("Health check returned HTTP {}", response.status))
+    }
+}
+
+fn network(app: &tauri::AppHandle) -> Result<String, String> {
+    let status = crate::network::check(app, true);
+    match status.latency_ms {
+        Some(ms) if status.online => Ok(format!("Online ({} ms)", ms)),
+        _ => Err("Offline".to_string()),
+    }
+}
+
+async fn printer(app: tauri::AppHandle) -> Result<String, String> {
+    let name = crate::read_printer_prefs(&app)
+        .default_printer
+        .ok_or_else(|| "No default printer is set".to_string())?;
+    crate::printer_test::test_printer(app, name).await.map_err(|e| e.message().to_string())
+}
+
+/// Run every check and return the combined report.
+#[tauri::command]
+pub async fn run_self_test(app_handle: tauri::AppHandle) -> SelfTestReport {
+    info!("🩺 Running self-test");
+    let app = &app_handle;
+    let tasks = vec![
+        spawn_blocking_check("backend_health", {
+            let app = app.clone();
+            move || backend_health(&app)
+        }),
+        spawn_blocking_check("network", {
+            let app = app.clone();
+            move || network(&app)
+        }),
+        spawn_check("printer", PRINTER_TIMEOUT, printer(app.clone())),
+        spawn_blocking_check("disk_space", {
+            let paths = app.state::<AppPaths>().inner().clone();
+            move || crate::preflight::check_disk_space(&paths)
+        }),
+        spawn_blocking_check("update_server", crate::preflight::check_update_reachable),
+    ];
+
+    let total = tasks.len();
+    let mut checks = Vec::with_capacity(total);
+    for task in tasks {
+        let check = task.await.unwrap_or_else(|e| SelfTestCheck {
+            name: "unknown",
+            ok: false,
+            detail: format!("Check panicked: {}", e),
+            duration_ms: 0,
+        });
+        if check.ok {
+            info!("✅ Self-test {}: {}", check.name, check.detail);
+        } else {
+            warn!("⚠️ Self-test {}: {}", check.name, check.detail);
+        }
+        checks.push(check.clone());
+        let _ = app.emit("self-test-progress", SelfTestProgress { check, done: checks.len(), total });
+    }
+
+    let failed: Vec<&str> = checks.iter().filter(|c| !c.ok).map(|c| c.name).collect();
+    if failed.is_empty() {
+        info!("🩺 Self-test passed: all {} checks OK", total);
+    } else {
+        warn!("🩺 Self-test: {}/{} checks failed ({})", failed.len(), total, failed.join(", "));
+    }
+    SelfTestReport { passed: failed.is_empty(), checks }
+}
@@ -0,0 +1,52 @@
+//! Per-process session ID, so logs aggregated from many terminals can be
+//! told apart. Generated once at startup and stable until the process exits.
+//!
+//! The device ID identifies the terminal itself across restarts and
+//! profiles; it is generated the first time it's needed and kept in
+//! `device-id` in the platform data dir.
+
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::warn;
+
+use crate::paths::AppPaths;
+
+static SESSION_ID: OnceLock<String> = OnceLock::new();
+
+/// 16 hex characters. `RandomState` is seeded from the OS RNG; the time
+/// and PID are mixed in so two processes never share an ID.
+fn generate() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    hasher.write_u32(std::process::id());
+    format!("{:016x}", hasher.finish())
+}
+
+pub fn session_id() -> &'static str {
+    SESSION_ID.get_or_init(generate)
+}
+
+const DEVICE_ID_FILE: &str = "device-id";
+
+pub fn device_id(paths: &AppPaths) -> String {
+    let path = paths.base.join(DEVICE_ID_FILE);
+    if let Ok(id) = fs::read_to_string(&path) {
+        if !id.trim().is_empty() {
+            return id.trim().to_string();
+        }
+    }
+    let id = generate();
+    if let Err(e) = crate::paths::write_atomic(&path, id.as_bytes()) {
+        warn!("⚠️ Failed to save device ID to {:?}: {}", path, e);
+    }
+    id
+}
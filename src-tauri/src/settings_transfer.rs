@@ -0,0 +1,143 @@
+//! Cloning one terminal's configuration onto others.
+//!
+//! `export_settings` writes `settings-<ts>.json` to the export dir with the
+//! shell settings, the default printer and `backend-config.json`.
+//! Per-machine values are left out: the license, PIN, window geometry and
+//! update state are not exported at all, and `MACHINE_SPECIFIC_SETTINGS`
+//! are stripped from the settings. `import_settings` checks every section
+//! before writing any of them, applies them and has the backend reload its
+//! config (restarting it if needed).
+
+use std::fs;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::Manager;
+
+use crate::backend_config::{self, BackendConfig};
+use crate::error::AppError;
+use crate::settings::{self, SettingsState};
+
+const FORMAT_VERSION: u32 = 1;
+
+/// Settings paths (`section.key`) that describe this machine rather than
+/// how the store wants terminals set up.
+const MACHINE_SPECIFIC_SETTINGS: [&str; 6] = [
+    "backend_working_dir",
+    "backend_cpu_affinity",
+    "onboarded",
+    "window.customer_display",
+    "window.env_tag",
+    "network.ca_certificates",
+];
+
+#[derive(Serialize, Deserialize)]
+struct SettingsBundle {
+    format: u32,
+    app_version: String,
+    exported_at: String,
+    settings: Value,
+    default_printer: Option<String>,
+    backend_config: BackendConfig,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ImportReport {
+    pub app_version: String,
+    /// The sections that were applied.
+    pub imported: Vec<&'static str>,
+}
+
+fn strip_machine_specific(settings: &mut Value) {
+    for path in MACHINE_SPECIFIC_SETTINGS {
+        let (parent, key) = match path.split_once('.') {
+            Some((section, key)) => (settings.get_mut(section), key),
+            None => (Some(&mut *settings), path),
+        };
+        if let Some(Value::Object(map)) = parent {
+            map.remove(key);
+        }
+    }
+}
+
+/// Bundle this terminal's configuration into one file. Returns its path.
+#[tauri::command]
+pub async fn export_settings(app_handle: tauri::AppHandle) -> Result<String, AppError> {
+    let mut settings = serde_json::to_value(app_handle.state::<SettingsState>().get())?;
+    strip_machine_specific(&mut settings);
+    let bundle = SettingsBundle {
+        format: FORMAT_VERSION,
+        app_version: app_handle.package_info().version.to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        settings,
+        default_printer: crate::read_printer_prefs(&app_handle).default_printer,
+        backend_config: backend_config::read_config(&app_handle).map_err(AppError::Io)?,
+    };
+
+    let path = crate::diagnostics::export_dir(&app_handle)
+        .join(format!("settings-{}.json", crate::timestamps::file_stamp()));
+    let text = serde_json::to_vec_pretty(&bundle)?;
+    crate::paths::write_atomic(&path, &text)
+        .map_err(|e| AppError::Io(format!("Failed to write {:?}: {}", path, e)))?;
+    info!("📤 Settings exported to {:?}", path);
+    crate::recent_exports::record(&app_handle, &path, "settings");
+    Ok(path.display().to_string())
+}
+
+async fn import(app_handle: tauri::AppHandle, path: String) -> Result<ImportReport, AppError> {
+    let text = fs::read_to_string(&path)
+        .map_err(|e| AppError::NotFound(format!("Failed to read {}: {}", path, e)))?;
+    let mut bundle: SettingsBundle = serde_json::from_str(&text)
+        .map_err(|e| AppError::Validation(format!("Not a settings export: {}", e)))?;
+    if bundle.format != FORMAT_VERSION {
+        return Err(AppError::Validation(format!(
+            "Unsupported settings export format {} (expected {}).",
+            bundle.format, FORMAT_VERSION
+        )));
+    }
+
+    // Keep this machine's values even if the file carries them.
+    strip_machine_specific(&mut bundle.settings);
+    bundle
+        .backend_config
+        .validate()
+        .map_err(|e| AppError::Validation(format!("Invalid backend config: {}", e)))?;
+    // Validates the settings before anything is written.
+    let updated = app_handle.state::<SettingsState>().update(bundle.settings)?;
+    settings::apply(&updated);
+    crate::theme::apply(&app_handle);
+    crate::window_title::refresh(&app_handle);
+
+    crate::write_printer_prefs(&app_handle, &crate::PrinterPrefs { default_printer: bundle.default_printer })
+        .map_err(AppError::Io)?;
+
+    let app = app_handle.clone();
+    let cfg = bundle.backend_config;
+    tauri::async_runtime::spawn_blocking(move || backend_config::write_config(&app, &cfg))
+        .await
+        .map_err(|e| format!("Failed to apply backend config: {}", e))?
+        .map_err(AppError::Backend)?;
+
+    let imported = vec!["settings", "default_printer", "backend_config"];
+    info!(
+        "📥 Imported {} from {} (exported by app {})",
+        imported.join(", "),
+        path,
+        bundle.app_version
+    );
+    crate::audit_log::record(
+        &app_handle,
+        "settings_imported",
+        json!({ "path": path, "app_version": bundle.app_version, "imported": imported }),
+    );
+    Ok(ImportReport { app_version: bundle.app_version, imported })
+}
+
+/// Apply a file written by `export_settings`. Nothing is changed unless
+/// every section is valid.
+#[tauri::command]
+pub async fn import_settings(app_handle: tauri::AppHandle, path: String) -> Result<ImportReport, AppError> {
+    let result = import(app_handle.clone(), path).await;
+    crate::audit_log::privileged(&app_handle, "import_settings", result)
+}
@@ -0,0 +1,93 @@
+//! Splash window shown while the backend boots.
+//!
+//! The main window stays hidden until the backend reports ready, then the
+//! splash closes and main is revealed. Status text (`spawning`,
+//! `connecting`, `ready`) is pushed into the splash page with `eval` and
+//! also emitted as `splash-status`. Every failure path calls `finish`, and a
+//! fallback timer does too, so the splash can never stay on screen.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::backend::HANDSHAKE_TIMEOUT;
+use crate::settings::SettingsState;
+
+pub const SPLASH_LABEL: &str = "splash";
+/// Extra time after the backend handshake timeout before the splash is
+/// force-closed.
+const FALLBACK_GRACE: Duration = Duration::from_secs(5);
+
+/// Set once main has been revealed, so later backend restarts don't
+/// re-show a window the idle lock has hidden.
+static FINISHED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Serialize)]
+struct SplashStatus<'a> {
+    stage: &'a str,
+    message: &'a str,
+}
+
+/// Open the splash if enabled in settings. Returns whether it was shown;
+/// if not, the caller should show the main window itself.
+pub fn show<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> bool {
+    if !app.state::<SettingsState>().get().window.splash {
+        FINISHED.store(true, Ordering::SeqCst);
+        return false;
+    }
+
+    let built = WebviewWindowBuilder::new(app, SPLASH_LABEL, WebviewUrl::App("splash.html".into()))
+        .title("Siri Billing")
+        .inner_size(420.0, 260.0)
+        .resizable(false)
+        .decorations(false)
+        .center()
+        .skip_taskbar(true)
+        .always_on_top(true)
+        .build();
+    if let Err(e) = built {
+        warn!("⚠️ Failed to open splash window: {}", e);
+        FINISHED.store(true, Ordering::SeqCst);
+        return false;
+    }
+    info!("🪟 Splash window shown");
+
+    let fallback_app = app.clone();
+    thread::spawn(move || {
+        thread::sleep(HANDSHAKE_TIMEOUT + FALLBACK_GRACE);
+        if !FINISHED.load(Ordering::SeqCst) {
+            warn!("⚠️ Backend still not ready; closing splash anyway");
+            finish(&fallback_app);
+        }
+    });
+    true
+}
+
+/// Update the status line on the splash.
+pub fn set_status<R: tauri::Runtime>(app: &tauri::AppHandle<R>, stage: &str, message: &str) {
+    let _ = app.emit("splash-status", SplashStatus { stage, message });
+    if let Some(splash) = app.get_webview_window(SPLASH_LABEL) {
+        let text = serde_json::to_string(message).unwrap_or_default();
+        let _ = splash.eval(&format!("window.setSplashStatus && window.setSplashStatus({})", text));
+    }
+}
+
+/// Close the splash and reveal the main window. Safe to call repeatedly.
+pub fn finish<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    if FINISHED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    if let Some(splash) = app.get_webview_window(SPLASH_LABEL) {
+        let _ = splash.close();
+    }
+    if let Some(main) = app.get_webview_window("main") {
+        let _ = main.show();
+        let _ = main.set_focus();
+        crate::startup_timing::mark(app, "window_shown");
+    }
+    info!("🪟 Splash closed, main window shown");
+}
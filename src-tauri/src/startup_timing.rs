@@ -0,0 +1,93 @@
+//! Startup timing breakdown, for "the app is slow to open" reports.
+//!
+//! The clock starts when `main` builds the app. Each milestone is marked
+//! the first time it is reached (later backend restarts don't move it):
+//!
+//! - `setup_started`: Tauri and its plugins are initialized
+//! - `settings_loaded`: data dirs created and settings read from disk
+//! - `preflight_done`: preflight checks finished
+//! - `sidecar_spawned`: the backend process is running
+//! - `backend_ready`: the backend's port handshake arrived
+//! - `window_shown`: the main window is visible
+//!
+//! Once the backend is ready and the window shown, the breakdown is logged.
+//! `startup_timings` returns the marks reached so far in the order they
+//! happened, each with the time since the previous one.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use log::info;
+use serde::Serialize;
+use tauri::Manager;
+
+pub struct StartupTimings {
+    started: Instant,
+    marks: Mutex<Vec<(&'static str, Duration)>>,
+}
+
+impl Default for StartupTimings {
+    fn default() -> Self {
+        Self { started: Instant::now(), marks: Mutex::new(Vec::new()) }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct StartupMark {
+    pub name: &'static str,
+    /// Since the app started.
+    pub at_ms: u64,
+    /// Since the previous mark; how long this phase took.
+    pub duration_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct StartupReport {
+    pub marks: Vec<StartupMark>,
+    /// Both `backend_ready` and `window_shown` have been reached.
+    pub complete: bool,
+}
+
+fn report(marks: &[(&'static str, Duration)]) -> StartupReport {
+    let mut previous = Duration::ZERO;
+    let marks: Vec<StartupMark> = marks
+        .iter()
+        .map(|&(name, at)| {
+            let mark = StartupMark {
+                name,
+                at_ms: at.as_millis() as u64,
+                duration_ms: at.saturating_sub(previous).as_millis() as u64,
+            };
+            previous = at;
+            mark
+        })
+        .collect();
+    let reached = |name: &str| marks.iter().any(|m: &StartupMark| m.name == name);
+    let complete = reached("backend_ready") && reached("window_shown");
+    StartupReport { marks, complete }
+}
+
+/// Record `name` if it hasn't been reached yet, and log the breakdown once
+/// startup is complete.
+pub fn mark<R: tauri::Runtime>(app: &tauri::AppHandle<R>, name: &'static str) {
+    let Some(timings) = app.try_state::<StartupTimings>() else { return };
+    let mut marks = timings.marks.lock().unwrap();
+    if marks.iter().any(|(n, _)| *n == name) {
+        return;
+    }
+    let was_complete = report(&marks).complete;
+    marks.push((name, timings.started.elapsed()));
+
+    let report = report(&marks);
+    if report.complete && !was_complete {
+        let breakdown: Vec<String> =
+            report.marks.iter().map(|m| format!("{} +{}ms", m.name, m.duration_ms)).collect();
+        let total = report.marks.last().map_or(0, |m| m.at_ms);
+        info!("⏱️ Startup took {}ms: {}", total, breakdown.join(", "));
+    }
+}
+
+#[tauri::command]
+pub fn startup_timings(timings: tauri::State<'_, StartupTimings>) -> StartupReport {
+    report(&timings.marks.lock().unwrap())
+}
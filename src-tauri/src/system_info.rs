@@ -0,0 +1,73 @@
+//! Machine specs for support tickets.
+//!
+//! OS, CPU and RAM don't change while the app runs, so they are read from
+//! `sysinfo` once and cached; only the free disk space is measured on each
+//! call. Anything the platform doesn't report comes back as `None`.
+
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
+
+use crate::error::AppError;
+use crate::paths::AppPaths;
+
+#[derive(Clone, Serialize)]
+struct StaticInfo {
+    os_name: Option<String>,
+    os_version: Option<String>,
+    kernel_version: Option<String>,
+    cpu_model: Option<String>,
+    cpu_physical_cores: Option<usize>,
+    cpu_logical_cores: Option<usize>,
+    total_ram_bytes: Option<u64>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SystemInfo {
+    #[serde(flatten)]
+    specs: StaticInfo,
+    /// Free space on the volume holding the app data dir.
+    data_disk_free_bytes: Option<u64>,
+}
+
+static STATIC_INFO: OnceLock<StaticInfo> = OnceLock::new();
+
+fn static_info() -> &'static StaticInfo {
+    STATIC_INFO.get_or_init(|| {
+        let sys = System::new_with_specifics(
+            RefreshKind::new()
+                .with_cpu(CpuRefreshKind::new())
+                .with_memory(MemoryRefreshKind::new().with_ram()),
+        );
+        let cpu_model = sys
+            .cpus()
+            .first()
+            .map(|cpu| cpu.brand().trim().to_string())
+            .filter(|brand| !brand.is_empty());
+        StaticInfo {
+            os_name: System::name(),
+            os_version: System::long_os_version().or_else(System::os_version),
+            kernel_version: System::kernel_version(),
+            cpu_model,
+            cpu_physical_cores: sys.physical_core_count(),
+            cpu_logical_cores: Some(sys.cpus().len()).filter(|n| *n > 0),
+            total_ram_bytes: Some(sys.total_memory()).filter(|n| *n > 0),
+        }
+    })
+}
+
+pub fn collect(paths: &AppPaths) -> SystemInfo {
+    SystemInfo {
+        specs: static_info().clone(),
+        data_disk_free_bytes: crate::disk::available_space(&paths.root).ok(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_system_info(app_paths: tauri::State<'_, AppPaths>) -> Result<SystemInfo, AppError> {
+    let paths = app_paths.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || collect(&paths))
+        .await
+        .map_err(|e| AppError::Internal(format!("System info task failed: {}", e)))
+}
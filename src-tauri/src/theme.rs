@@ -0,0 +1,92 @@
+//! Light/dark theme override for the app windows.
+//!
+//! The choice is stored as `window.theme` in `settings.json` (`system`,
+//! `light` or `dark`) and applied in setup before the main window is shown,
+//! so there is no flash of the wrong theme. `theme-changed` is emitted
+//! whenever the effective theme changes, including OS theme switches while
+//! following `system`.
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{Emitter, Manager, Theme};
+
+use crate::error::AppError;
+use crate::settings::SettingsState;
+
+/// Payload of the `theme-changed` event.
+#[derive(Clone, Serialize)]
+struct ThemeChanged {
+    /// The stored preference: `system`, `light` or `dark`.
+    theme: String,
+    /// What is actually shown: `light` or `dark`.
+    effective: &'static str,
+}
+
+/// Map a preference to a window theme; `None` follows the OS.
+pub fn parse_theme(theme: &str) -> Result<Option<Theme>, String> {
+    match theme {
+        "system" => Ok(None),
+        "light" => Ok(Some(Theme::Light)),
+        "dark" => Ok(Some(Theme::Dark)),
+        other => Err(format!("Unknown theme '{}' (expected system, light or dark)", other)),
+    }
+}
+
+fn theme_name(theme: Theme) -> &'static str {
+    match theme {
+        Theme::Dark => "dark",
+        _ => "light",
+    }
+}
+
+fn emit_changed(app: &tauri::AppHandle, theme: &str, effective: Theme) {
+    let _ = app.emit(
+        "theme-changed",
+        ThemeChanged { theme: theme.to_string(), effective: theme_name(effective) },
+    );
+}
+
+/// Apply the stored theme to every open window.
+pub fn apply(app: &tauri::AppHandle) {
+    let stored = app.state::<SettingsState>().get().window.theme;
+    let theme = parse_theme(&stored).unwrap_or_else(|e| {
+        warn!("⚠️ {}; following the system theme", e);
+        None
+    });
+    for window in app.webview_windows().values() {
+        if let Err(e) = window.set_theme(theme) {
+            warn!("⚠️ Failed to set theme on {}: {}", window.label(), e);
+        }
+    }
+}
+
+/// Forward an OS theme switch to the frontend when following `system`.
+pub fn on_os_theme_changed(app: &tauri::AppHandle, theme: Theme) {
+    let stored = app.state::<SettingsState>().get().window.theme;
+    if stored == "system" {
+        info!("🎨 System theme changed to {}", theme_name(theme));
+        emit_changed(app, &stored, theme);
+    }
+}
+
+#[tauri::command]
+pub fn get_theme(state: tauri::State<'_, SettingsState>) -> String {
+    state.get().window.theme
+}
+
+#[tauri::command]
+pub fn set_theme(app_handle: tauri::AppHandle, theme: String) -> Result<String, AppError> {
+    parse_theme(&theme).map_err(AppError::Validation)?;
+    app_handle
+        .state::<SettingsState>()
+        .update(serde_json::json!({ "window": { "theme": theme } }))?;
+    apply(&app_handle);
+
+    let effective = app_handle
+        .get_webview_window("main")
+        .and_then(|w| w.theme().ok())
+        .unwrap_or(Theme::Light);
+    info!("🎨 Theme set to {} ({})", theme, theme_name(effective));
+    emit_changed(&app_handle, &theme, effective);
+    Ok(theme)
+}
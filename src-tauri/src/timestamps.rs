@@ -0,0 +1,58 @@
+//! Timestamp conventions shared by logs, events and generated filenames.
+//!
+//! Anything machine-readable is UTC: filenames use `20240101T120000Z`
+//! (`file_stamp`, parsed back by `parse_file_stamp`) and events store epoch
+//! millis plus the terminal's UTC offset. Only the human-readable log line
+//! prefix follows `log_timezone` (`local` or `utc`), and it always carries an
+//! explicit offset so lines from different terminals can be lined up.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{DateTime, Local, NaiveDateTime, Utc};
+
+/// `strftime` layout of `file_stamp`.
+pub const FILE_STAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Whether log line prefixes are printed in UTC rather than local time.
+static LOG_UTC: AtomicBool = AtomicBool::new(false);
+
+/// Current UTC time for use in a filename, e.g. `20240101T120000Z`.
+pub fn file_stamp() -> String {
+    Utc::now().format(FILE_STAMP_FORMAT).to_string()
+}
+
+/// Reverse of `file_stamp`.
+pub fn parse_file_stamp(stamp: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(stamp, FILE_STAMP_FORMAT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// The terminal's current UTC offset, e.g. `+05:30`.
+pub fn local_offset() -> String {
+    Local::now().format("%:z").to_string()
+}
+
+/// Check a `log_timezone` setting value.
+pub fn parse_log_timezone(value: &str) -> Result<bool, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "local" => Ok(false),
+        "utc" => Ok(true),
+        other => Err(format!("Unknown log timezone '{}'; expected 'local' or 'utc'", other)),
+    }
+}
+
+pub fn set_log_timezone(value: &str) {
+    if let Ok(utc) = parse_log_timezone(value) {
+        LOG_UTC.store(utc, Ordering::Relaxed);
+    }
+}
+
+/// Date and time for the log line prefix, as `date][time`.
+pub fn log_timestamp() -> String {
+    if LOG_UTC.load(Ordering::Relaxed) {
+        Utc::now().format("%Y-%m-%d][%H:%M:%SZ").to_string()
+    } else {
+        Local::now().format("%Y-%m-%d][%H:%M:%S%:z").to_string()
+    }
+}
@@ -0,0 +1,108 @@
+//! System tray icon with Show, Hide, Restart Backend and Quit.
+//!
+//! With `window.minimize_to_tray` on, closing the main window only hides it
+//! and the backend keeps running; clicking the tray icon shows or hides the
+//! window again. The backend is shut down only by the tray's Quit (or any
+//! other real exit). Quit is ignored in kiosk mode, like window close.
+//! Restart Backend goes through `restart_backend`, so it is audited and
+//! emits `backend-restarted` like the command.
+
+use std::thread;
+
+use log::{info, warn};
+use tauri::menu::{MenuBuilder, MenuEvent, MenuItemBuilder};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+use tauri::Manager;
+
+use crate::backend::{self, BackendState};
+use crate::kiosk::KioskState;
+
+const SHOW: &str = "tray_show";
+const HIDE: &str = "tray_hide";
+const RESTART: &str = "tray_restart_backend";
+const QUIT: &str = "tray_quit";
+
+fn set_visible(app: &tauri::AppHandle, visible: bool) {
+    let Some(window) = app.get_webview_window("main") else { return };
+    if visible {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    } else {
+        let _ = window.hide();
+    }
+}
+
+fn toggle(app: &tauri::AppHandle) {
+    let visible = app
+        .get_webview_window("main")
+        .and_then(|w| w.is_visible().ok())
+        .unwrap_or(false);
+    set_visible(app, !visible);
+}
+
+fn restart(app: &tauri::AppHandle) {
+    info!("🔁 Backend restart requested from the tray");
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = backend::restart_backend(app).await {
+            warn!("⚠️ Tray backend restart failed: {}", e.message());
+        }
+    });
+}
+
+/// Shut the backend down gracefully, then exit.
+fn quit(app: &tauri::AppHandle) {
+    if app.state::<KioskState>().is_active() {
+        info!("🔐 Tray quit blocked: kiosk mode is active");
+        return;
+    }
+    info!("🚪 Quit from the tray - shutting down");
+    let app = app.clone();
+    thread::spawn(move || {
+        if let Some(result) = backend::shutdown_backend(&app.state::<BackendState>()) {
+            backend::log_kill_outcome(&result);
+        }
+        app.exit(0);
+    });
+}
+
+fn on_menu_event(app: &tauri::AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        SHOW => set_visible(app, true),
+        HIDE => set_visible(app, false),
+        RESTART => restart(app),
+        QUIT => quit(app),
+        _ => {}
+    }
+}
+
+fn on_icon_event(tray: &TrayIcon, event: TrayIconEvent) {
+    if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+        toggle(tray.app_handle());
+    }
+}
+
+pub fn install(app: &tauri::AppHandle) -> tauri::Result<()> {
+    let menu = MenuBuilder::new(app)
+        .item(&MenuItemBuilder::with_id(SHOW, "Show").build(app)?)
+        .item(&MenuItemBuilder::with_id(HIDE, "Hide").build(app)?)
+        .separator()
+        .item(&MenuItemBuilder::with_id(RESTART, "Restart Backend").build(app)?)
+        .separator()
+        .item(&MenuItemBuilder::with_id(QUIT, "Quit").build(app)?)
+        .build()?;
+
+    let mut builder = TrayIconBuilder::with_id("main")
+        .tooltip(app.package_info().name.clone())
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(on_menu_event)
+        .on_tray_icon_event(on_icon_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    info!("🖥️ Tray icon installed");
+    Ok(())
+}
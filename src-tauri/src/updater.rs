@@ -0,0 +1,1203 @@
+//! Updater commands built on `tauri_plugin_updater`.
+//!
+//! Downloading and applying an update are separate steps: `install_update`
+//! downloads and verifies the package, keeps it in memory and emits
+//! `update-ready`; the UI then shows a "Restart to update" banner and calls
+//! `apply_update_and_restart` when the cashier is ready. Alongside:
+//!
+//! - `update://available { version, current_version }` when a check finds
+//!   an update; `check_for_updates` then starts the download in the
+//!   background;
+//! - `update://progress { version, downloaded, content_length }` while
+//!   downloading, at most every `events.progress_interval_ms`;
+//! - `update://finished { version }` once the package is staged, and again
+//!   once it is installed;
+//! - `update://error { version, error }` when a check, download, staging or
+//!   install fails (`version` is `None` for a failed check). A cancelled
+//!   download, or one refused because another is running, isn't an error.
+//!
+//! The package is streamed to `update-<version>.part` in the app data dir
+//! rather than through `Update::download`, so a dropped connection resumes
+//! with an HTTP `Range` request instead of starting over. The file is only
+//! renamed to `update-<version>.pkg` once its size matches and its minisign
+//! signature verifies against the updater `pubkey`.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use log::{error, info, warn};
+use reqwest::header::{ACCEPT, CONTENT_RANGE, RANGE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::backend;
+use crate::ca_certs;
+use crate::disk;
+use crate::event_bus::{self, LifecycleEvent};
+use crate::error::AppError;
+use crate::paths::AppPaths;
+use crate::proxy;
+use crate::settings::SettingsState;
+
+/// Shared updater status, managed as Tauri state.
+#[derive(Default)]
+pub struct UpdateState {
+    /// Set while an update is being downloaded/installed.
+    pub downloading: AtomicBool,
+    /// Downloaded package waiting for `apply_update_and_restart`.
+    pending: Mutex<Option<PendingUpdate>>,
+    /// Why updates are currently unavailable (offline, updater not
+    /// configured); `None` when the update server was last reachable.
+    unavailable: Mutex<Option<String>>,
+    /// Set while the background availability re-check loop is running.
+    rechecking: AtomicBool,
+    /// Release notes of the update found by the last successful check.
+    notes: Mutex<Option<UpdateNotes>>,
+    /// Set by `cancel_update`; the download loop stops when it sees it.
+    cancel: AtomicBool,
+    /// Set once the installer has been started; from then on the update
+    /// can't be cancelled.
+    installing: AtomicBool,
+}
+
+struct PendingUpdate {
+    update: Update,
+    bytes: Vec<u8>,
+}
+
+/// Clears `UpdateState::downloading` when the download scope ends, on any path.
+struct DownloadGuard<'a>(&'a AtomicBool);
+
+impl Drop for DownloadGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct UpdateInfo {
+    available: bool,
+    version: String,
+    current_version: String,
+    notes: String,
+    date: String,
+}
+
+/// How many times an update check/download is attempted before giving up.
+const UPDATER_MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry; doubled on each subsequent attempt.
+const UPDATER_RETRY_BASE_DELAY: Duration = Duration::from_millis(1500);
+
+/// Network hiccups are worth retrying; signature, manifest and install
+/// errors will fail the same way every time, so they are returned at once.
+fn is_retryable_updater_error(err: &tauri_plugin_updater::Error) -> bool {
+    match err {
+        tauri_plugin_updater::Error::Reqwest(e) => {
+            e.is_timeout() || e.is_connect() || e.is_request() || e.is_body()
+        }
+        tauri_plugin_updater::Error::Network(_) => true,
+        _ => false,
+    }
+}
+
+/// A failed updater operation. `offline` means the update server couldn't
+/// be reached, which is expected on offline kiosks and not an error.
+struct UpdaterFailure {
+    message: String,
+    offline: bool,
+}
+
+impl From<UpdaterFailure> for String {
+    fn from(failure: UpdaterFailure) -> Self {
+        failure.message
+    }
+}
+
+/// Run an updater operation, retrying transient network failures with a
+/// short exponential backoff. The final error mentions how many attempts
+/// were made.
+async fn with_updater_retry<T, F, Fut>(what: &str, mut op: F) -> Result<T, UpdaterFailure>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, tauri_plugin_updater::Error>>,
+{
+    let mut attempt = 1;
+    loop {
+        info!("🔄 {} (attempt {}/{})", what, attempt, UPDATER_MAX_ATTEMPTS);
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < UPDATER_MAX_ATTEMPTS && is_retryable_updater_error(&e) => {
+                let delay = UPDATER_RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+                warn!("⚠️ {} failed on attempt {}: {} — retrying in {:?}", what, attempt, e, delay);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                let offline = is_retryable_updater_error(&e);
+                if offline {
+                    warn!("📴 {} failed after {} attempt(s), update server unreachable: {}", what, attempt, e);
+                } else {
+                    error!("❌ {} failed after {} attempt(s): {}", what, attempt, e);
+                }
+                return Err(UpdaterFailure {
+                    message: format!("{} failed after {} attempt(s): {}", what, attempt, e),
+                    offline,
+                });
+            }
+        }
+    }
+}
+
+// ============================================================================
+// OFFLINE / DEGRADED MODE
+// ============================================================================
+
+/// How often availability is re-checked while updates are unavailable.
+const AVAILABILITY_RECHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Payload of the `updates-unavailable` event; sent with
+/// `unavailable: false` once the update server is reachable again.
+#[derive(Clone, Serialize)]
+pub struct UpdatesUnavailable {
+    unavailable: bool,
+    reason: Option<String>,
+}
+
+fn mark_unavailable(app: &tauri::AppHandle, reason: String) {
+    let state = app.state::<UpdateState>();
+    let was_available = state.unavailable.lock().unwrap().replace(reason.clone()).is_none();
+    if was_available {
+        info!("📴 Updates disabled: {}", reason);
+        let _ = app.emit("updates-unavailable", UpdatesUnavailable { unavailable: true, reason: Some(reason) });
+    }
+    if !state.rechecking.swap(true, Ordering::SeqCst) {
+        tauri::async_runtime::spawn(recheck_availability(app.clone()));
+    }
+}
+
+fn mark_available(app: &tauri::AppHandle) {
+    if app.state::<UpdateState>().unavailable.lock().unwrap().take().is_some() {
+        info!("📶 Update server reachable again; updates re-enabled");
+        let _ = app.emit("updates-unavailable", UpdatesUnavailable { unavailable: false, reason: None });
+    }
+}
+
+/// The updater, routed through the configured proxy if there is one and
+/// trusting any extra root certificates.
+fn proxied_updater(app: &tauri::AppHandle) -> Result<tauri_plugin_updater::Updater, String> {
+    configure_updater(app, app.updater_builder())
+}
+
+fn configure_updater(
+    app: &tauri::AppHandle,
+    mut builder: tauri_plugin_updater::UpdaterBuilder,
+) -> Result<tauri_plugin_updater::Updater, String> {
+    let roots = ca_certs::configured(app)?;
+    if !roots.is_empty() {
+        builder = builder.configure_client(move |client| {
+            roots
+                .iter()
+                .filter_map(|der| updater_reqwest::Certificate::from_der(der).ok())
+                .fold(client, |client, cert| client.add_root_certificate(cert))
+        });
+    }
+    let builder = match proxy::configured(app) {
+        Some(url) => builder.proxy(url),
+        None => builder,
+    };
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// Run an update check, switching between normal and degraded mode based on
+/// whether the server answered. `Ok(None)` covers both "no update" and
+/// "offline"; only unexpected failures are errors.
+async fn checked_update(app: &tauri::AppHandle) -> Result<Option<Update>, String> {
+    // A missing endpoint or pubkey is a configuration state, not a failure.
+    let updater = match proxied_updater(app) {
+        Ok(updater) => updater,
+        Err(e) => {
+            mark_unavailable(app, format!("Updater unavailable: {}", e));
+            return Ok(None);
+        }
+    };
+    match with_updater_retry("Update check", || updater.check()).await {
+        Ok(update) => {
+            mark_available(app);
+            modify_update_state(app, |file| file.last_check = Some(unix_millis()));
+            cache_notes(app, update.as_ref());
+            Ok(update)
+        }
+        Err(failure) if failure.offline => {
+            mark_unavailable(app, failure.message);
+            Ok(None)
+        }
+        Err(failure) => {
+            emit_error(app, None, &failure.message);
+            Err(failure.message)
+        }
+    }
+}
+
+/// Poll the update server quietly until it answers again.
+async fn recheck_availability(app: tauri::AppHandle) {
+    loop {
+        tokio::time::sleep(AVAILABILITY_RECHECK_INTERVAL).await;
+        let reachable = match proxied_updater(&app) {
+            Ok(updater) => updater.check().await.is_ok(),
+            Err(_) => false,
+        };
+        if reachable {
+            mark_available(&app);
+            break;
+        }
+    }
+    app.state::<UpdateState>().rechecking.store(false, Ordering::SeqCst);
+}
+
+/// Probe the update server once at startup so offline terminals hide the
+/// update UI from the start.
+pub fn probe_availability(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = checked_update(&app).await;
+    });
+}
+
+/// Current degraded-mode state, for a frontend that missed the event.
+#[tauri::command]
+pub fn updates_status(update_state: tauri::State<'_, UpdateState>) -> UpdatesUnavailable {
+    let reason = update_state.unavailable.lock().unwrap().clone();
+    UpdatesUnavailable { unavailable: reason.is_some(), reason }
+}
+
+/// Check for an update and, if there is one, emit `update://available` and
+/// start downloading it. Returns the available version.
+#[tauri::command]
+pub async fn check_for_updates(app_handle: tauri::AppHandle) -> Result<Option<String>, AppError> {
+    info!("Checking for updates...");
+    let Some(update) = checked_update(&app_handle).await.map_err(AppError::Updater)? else {
+        info!("No update available.");
+        return Ok(None);
+    };
+    info!("Update available: {:?}", update.version);
+    emit_available(&app_handle, &update);
+
+    let version = update.version.clone();
+    let update_state = app_handle.state::<UpdateState>();
+    let staged = update_state.pending.lock().unwrap().as_ref().is_some_and(|p| p.update.version == version);
+    if !staged && !update_state.downloading.load(Ordering::SeqCst) {
+        let app = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = stage_update(&app, &app.state::<UpdateState>(), update).await {
+                warn!("⚠️ Update download failed: {}", e);
+            }
+        });
+    }
+    Ok(Some(version))
+}
+
+// ============================================================================
+// RELEASE NOTES
+// ============================================================================
+
+/// Release notes of the pending update, as returned by `get_update_notes`.
+#[derive(Clone, Serialize)]
+pub struct UpdateNotes {
+    pub version: String,
+    pub date: Option<String>,
+    /// Plain text with markdown markup stripped; `None` when the manifest
+    /// has no notes.
+    pub notes: Option<String>,
+}
+
+/// Remember the notes of `update`, or forget them when there's no update.
+/// An offline check never gets here, so cached notes survive it.
+fn cache_notes(app: &tauri::AppHandle, update: Option<&Update>) {
+    let notes = update.map(|update| UpdateNotes {
+        version: update.version.clone(),
+        date: update.date.map(|d| d.to_string()),
+        notes: update
+            .body
+            .as_deref()
+            .map(notes_to_plain_text)
+            .filter(|text| !text.is_empty()),
+    });
+    *app.state::<UpdateState>().notes.lock().unwrap() = notes;
+}
+
+/// Flatten markdown release notes to plain text: headings, emphasis, code
+/// marks and HTML tags are dropped, list bullets become `•` and links become
+/// `text (url)`.
+fn notes_to_plain_text(markdown: &str) -> String {
+    let lines: Vec<String> = markdown
+        .lines()
+        .map(|line| {
+            let line = line.trim_end();
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let mut body = line.trim_start().trim_start_matches('#').trim_start();
+            let bullet = ["- ", "* ", "+ "].iter().any(|b| body.starts_with(b));
+            if bullet {
+                body = &body[2..];
+            }
+            let text = strip_inline_markup(body);
+            if bullet {
+                format!("{}• {}", indent, text)
+            } else {
+                format!("{}{}", indent, text)
+            }
+        })
+        .collect();
+    lines.join("\n").trim().to_string()
+}
+
+fn strip_inline_markup(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '*' | '_' | '`' => rest = &rest[1..],
+            '<' => match rest.find('>') {
+                Some(end) => rest = &rest[end + 1..],
+                None => {
+                    out.push('<');
+                    rest = &rest[1..];
+                }
+            },
+            '[' => {
+                let link = rest.find("](").and_then(|mid| {
+                    rest[mid..].find(')').map(|end| (mid, mid + end))
+                });
+                match link {
+                    Some((mid, end)) => {
+                        out.push_str(&strip_inline_markup(&rest[1..mid]));
+                        out.push_str(&format!(" ({})", &rest[mid + 2..end]));
+                        rest = &rest[end + 1..];
+                    }
+                    None => {
+                        out.push('[');
+                        rest = &rest[1..];
+                    }
+                }
+            }
+            _ => {
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    out
+}
+
+/// Notes of the update found by the last check, if any.
+#[tauri::command]
+pub fn get_update_notes(update_state: tauri::State<'_, UpdateState>) -> Option<UpdateNotes> {
+    update_state.notes.lock().unwrap().clone()
+}
+
+async fn install(
+    app_handle: tauri::AppHandle,
+    update_state: tauri::State<'_, UpdateState>,
+) -> Result<String, AppError> {
+    info!("Installing update...");
+    let update = match checked_update(&app_handle).await.map_err(AppError::Updater)? {
+        Some(update) => update,
+        None => {
+            info!("No update available to install.");
+            return Ok("No update available to install.".to_string());
+        }
+    };
+    let version = stage_update(&app_handle, &update_state, update).await?;
+    Ok(format!("Update {} downloaded. Restart the app to apply it.", version))
+}
+
+/// Download the latest update and keep it ready to apply. Emits
+/// `update-ready { version }` once the package is downloaded and verified.
+#[tauri::command]
+pub async fn install_update(
+    app_handle: tauri::AppHandle,
+    update_state: tauri::State<'_, UpdateState>,
+) -> Result<String, AppError> {
+    let result = install(app_handle.clone(), update_state).await;
+    crate::audit_log::privileged(&app_handle, "install_update", result)
+}
+
+#[derive(Clone, Serialize)]
+struct UpdateProgress {
+    version: String,
+    /// Bytes of the package on disk so far, including a resumed part.
+    downloaded: u64,
+    content_length: Option<u64>,
+}
+
+#[derive(Clone, Serialize)]
+struct UpdateError {
+    version: Option<String>,
+    error: String,
+}
+
+#[derive(Clone, Serialize)]
+struct UpdateFinished {
+    version: String,
+}
+
+fn emit_error(app: &tauri::AppHandle, version: Option<&str>, error: &str) {
+    let payload = UpdateError { version: version.map(str::to_string), error: error.to_string() };
+    let _ = app.emit("update://error", payload);
+}
+
+fn emit_finished(app: &tauri::AppHandle, version: &str) {
+    let _ = app.emit("update://finished", UpdateFinished { version: version.to_string() });
+}
+
+/// Download and verify `update`, keep it as the pending update and emit
+/// `update-ready` and `update://finished`. Returns the version.
+async fn stage_update(app: &tauri::AppHandle, update_state: &UpdateState, update: Update) -> Result<String, AppError> {
+    let fail = |e: AppError| {
+        emit_error(app, Some(&update.version), e.message());
+        e
+    };
+    let staging_dir = app.state::<AppPaths>().root.clone();
+    disk::ensure_space(app, &staging_dir, disk::MIN_UPDATE_FREE_SPACE, "update download")
+        .map_err(|e| fail(AppError::Io(e)))?;
+
+    if update_state.downloading.swap(true, Ordering::SeqCst) {
+        return Err(AppError::Conflict("An update download is already in progress.".to_string()));
+    }
+    let _downloading = DownloadGuard(&update_state.downloading);
+
+    update_state.cancel.store(false, Ordering::SeqCst);
+
+    info!("Update found, downloading...");
+    let pubkey = updater_pubkey(app).map_err(|e| fail(AppError::Updater(e)))?;
+    let proxy = proxy::reqwest_proxy(app);
+    let roots = ca_certs::reqwest_certs(app).map_err(|e| fail(AppError::Updater(e)))?;
+    let progress = |downloaded: u64, content_length: Option<u64>| {
+        let version = update.version.clone();
+        let payload = UpdateProgress { version, downloaded, content_length };
+        crate::event_throttle::emit_coalesced(app, "update://progress", payload);
+    };
+    let cancel = &update_state.cancel;
+    let bytes = match download_resumable(&update, &staging_dir, &pubkey, proxy, roots, cancel, &progress).await {
+        Ok(bytes) => bytes,
+        Err(Download::Cancelled) => {
+            info!("🛑 Update {} download cancelled; partial file removed", update.version);
+            event_bus::publish(app, LifecycleEvent::UpdateCancelled { version: update.version.clone() });
+            return Err(AppError::Updater("Update download cancelled.".to_string())
+                .with_details(serde_json::json!({ "cancelled": true })));
+        }
+        Err(Download::Failed(e)) => return Err(fail(AppError::Updater(e))),
+    };
+
+    let version = update.version.clone();
+    *update_state.pending.lock().unwrap() = Some(PendingUpdate { update, bytes });
+
+    info!("✅ Update {} downloaded and ready. Restart required to apply.", version);
+    event_bus::publish(app, LifecycleEvent::UpdateReady { version: version.clone() });
+    emit_finished(app, &version);
+    Ok(version)
+}
+
+// ============================================================================
+// TEST UPDATES
+// ============================================================================
+
+async fn check_from(
+    app_handle: &tauri::AppHandle,
+    update_state: &UpdateState,
+    url: String,
+    admin_pin: Option<String>,
+) -> Result<String, AppError> {
+    if !cfg!(debug_assertions) {
+        match admin_pin.as_deref().and_then(|pin| crate::pin::check_pin(app_handle, pin)) {
+            Some(true) => {}
+            _ => {
+                warn!("⚠️ Test update from {} rejected: admin PIN required", url);
+                return Err(AppError::PermissionDenied(
+                    "Test updates need the admin PIN in release builds.".to_string(),
+                ));
+            }
+        }
+    }
+    let endpoint = tauri::Url::parse(url.trim())
+        .ok()
+        .filter(|u| matches!(u.scheme(), "http" | "https"))
+        .ok_or_else(|| AppError::Validation(format!("Invalid manifest URL '{}'", url)))?;
+
+    warn!("=================================================");
+    warn!("🧪 TEST UPDATE: checking {} instead of the configured endpoints (one-shot, not saved)", endpoint);
+    warn!("=================================================");
+    crate::audit_log::record(app_handle, "test_update_check", serde_json::json!({ "url": endpoint.as_str() }));
+
+    let builder = app_handle.updater_builder().endpoints(vec![endpoint.clone()])?;
+    let updater = configure_updater(app_handle, builder).map_err(AppError::Updater)?;
+    let checked = updater.check().await.map_err(|e| {
+        let e = AppError::from(e);
+        emit_error(app_handle, None, e.message());
+        e
+    })?;
+    let Some(update) = checked else {
+        info!("🧪 Test manifest {} offers no newer version", endpoint);
+        return Ok("The test manifest offers no newer version.".to_string());
+    };
+
+    info!("🧪 Test manifest offers {}", update.version);
+    let version = stage_update(app_handle, update_state, update).await?;
+    warn!("🧪 Test update {} downloaded and verified; restart to install it", version);
+    Ok(format!("Test update {} downloaded. Restart the app to apply it.", version))
+}
+
+/// Check `url` instead of the configured endpoints, once, and download and
+/// verify whatever it offers as the pending update; QA then installs it
+/// with `apply_update_and_restart`. The override is never saved. Allowed in
+/// debug builds, or in release builds with the admin PIN.
+#[tauri::command]
+pub async fn check_for_update_from(
+    app_handle: tauri::AppHandle,
+    update_state: tauri::State<'_, UpdateState>,
+    url: String,
+    admin_pin: Option<String>,
+) -> Result<String, AppError> {
+    let result = check_from(&app_handle, &update_state, url, admin_pin).await;
+    crate::audit_log::privileged(&app_handle, "test_update_check", result)
+}
+
+// ============================================================================
+// SCHEDULED CHECKS
+// ============================================================================
+
+/// While scheduled checks are off, how often to look whether they were
+/// turned back on.
+const SCHEDULE_IDLE_POLL: Duration = Duration::from_secs(15 * 60);
+/// Each scheduled check lands up to this fraction of the interval early or
+/// late, so a fleet started together doesn't hit the update server at once.
+const CHECK_JITTER: f64 = 0.1;
+
+/// Payload of the `update://available` event.
+#[derive(Clone, Serialize)]
+struct UpdateAvailable {
+    version: String,
+    current_version: String,
+}
+
+fn emit_available(app: &tauri::AppHandle, update: &Update) {
+    let _ = app.emit(
+        "update://available",
+        UpdateAvailable {
+            version: update.version.clone(),
+            current_version: app.package_info().version.to_string(),
+        },
+    );
+}
+
+/// Uniform value in [-1, 1].
+fn random_unit() -> f64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+}
+
+/// Next check time (unix millis) `interval` from `now`, with jitter.
+fn next_check_after(now: u64, interval: Duration) -> u64 {
+    let jitter = interval.as_millis() as f64 * CHECK_JITTER * random_unit();
+    (now as f64 + interval.as_millis() as f64 + jitter).max(now as f64) as u64
+}
+
+/// Check for updates every `update_check_hours` in the background. The
+/// next check time is persisted, so a restart keeps its slot instead of
+/// checking right away. Checks are skipped while offline or while updates
+/// are unavailable; a found update emits `update://available` and is only
+/// downloaded when `auto_update` is on.
+pub fn start_update_schedule(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let settings = app.state::<SettingsState>().get();
+            if settings.update_check_hours == 0 {
+                modify_update_state(&app, |file| file.next_check = None);
+                tokio::time::sleep(SCHEDULE_IDLE_POLL).await;
+                continue;
+            }
+            let interval = Duration::from_secs(settings.update_check_hours * 60 * 60);
+
+            // Keep a persisted slot unless it's past or no longer fits the interval.
+            let now = unix_millis();
+            let latest = now + (interval.as_millis() as f64 * (1.0 + CHECK_JITTER)) as u64;
+            let next = match read_update_state(&app).next_check {
+                Some(at) if at > now && at <= latest => at,
+                _ => {
+                    let at = next_check_after(now, interval);
+                    modify_update_state(&app, |file| file.next_check = Some(at));
+                    at
+                }
+            };
+            info!("🗓️ Next scheduled update check in {} min", (next - now) / 60_000);
+            tokio::time::sleep(Duration::from_millis(next - now)).await;
+            modify_update_state(&app, |file| file.next_check = None);
+
+            scheduled_check(&app).await;
+        }
+    });
+}
+
+async fn scheduled_check(app: &tauri::AppHandle) {
+    let update_state = app.state::<UpdateState>();
+    if update_state.unavailable.lock().unwrap().is_some() {
+        info!("🗓️ Scheduled update check skipped: updates unavailable");
+        return;
+    }
+    let net_app = app.clone();
+    let online = tauri::async_runtime::spawn_blocking(move || crate::network::check(&net_app, false).online)
+        .await
+        .unwrap_or(false);
+    if !online {
+        info!("🗓️ Scheduled update check skipped: offline");
+        return;
+    }
+    if update_state.downloading.load(Ordering::SeqCst) || update_state.pending.lock().unwrap().is_some() {
+        return;
+    }
+
+    let update = match checked_update(app).await {
+        Ok(Some(update)) => update,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("⚠️ Scheduled update check failed: {}", e);
+            return;
+        }
+    };
+    info!("🗓️ Scheduled check found update {}", update.version);
+    emit_available(app, &update);
+    if app.state::<SettingsState>().get().auto_update {
+        if let Err(e) = stage_update(app, &update_state, update).await {
+            warn!("⚠️ Automatic update download failed: {}", e);
+        }
+    }
+}
+
+// ============================================================================
+// RESUMABLE DOWNLOAD
+// ============================================================================
+
+fn package_paths(dir: &Path, version: &str) -> (PathBuf, PathBuf) {
+    (
+        dir.join(format!("update-{}.part", version)),
+        dir.join(format!("update-{}.pkg", version)),
+    )
+}
+
+// ============================================================================
+// DOWNLOAD CACHE
+// ============================================================================
+
+#[derive(Clone, Serialize)]
+pub struct CachedFile {
+    pub name: String,
+    pub size: u64,
+    /// `true` for an interrupted `.part` download.
+    pub partial: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct UpdateCacheInfo {
+    pub dir: String,
+    pub files: Vec<CachedFile>,
+    pub total_bytes: u64,
+}
+
+/// Partial and staged packages in the download dir. Only names written by
+/// `package_paths` match, so `update-state.json` is never included.
+pub fn cache_files(app: &tauri::AppHandle) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(&app.state::<AppPaths>().root) else { return Vec::new() };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+            name.starts_with("update-") && (name.ends_with(".part") || name.ends_with(".pkg"))
+        })
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Size and list of the cached update downloads.
+#[tauri::command]
+pub fn update_cache_info(app_handle: tauri::AppHandle) -> UpdateCacheInfo {
+    let files: Vec<CachedFile> = cache_files(&app_handle)
+        .iter()
+        .map(|path| CachedFile {
+            name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            size: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+            partial: path.extension().is_some_and(|ext| ext == "part"),
+        })
+        .collect();
+    UpdateCacheInfo {
+        dir: app_handle.state::<AppPaths>().root.display().to_string(),
+        total_bytes: files.iter().map(|f| f.size).sum(),
+        files,
+    }
+}
+
+fn clear_downloads(app_handle: tauri::AppHandle) -> Result<u64, AppError> {
+    let update_state = app_handle.state::<UpdateState>();
+    if update_state.downloading.load(Ordering::SeqCst) || update_state.installing.load(Ordering::SeqCst) {
+        return Err(AppError::Conflict(
+            "Cannot clear the update cache while an update is downloading.".to_string(),
+        ));
+    }
+
+    let mut freed = 0;
+    for path in cache_files(&app_handle) {
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        match fs::remove_file(&path) {
+            Ok(()) => freed += size,
+            Err(e) => warn!("⚠️ Could not remove cached update {:?}: {}", path, e),
+        }
+    }
+    info!("🧹 Update cache cleared: {} bytes freed", freed);
+    Ok(freed)
+}
+
+/// Delete the cached update downloads, e.g. when a corrupt package keeps
+/// an update from installing. Returns the bytes freed.
+#[tauri::command]
+pub fn clear_update_cache(app_handle: tauri::AppHandle) -> Result<u64, AppError> {
+    let result = clear_downloads(app_handle.clone());
+    crate::audit_log::privileged(&app_handle, "clear_update_cache", result)
+}
+
+/// The updater `pubkey` from `tauri.conf.json`.
+fn updater_pubkey(app: &tauri::AppHandle) -> Result<String, String> {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|cfg| cfg.get("pubkey"))
+        .and_then(|key| key.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| "Updater public key is not configured.".to_string())
+}
+
+/// Check `data` against the update's minisign signature, the same way
+/// `Update::download` does. Both key and signature are base64-wrapped
+/// minisign text.
+fn verify_package(data: &[u8], signature: &str, pubkey: &str) -> Result<(), String> {
+    let decode = |value: &str, what: &str| {
+        STANDARD
+            .decode(value)
+            .ok()
+            .and_then(|raw| String::from_utf8(raw).ok())
+            .ok_or_else(|| format!("Invalid update {} encoding.", what))
+    };
+    let key = minisign_verify::PublicKey::decode(&decode(pubkey, "public key")?)
+        .map_err(|e| format!("Invalid updater public key: {}", e))?;
+    let sig = minisign_verify::Signature::decode(&decode(signature, "signature")?)
+        .map_err(|e| format!("Invalid update signature: {}", e))?;
+    key.verify(data, &sig, true)
+        .map_err(|e| format!("Update signature verification failed: {}", e))
+}
+
+/// Total size from a `Content-Range: bytes start-end/total` header.
+fn content_range_total(resp: &reqwest::Response) -> Option<u64> {
+    resp.headers()
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Result of one download attempt.
+enum Attempt {
+    Complete,
+    /// Transient failure; the next attempt resumes from the `.part` file.
+    Interrupted(String),
+    /// `cancel_update` was called.
+    Cancelled,
+}
+
+/// Why `download_resumable` didn't return a package.
+enum Download {
+    Cancelled,
+    Failed(String),
+}
+
+impl From<String> for Download {
+    fn from(message: String) -> Self {
+        Download::Failed(message)
+    }
+}
+
+/// Download progress callback: bytes so far and the expected total.
+type Progress<'a> = dyn Fn(u64, Option<u64>) + Send + Sync + 'a;
+
+/// Append the rest of the package to `part`, resuming from its current size.
+async fn download_attempt(
+    client: &reqwest::Client,
+    update: &Update,
+    part: &Path,
+    cancel: &AtomicBool,
+    progress: &Progress<'_>,
+) -> Result<Attempt, String> {
+    let offset = fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+    let mut request = client
+        .get(update.download_url.clone())
+        .header(ACCEPT, "application/octet-stream");
+    if offset > 0 {
+        info!("⏯️ Resuming update download at {} bytes", offset);
+        request = request.header(RANGE, format!("bytes={}-", offset));
+    }
+
+    let mut resp = match request.send().await {
+        Ok(resp) => resp,
+        Err(e) => return Ok(Attempt::Interrupted(e.to_string())),
+    };
+
+    let (append, total) = match resp.status() {
+        StatusCode::PARTIAL_CONTENT => (true, content_range_total(&resp)),
+        StatusCode::OK => {
+            if offset > 0 {
+                warn!("⚠️ Server ignored the Range request; restarting download");
+            }
+            (false, resp.content_length())
+        }
+        StatusCode::RANGE_NOT_SATISFIABLE => {
+            // The partial file no longer matches what the server has.
+            warn!("⚠️ Stale partial download {:?}; discarding", part);
+            let _ = fs::remove_file(part);
+            return Ok(Attempt::Interrupted("partial download was stale".to_string()));
+        }
+        status if status.is_server_error() => {
+            return Ok(Attempt::Interrupted(format!("server returned {}", status)));
+        }
+        status => return Err(format!("Update download failed: server returned {}", status)),
+    };
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(part)
+        .map_err(|e| format!("Failed to open {:?}: {}", part, e))?;
+    let mut downloaded = if append { offset } else { 0 };
+
+    loop {
+        if cancel.load(Ordering::SeqCst) {
+            return Ok(Attempt::Cancelled);
+        }
+        match resp.chunk().await {
+            Ok(Some(chunk)) => {
+                file.write_all(&chunk)
+                    .map_err(|e| format!("Failed to write {:?}: {}", part, e))?;
+                downloaded += chunk.len() as u64;
+                progress(downloaded, total);
+            }
+            Ok(None) => break,
+            Err(e) => return Ok(Attempt::Interrupted(e.to_string())),
+        }
+    }
+    file.sync_all().map_err(|e| format!("Failed to flush {:?}: {}", part, e))?;
+
+    match total {
+        Some(total) if downloaded < total => Ok(Attempt::Interrupted(format!(
+            "connection closed at {} of {} bytes",
+            downloaded, total
+        ))),
+        Some(total) if downloaded > total => {
+            let _ = fs::remove_file(part);
+            Err(format!("Update download is larger than expected ({} > {} bytes)", downloaded, total))
+        }
+        _ => Ok(Attempt::Complete),
+    }
+}
+
+/// Download `update` into `dir`, resuming across attempts, and return the
+/// verified package bytes. A package already verified by an earlier run is
+/// reused without downloading. Setting `cancel` stops the download and
+/// removes the `.part` file. `progress` gets the bytes downloaded and the
+/// package size, when the server sends it, as chunks arrive.
+async fn download_resumable(
+    update: &Update,
+    dir: &Path,
+    pubkey: &str,
+    proxy: Option<reqwest::Proxy>,
+    roots: Vec<reqwest::Certificate>,
+    cancel: &AtomicBool,
+    progress: &Progress<'_>,
+) -> Result<Vec<u8>, Download> {
+    let (part, pkg) = package_paths(dir, &update.version);
+
+    if let Ok(bytes) = fs::read(&pkg) {
+        if verify_package(&bytes, &update.signature, pubkey).is_ok() {
+            info!("✅ Reusing previously downloaded update {:?}", pkg);
+            return Ok(bytes);
+        }
+        warn!("⚠️ Discarding unverifiable staged update {:?}", pkg);
+        let _ = fs::remove_file(&pkg);
+    }
+
+    let builder = reqwest::Client::builder().connect_timeout(Duration::from_secs(15));
+    let builder = match proxy {
+        Some(proxy) => builder.proxy(proxy),
+        None => builder.no_proxy(),
+    };
+    let builder = roots.into_iter().fold(builder, |b, cert| b.add_root_certificate(cert));
+    let client = builder
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let mut attempt = 1;
+    loop {
+        info!("🔄 Update download (attempt {}/{})", attempt, UPDATER_MAX_ATTEMPTS);
+        let outcome = match download_attempt(&client, update, &part, cancel, progress).await? {
+            Attempt::Interrupted(_) if cancel.load(Ordering::SeqCst) => Attempt::Cancelled,
+            outcome => outcome,
+        };
+        match outcome {
+            Attempt::Complete => break,
+            Attempt::Cancelled => {
+                let _ = fs::remove_file(&part);
+                return Err(Download::Cancelled);
+            }
+            Attempt::Interrupted(reason) if attempt < UPDATER_MAX_ATTEMPTS => {
+                let delay = UPDATER_RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+                warn!("⚠️ Update download interrupted on attempt {}: {} — resuming in {:?}", attempt, reason, delay);
+                tokio::time::sleep(delay).await;
+                if cancel.load(Ordering::SeqCst) {
+                    let _ = fs::remove_file(&part);
+                    return Err(Download::Cancelled);
+                }
+                attempt += 1;
+            }
+            Attempt::Interrupted(reason) => {
+                error!("❌ Update download failed after {} attempt(s): {}", attempt, reason);
+                return Err(format!("Update download failed after {} attempt(s): {}", attempt, reason).into());
+            }
+        }
+    }
+    info!("Download finished");
+
+    let bytes = fs::read(&part).map_err(|e| format!("Failed to read {:?}: {}", part, e))?;
+    if let Err(e) = verify_package(&bytes, &update.signature, pubkey) {
+        // A corrupt part file would fail again on resume; start clean next time.
+        let _ = fs::remove_file(&part);
+        error!("❌ {}", e);
+        return Err(e.into());
+    }
+    fs::rename(&part, &pkg).map_err(|e| format!("Failed to finalize update package: {}", e))?;
+    info!("✅ Update package verified and staged at {:?}", pkg);
+    Ok(bytes)
+}
+
+// ============================================================================
+// UPDATE HISTORY
+// ============================================================================
+
+pub const UPDATE_STATE_FILE: &str = "update-state.json";
+
+/// Persisted in `update-state.json`. Timestamps are unix millis.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct UpdateStateFile {
+    last_check: Option<u64>,
+    last_applied: Option<u64>,
+    last_applied_version: Option<String>,
+    /// Version handed to the installer; confirmed as applied when the app
+    /// next starts on that version.
+    installing_version: Option<String>,
+    /// When the background schedule will check next.
+    next_check: Option<u64>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct UpdateHistory {
+    pub last_check: Option<u64>,
+    pub last_applied: Option<u64>,
+    pub last_applied_version: Option<String>,
+    pub next_check: Option<u64>,
+    pub current_version: String,
+}
+
+fn unix_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn update_state_path(app: &tauri::AppHandle) -> PathBuf {
+    app.state::<AppPaths>().root.join(UPDATE_STATE_FILE)
+}
+
+fn read_update_state(app: &tauri::AppHandle) -> UpdateStateFile {
+    crate::paths::read_config_or_default(&update_state_path(app))
+}
+
+fn modify_update_state(app: &tauri::AppHandle, change: impl FnOnce(&mut UpdateStateFile)) {
+    let mut file = read_update_state(app);
+    change(&mut file);
+    let path = update_state_path(app);
+    let result = serde_json::to_vec_pretty(&file)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| crate::paths::write_atomic(&path, &bytes).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("⚠️ Failed to write {:?}: {}", path, e);
+    }
+}
+
+/// Called at startup: if the previous run handed this version to the
+/// installer, the update went through.
+pub fn confirm_applied_update(app: &tauri::AppHandle) {
+    let current = app.package_info().version.to_string();
+    if read_update_state(app).installing_version.as_deref() == Some(current.as_str()) {
+        info!("✅ Update to {} applied successfully", current);
+        modify_update_state(app, |file| {
+            file.installing_version = None;
+            file.last_applied = Some(unix_millis());
+            file.last_applied_version = Some(current);
+        });
+    }
+}
+
+pub fn read_history(app: &tauri::AppHandle) -> UpdateHistory {
+    let file = read_update_state(app);
+    UpdateHistory {
+        last_check: file.last_check,
+        last_applied: file.last_applied,
+        last_applied_version: file.last_applied_version,
+        next_check: file.next_check,
+        current_version: app.package_info().version.to_string(),
+    }
+}
+
+/// When updates were last checked and applied, so IT can spot terminals
+/// stuck on an old build.
+#[tauri::command]
+pub fn update_history(app_handle: tauri::AppHandle) -> UpdateHistory {
+    read_history(&app_handle)
+}
+
+/// Abort the update download in progress. The `.part` file is removed and
+/// `update-cancelled` emitted once the download loop has stopped. Fails
+/// once the installer has started.
+#[tauri::command]
+pub fn cancel_update(update_state: tauri::State<'_, UpdateState>) -> Result<(), AppError> {
+    if update_state.installing.load(Ordering::SeqCst) {
+        return Err(AppError::Conflict(
+            "The update is already being installed and can't be cancelled.".to_string(),
+        ));
+    }
+    if !update_state.downloading.load(Ordering::SeqCst) {
+        return Err(AppError::Conflict("No update download is in progress.".to_string()));
+    }
+    info!("🛑 Update download cancellation requested");
+    update_state.cancel.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Check a side-loaded update artifact against the updater `pubkey`, for
+/// air-gapped installs. The signature is read from `<path>.sig`, as written
+/// by the Tauri bundler.
+#[tauri::command]
+pub async fn verify_update_artifact(app_handle: tauri::AppHandle, path: String) -> Result<(), AppError> {
+    let pubkey = updater_pubkey(&app_handle).map_err(AppError::Updater)?;
+    let artifact = PathBuf::from(&path);
+    let sig_path = PathBuf::from(format!("{}.sig", path));
+
+    let result = tauri::async_runtime::spawn_blocking(move || -> Result<(), AppError> {
+        let data = fs::read(&artifact)
+            .map_err(|e| AppError::from(e).with_details(serde_json::json!({ "path": artifact })))?;
+        let signature = fs::read_to_string(&sig_path).map_err(|e| {
+            AppError::NotFound(format!("Signature {:?} could not be read: {}", sig_path, e))
+        })?;
+        verify_package(&data, signature.trim(), &pubkey).map_err(AppError::Validation)
+    })
+    .await
+    .map_err(|e| format!("Verification task failed: {}", e))?;
+
+    match &result {
+        Ok(()) => info!("🔏 Update artifact {} is authentic", path),
+        Err(e) => warn!("⚠️ Update artifact {} failed verification: {}", path, e),
+    }
+    result
+}
+
+async fn apply_update(
+    app_handle: tauri::AppHandle,
+    update_state: tauri::State<'_, UpdateState>,
+) -> Result<(), AppError> {
+    let pending = update_state
+        .pending
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| AppError::Conflict("No downloaded update is ready to apply.".to_string()))?;
+
+    info!("=================================================");
+    info!("📦 Applying update {} and restarting", pending.update.version);
+    info!("=================================================");
+    crate::audit_log::record(
+        &app_handle,
+        "update_applied",
+        serde_json::json!({ "version": pending.update.version }),
+    );
+
+    let version = pending.update.version.clone();
+    let app = app_handle.clone();
+    let stopped = tauri::async_runtime::spawn_blocking(move || {
+        backend::stop_backend(&app.state::<backend::BackendState>())
+    })
+    .await
+    .map_err(|e| format!("Backend shutdown failed: {}", e))
+    .and_then(|result| result);
+    if let Err(message) = stopped {
+        emit_error(&app_handle, Some(&version), &message);
+        return Err(AppError::Backend(message));
+    }
+
+    modify_update_state(&app_handle, |file| file.installing_version = Some(version.clone()));
+
+    update_state.installing.store(true, Ordering::SeqCst);
+    if let Err(e) = pending.update.install(&pending.bytes) {
+        update_state.installing.store(false, Ordering::SeqCst);
+        modify_update_state(&app_handle, |file| file.installing_version = None);
+        error!("❌ Failed to install update: {}", e);
+        let message = format!("Failed to install update: {}", e);
+        emit_error(&app_handle, Some(&version), &message);
+        return Err(AppError::Updater(message));
+    }
+    emit_finished(&app_handle, &version);
+
+    let (_, pkg) = package_paths(&app_handle.state::<AppPaths>().root, &pending.update.version);
+    let _ = fs::remove_file(pkg);
+    Ok(())
+}
+
+/// Stop the backend, install the downloaded update and relaunch the app.
+#[tauri::command]
+pub async fn apply_update_and_restart(
+    app_handle: tauri::AppHandle,
+    update_state: tauri::State<'_, UpdateState>,
+) -> Result<(), AppError> {
+    let result = apply_update(app_handle.clone(), update_state).await;
+    crate::audit_log::privileged(&app_handle, "apply_update_and_restart", result)?;
+    info!("✅ Update installed, relaunching...");
+    app_handle.restart();
+}
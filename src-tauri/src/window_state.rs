@@ -0,0 +1,197 @@
+//! Persist and restore the main window's geometry in `window-state.json`.
+//!
+//! Geometry is stored in physical pixels together with the scale factor it
+//! was captured at. On restore the saved rect is checked against the
+//! monitors that are connected now: if it no longer overlaps any of them
+//! (e.g. the second screen was unplugged) the window is re-centred on the
+//! primary monitor, and the size is rescaled when the target monitor has a
+//! different DPI scale so the window keeps the same apparent size.
+//!
+//! Moves and resizes go through `schedule_save`, which coalesces a drag
+//! into one write once the geometry has been still for `SAVE_DEBOUNCE`;
+//! `save` writes immediately and is used on close.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::window::Monitor;
+use tauri::{Manager, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+use crate::paths::{self, AppPaths};
+
+const WINDOW_STATE_FILE: &str = "window-state.json";
+/// Minimum overlap (px) with a monitor for the saved position to be kept.
+const MIN_VISIBLE_PX: i32 = 64;
+/// Quiet period after the last move/resize before it is written.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+pub struct WindowStateSaver {
+    /// Time of the latest geometry change not yet written.
+    changed_at: Mutex<Option<Instant>>,
+    wake: Condvar,
+    /// Set once the writer thread is running.
+    started: AtomicBool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub maximized: bool,
+}
+
+fn state_path(window: &WebviewWindow) -> std::path::PathBuf {
+    window.state::<AppPaths>().root.join(WINDOW_STATE_FILE)
+}
+
+fn load(window: &WebviewWindow) -> Option<WindowState> {
+    paths::read_config_or_default(&state_path(window))
+}
+
+/// Capture the current geometry. Position/size are taken from the
+/// un-maximized window only, so restoring doesn't produce a giant window.
+pub fn capture(window: &WebviewWindow) -> Option<WindowState> {
+    let maximized = window.is_maximized().unwrap_or(false);
+    let previous = load(window);
+
+    let (x, y, width, height) = match (maximized, &previous) {
+        (true, Some(prev)) => (prev.x, prev.y, prev.width, prev.height),
+        _ => {
+            let pos = window.outer_position().ok()?;
+            let size = window.inner_size().ok()?;
+            (pos.x, pos.y, size.width, size.height)
+        }
+    };
+
+    Some(WindowState {
+        x,
+        y,
+        width,
+        height,
+        scale_factor: window.scale_factor().unwrap_or(1.0),
+        maximized,
+    })
+}
+
+/// Write the geometry now, dropping any pending debounced save.
+pub fn save(window: &WebviewWindow) {
+    window.state::<WindowStateSaver>().changed_at.lock().unwrap().take();
+    write(window);
+}
+
+/// Note a geometry change; it is written once no further change arrives
+/// for `SAVE_DEBOUNCE`.
+pub fn schedule_save(window: &WebviewWindow) {
+    let saver = window.state::<WindowStateSaver>();
+    *saver.changed_at.lock().unwrap() = Some(Instant::now());
+    saver.wake.notify_one();
+
+    if !saver.started.swap(true, Ordering::SeqCst) {
+        let window = window.clone();
+        thread::spawn(move || debounced_writer(window));
+    }
+}
+
+fn debounced_writer(window: WebviewWindow) {
+    let saver = window.state::<WindowStateSaver>();
+    let mut changed_at = saver.changed_at.lock().unwrap();
+    loop {
+        match *changed_at {
+            None => changed_at = saver.wake.wait(changed_at).unwrap(),
+            Some(at) if at.elapsed() < SAVE_DEBOUNCE => {
+                let remaining = SAVE_DEBOUNCE.saturating_sub(at.elapsed());
+                changed_at = saver.wake.wait_timeout(changed_at, remaining).unwrap().0;
+            }
+            Some(_) => {
+                *changed_at = None;
+                drop(changed_at);
+                write(&window);
+                changed_at = saver.changed_at.lock().unwrap();
+            }
+        }
+    }
+}
+
+fn write(window: &WebviewWindow) {
+    let Some(state) = capture(window) else { return };
+    let path = state_path(window);
+    match serde_json::to_string_pretty(&state) {
+        Ok(text) => {
+            if let Err(e) = paths::write_atomic(&path, text.as_bytes()) {
+                warn!("⚠️ Failed to save window state to {:?}: {}", path, e);
+            }
+        }
+        Err(e) => warn!("⚠️ Failed to serialize window state: {}", e),
+    }
+}
+
+fn overlap(state: &WindowState, monitor: &Monitor) -> i64 {
+    let m_pos = monitor.position();
+    let m_size = monitor.size();
+    let left = state.x.max(m_pos.x);
+    let top = state.y.max(m_pos.y);
+    let right = (state.x + state.width as i32).min(m_pos.x + m_size.width as i32);
+    let bottom = (state.y + state.height as i32).min(m_pos.y + m_size.height as i32);
+    if right - left < MIN_VISIBLE_PX || bottom - top < MIN_VISIBLE_PX {
+        0
+    } else {
+        (right - left) as i64 * (bottom - top) as i64
+    }
+}
+
+/// Restore saved geometry onto `window`. Returns whether the window should
+/// be maximized according to the saved state (`None` if nothing was saved).
+pub fn restore(window: &WebviewWindow) -> Option<bool> {
+    let mut state = load(window)?;
+    let monitors = window.available_monitors().unwrap_or_default();
+
+    let target = monitors
+        .iter()
+        .map(|m| (overlap(&state, m), m))
+        .filter(|(area, _)| *area > 0)
+        .max_by_key(|(area, _)| *area)
+        .map(|(_, m)| m.clone());
+
+    let (monitor, recentre) = match target {
+        Some(m) => (Some(m), false),
+        None => (window.primary_monitor().ok().flatten(), true),
+    };
+
+    if let Some(monitor) = &monitor {
+        // Keep the same apparent size across DPI changes.
+        let ratio = monitor.scale_factor() / state.scale_factor.max(0.1);
+        if (ratio - 1.0).abs() > f64::EPSILON {
+            info!(
+                "🖥️ Window state: DPI scale changed {} → {}, rescaling size",
+                state.scale_factor,
+                monitor.scale_factor()
+            );
+            state.width = (state.width as f64 * ratio).round() as u32;
+            state.height = (state.height as f64 * ratio).round() as u32;
+        }
+
+        // Never open larger than the monitor.
+        state.width = state.width.min(monitor.size().width);
+        state.height = state.height.min(monitor.size().height);
+
+        if recentre {
+            let m_pos = monitor.position();
+            let m_size = monitor.size();
+            state.x = m_pos.x + (m_size.width as i32 - state.width as i32) / 2;
+            state.y = m_pos.y + (m_size.height as i32 - state.height as i32) / 2;
+            info!("🖥️ Window state: saved monitor is gone, re-centred on the primary monitor");
+        }
+    }
+
+    let _ = window.set_size(PhysicalSize::new(state.width, state.height));
+    let _ = window.set_position(PhysicalPosition::new(state.x, state.y));
+    Some(state.maximized)
+}
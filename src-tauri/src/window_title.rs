@@ -0,0 +1,77 @@
+//! Main window title showing which instance this is.
+//!
+//! With several profiles, a staging build or a demo running side by side,
+//! the title is the quickest way to tell them apart:
+//! `Siri Billing — <profile> [<tags>]`. The profile is shown unless it is
+//! the default one. Tags are the environment (`window.env_tag`, or
+//! `SIRI_ENV_TAG` at build time), `DEMO`, `MAINTENANCE` and `SAFE MODE`.
+//! With none of these the title stays the plain one from `tauri.conf.json`.
+//!
+//! `refresh` is called at startup and whenever demo, maintenance or safe
+//! mode changes; switching profile restarts the app, which sets it again.
+
+use log::{debug, warn};
+use tauri::Manager;
+
+use crate::paths::AppPaths;
+use crate::profiles::DEFAULT_PROFILE;
+use crate::settings::SettingsState;
+
+const TITLE_PREFIX: &str = "Siri Billing";
+
+/// Title the main window is configured with.
+fn plain_title(app: &tauri::AppHandle) -> String {
+    let windows = &app.config().app.windows;
+    windows
+        .iter()
+        .find(|w| w.label == "main")
+        .or(windows.first())
+        .map(|w| w.title.clone())
+        .unwrap_or_else(|| TITLE_PREFIX.to_string())
+}
+
+fn env_tag(app: &tauri::AppHandle) -> Option<String> {
+    app.state::<SettingsState>()
+        .get()
+        .window
+        .env_tag
+        .or_else(|| option_env!("SIRI_ENV_TAG").map(str::to_string))
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+}
+
+fn title(app: &tauri::AppHandle) -> String {
+    let profile = app.state::<AppPaths>().profile.clone();
+    let mut tags: Vec<String> = env_tag(app).into_iter().collect();
+    if crate::demo::is_active(app) {
+        tags.push("DEMO".to_string());
+    }
+    if crate::maintenance::is_active(app) {
+        tags.push("MAINTENANCE".to_string());
+    }
+    if crate::safe_mode::is_active(app) {
+        tags.push("SAFE MODE".to_string());
+    }
+
+    if profile == DEFAULT_PROFILE && tags.is_empty() {
+        return plain_title(app);
+    }
+    let mut title = TITLE_PREFIX.to_string();
+    if profile != DEFAULT_PROFILE {
+        title.push_str(&format!(" — {}", profile));
+    }
+    if !tags.is_empty() {
+        title.push_str(&format!(" [{}]", tags.join(" | ")));
+    }
+    title
+}
+
+/// Set the main window's title from the current profile and modes.
+pub fn refresh(app: &tauri::AppHandle) {
+    let Some(window) = app.get_webview_window("main") else { return };
+    let title = title(app);
+    debug!("🪟 Window title: {}", title);
+    if let Err(e) = window.set_title(&title) {
+        warn!("⚠️ Failed to set window title: {}", e);
+    }
+}
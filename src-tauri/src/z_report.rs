@@ -0,0 +1,163 @@
+//! End-of-day Z-report printing.
+//!
+//! Receipts print as HTML through `print_html_native` (there is no raw
+//! ESC/POS transport in the shell), so the report is laid out as an 80mm
+//! thermal-width HTML page and sent through the same WebView2 print path.
+
+use chrono::Local;
+use log::info;
+use serde::Deserialize;
+
+use crate::error::AppError;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PaymentTotal {
+    /// e.g. `Cash`, `Card`, `UPI`.
+    pub method: String,
+    pub count: u32,
+    pub amount: f64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CashCount {
+    pub expected: f64,
+    pub counted: f64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ZReportData {
+    /// Business day the report covers, as shown on paper.
+    pub business_date: String,
+    pub store_name: Option<String>,
+    pub terminal: Option<String>,
+    pub cashier: Option<String>,
+    pub bill_count: u32,
+    pub gross_total: f64,
+    pub tax_total: f64,
+    pub refund_count: u32,
+    pub refund_total: f64,
+    pub net_total: f64,
+    pub payments: Vec<PaymentTotal>,
+    pub cash_count: Option<CashCount>,
+    /// Symbol printed before amounts; defaults to `₹`.
+    pub currency: Option<String>,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn row(label: &str, value: &str) -> String {
+    format!("<tr><td>{}</td><td class=\"r\">{}</td></tr>", escape(label), escape(value))
+}
+
+fn render_html(report: &ZReportData, reprint: bool, printed_at: &str) -> String {
+    let cur = report.currency.as_deref().unwrap_or("₹");
+    let money = |v: f64| format!("{}{:.2}", cur, v);
+
+    let mut header = String::new();
+    if let Some(store) = &report.store_name {
+        header.push_str(&format!("<div class=\"c b big\">{}</div>", escape(store)));
+    }
+    header.push_str("<div class=\"c b\">Z-REPORT</div>");
+    if reprint {
+        header.push_str("<div class=\"c b\">** DUPLICATE **</div>");
+    }
+
+    let mut info = vec![row("Business date", &report.business_date)];
+    if let Some(terminal) = &report.terminal {
+        info.push(row("Terminal", terminal));
+    }
+    if let Some(cashier) = &report.cashier {
+        info.push(row("Cashier", cashier));
+    }
+    info.push(row("Printed", printed_at));
+
+    let totals = [
+        row("Bills", &report.bill_count.to_string()),
+        row("Gross sales", &money(report.gross_total)),
+        row("Tax", &money(report.tax_total)),
+        row(&format!("Refunds ({})", report.refund_count), &money(-report.refund_total)),
+        format!("<tr class=\"b\"><td>NET</td><td class=\"r\">{}</td></tr>", escape(&money(report.net_total))),
+    ];
+
+    let payments: Vec<String> = report
+        .payments
+        .iter()
+        .map(|p| row(&format!("{} ({})", p.method, p.count), &money(p.amount)))
+        .collect();
+
+    let cash = report
+        .cash_count
+        .as_ref()
+        .map(|c| {
+            [
+                "<hr>".to_string(),
+                "<div class=\"b\">CASH COUNT</div><table>".to_string(),
+                row("Expected", &money(c.expected)),
+                row("Counted", &money(c.counted)),
+                row("Difference", &money(c.counted - c.expected)),
+                "</table>".to_string(),
+            ]
+            .concat()
+        })
+        .unwrap_or_default();
+
+    format!(
+        r#"<!DOCTYPE html><html><head><meta charset="utf-8"><style>
+@page {{ size: 80mm auto; margin: 0; }}
+body {{ width: 72mm; margin: 4mm; font-family: monospace; font-size: 12px; }}
+table {{ width: 100%; border-collapse: collapse; }}
+.r {{ text-align: right; }} .c {{ text-align: center; }} .b {{ font-weight: bold; }}
+.big {{ font-size: 15px; }} hr {{ border: 0; border-top: 1px dashed #000; }}
+</style></head><body>
+{header}<hr><table>{info}</table><hr>
+<div class="b">TOTALS</div><table>{totals}</table><hr>
+<div class="b">PAYMENTS</div><table>{payments}</table>
+{cash}<hr><div class="c">End of report</div>
+</body></html>"#,
+        header = header,
+        info = info.concat(),
+        totals = totals.concat(),
+        payments = payments.concat(),
+        cash = cash,
+    )
+}
+
+/// Print a Z-report on `printer` (or the saved default) and return the
+/// printed timestamp (RFC 3339) for the backend to record. `reprint` marks
+/// the paper copy as a duplicate.
+#[tauri::command]
+pub async fn print_z_report(
+    app_handle: tauri::AppHandle,
+    report: ZReportData,
+    printer: Option<String>,
+    reprint: Option<bool>,
+) -> Result<String, AppError> {
+    // The printed timestamp is only useful if the backend can record it.
+    crate::backend::ensure_ready(&app_handle, "print_z_report")?;
+    let reprint = reprint.unwrap_or(false);
+    let printed_at = Local::now().to_rfc3339();
+    info!(
+        "🧾 Printing Z-report for {}{}",
+        report.business_date,
+        if reprint { " (duplicate)" } else { "" }
+    );
+
+    let html = render_html(&report, reprint, &printed_at);
+    crate::print_html_native(app_handle.clone(), html, printer.unwrap_or_default(), 1, None).await?;
+    crate::audit_log::record(
+        &app_handle,
+        "z_report_printed",
+        serde_json::json!({
+            "business_date": report.business_date,
+            "net_total": report.net_total,
+            "reprint": reprint,
+            "printed_at": printed_at,
+        }),
+    );
+    Ok(printed_at)
+}